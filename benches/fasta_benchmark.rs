@@ -1,11 +1,10 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use biocirc::sequence::BioType;
 use biocirc::FastaReader;
-
+use criterion::{criterion_group, criterion_main, Criterion};
 
 fn read_fasta() {
     let fastareader = FastaReader {
-        file: "tests\\data\\example.fasta".to_string(),
+        file: "tests/data/example.fasta".to_string(),
         biotype: BioType::Dna,
     };
     let sequences = fastareader.read().unwrap();
@@ -13,8 +12,8 @@ fn read_fasta() {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("fasta open", |b| b.iter(|| read_fasta()));
+    c.bench_function("fasta open", |b| b.iter(read_fasta));
 }
 
 criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -0,0 +1,162 @@
+use super::sequence::Sequence;
+
+use std::collections::{HashMap, VecDeque};
+
+// Aho-Corasick 自动机：一次扫描同时查找多个模式
+// 时间复杂度为 O(n + m + 匹配数)，与模式的个数无关
+
+/// 自动机中的一个结点
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    /// 失配时跳转的结点
+    fail: usize,
+    /// 以该结点结尾的模式编号，包括沿失配链可以到达的模式
+    outputs: Vec<usize>,
+}
+
+/// 多模式匹配自动机
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    /// 每个模式的字符数
+    lengths: Vec<usize>,
+    case_insensitive: bool,
+}
+
+impl AhoCorasick {
+    /// 由一组模式构建区分大小写的自动机，空模式会被忽略
+    pub fn build(patterns: &[&str]) -> AhoCorasick {
+        AhoCorasick::build_with(patterns, false)
+    }
+
+    /// 由一组模式构建自动机，`case_insensitive` 为 true 时匹配不区分大小写
+    pub fn build_with(patterns: &[&str], case_insensitive: bool) -> AhoCorasick {
+        let mut automaton = AhoCorasick {
+            nodes: vec![Node::default()],
+            lengths: Vec::with_capacity(patterns.len()),
+            case_insensitive,
+        };
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut node = 0;
+            let mut length = 0;
+            for c in pattern.chars() {
+                let c = automaton.normalize(c);
+                node = match automaton.nodes[node].children.get(&c) {
+                    Some(&child) => child,
+                    None => {
+                        automaton.nodes.push(Node::default());
+                        let child = automaton.nodes.len() - 1;
+                        automaton.nodes[node].children.insert(c, child);
+                        child
+                    }
+                };
+                length += 1;
+            }
+            automaton.lengths.push(length);
+            if length > 0 {
+                automaton.nodes[node].outputs.push(index);
+            }
+        }
+
+        // 按层次遍历计算失配指针
+        let mut queue: VecDeque<usize> = automaton.nodes[0].children.values().cloned().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(char, usize)> = automaton.nodes[node]
+                .children
+                .iter()
+                .map(|(&c, &child)| (c, child))
+                .collect();
+            for (c, child) in children {
+                let mut fail = automaton.nodes[node].fail;
+                let target = loop {
+                    if let Some(&next) = automaton.nodes[fail].children.get(&c) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = automaton.nodes[fail].fail;
+                };
+                automaton.nodes[child].fail = target;
+                let inherited = automaton.nodes[target].outputs.clone();
+                automaton.nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        automaton
+    }
+
+    fn normalize(&self, c: char) -> char {
+        if self.case_insensitive {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+
+    /// 模式的个数
+    pub fn pattern_count(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// 查找所有模式的所有出现位置，允许重叠
+    /// 返回 (位置, 长度, 模式编号)，按位置、长度与模式编号排序
+    pub fn search(&self, seq: &Sequence) -> Vec<(usize, usize, usize)> {
+        let mut matches = Vec::new();
+        let mut node = 0;
+        for (i, c) in seq.seq.chars().enumerate() {
+            let c = self.normalize(c);
+            loop {
+                if let Some(&next) = self.nodes[node].children.get(&c) {
+                    node = next;
+                    break;
+                }
+                if node == 0 {
+                    break;
+                }
+                node = self.nodes[node].fail;
+            }
+            for &index in self.nodes[node].outputs.iter() {
+                let length = self.lengths[index];
+                matches.push((i + 1 - length, length, index));
+            }
+        }
+        matches.sort_unstable();
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn multi_pattern_search() {
+        let automaton = AhoCorasick::build(&["HE", "SHE", "HIS", "HERS", ""]);
+        assert_eq!(automaton.pattern_count(), 5);
+        let text = Sequence::new(BioType::Protein, String::from("USHERS"));
+        assert_eq!(
+            automaton.search(&text),
+            vec![(1, 3, 1), (2, 2, 0), (2, 4, 3)]
+        );
+        let lower = Sequence::new(BioType::Protein, String::from("ushers"));
+        assert!(automaton.search(&lower).is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_search() {
+        let automaton = AhoCorasick::build_with(&["gaattc", "AATT", "TT"], true);
+        let dna = Sequence::new(BioType::Dna, String::from("ccGAATTCaatt"));
+        let matches = automaton.search(&dna);
+        let expected: Vec<(usize, usize, usize)> =
+            vec![(2, 6, 0), (3, 4, 1), (5, 2, 2), (8, 4, 1), (10, 2, 2)];
+        assert_eq!(matches, expected);
+        // 与逐个模式查找的结果一致
+        for &(position, length, index) in matches.iter() {
+            let found = dna.seq[position..position + length].to_ascii_uppercase();
+            assert_eq!(found, ["GAATTC", "AATT", "TT"][index]);
+        }
+    }
+}
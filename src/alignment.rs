@@ -0,0 +1,944 @@
+use super::distance::levenshtein;
+use super::sequence::{AmbiguousBase, BioType, Sequence};
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::{error, fmt};
+
+// 序列比对
+// 得分直接相加，因此错配与空位的罚分应为负数，例如 match 1、mismatch -1、gap -1
+// 比较时不区分大小写
+
+/// 两条序列的比对结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    /// 插入空位 `-` 后的查询序列
+    pub aligned_query: String,
+    /// 插入空位 `-` 后的目标序列
+    pub aligned_target: String,
+    pub score: i32,
+    /// M 为比对上的位置，I 为目标序列中的空位，D 为查询序列中的空位
+    pub cigar: String,
+    /// 比对区域在查询序列上的左闭右开区间，全局比对时为整条序列
+    pub query_start: usize,
+    pub query_end: usize,
+    /// 比对区域在目标序列上的左闭右开区间
+    pub target_start: usize,
+    pub target_end: usize,
+}
+
+impl Alignment {
+    /// 比对结果中两条序列相同的位置数
+    pub fn identity(&self) -> usize {
+        self.aligned_query
+            .chars()
+            .zip(self.aligned_target.chars())
+            .filter(|(q, t)| *q != '-' && q.eq_ignore_ascii_case(t))
+            .count()
+    }
+}
+
+/// 以经典的三行形式显示：查询序列、匹配标记 (`|` 相同，`.` 不同，空格为空位)、目标序列
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let markers: String = self
+            .aligned_query
+            .chars()
+            .zip(self.aligned_target.chars())
+            .map(|(q, t)| match (q, t) {
+                ('-', _) | (_, '-') => ' ',
+                _ if q.eq_ignore_ascii_case(&t) => '|',
+                _ => '.',
+            })
+            .collect();
+        write!(
+            f,
+            "{}\n{}\n{}",
+            self.aligned_query, markers, self.aligned_target
+        )
+    }
+}
+
+/// 多序列比对的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlignmentError {
+    /// 没有输入序列
+    Empty,
+    /// 序列类型与第一条序列不同
+    BiotypeMismatch {
+        index: usize,
+        expected: BioType,
+        found: BioType,
+    },
+}
+
+impl fmt::Display for AlignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlignmentError::Empty => write!(f, "至少需要一条序列才能进行多序列比对"),
+            AlignmentError::BiotypeMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "第 {} 条序列的类型为 {}，与第一条序列的 {} 不一致",
+                index + 1,
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+impl error::Error for AlignmentError {}
+
+/// 回溯方向
+#[derive(Clone, Copy, PartialEq)]
+enum Trace {
+    Diagonal,
+    Up,
+    Left,
+}
+
+/// Needleman-Wunsch 全局比对，使用线性空位罚分 `gap_open`
+/// 蛋白质序列同样按是否相同计分
+pub fn global_align(
+    query: &Sequence,
+    target: &Sequence,
+    match_score: i32,
+    mismatch: i32,
+    gap_open: i32,
+) -> Alignment {
+    let q: Vec<char> = query.seq.chars().collect();
+    let t: Vec<char> = target.seq.chars().collect();
+    let (n, m) = (q.len(), t.len());
+    let score = |a: char, b: char| {
+        if a.eq_ignore_ascii_case(&b) {
+            match_score
+        } else {
+            mismatch
+        }
+    };
+
+    let mut matrix = vec![vec![0i32; m + 1]; n + 1];
+    let mut trace = vec![vec![Trace::Diagonal; m + 1]; n + 1];
+    for i in 1..=n {
+        matrix[i][0] = matrix[i - 1][0] + gap_open;
+        trace[i][0] = Trace::Up;
+    }
+    for j in 1..=m {
+        matrix[0][j] = matrix[0][j - 1] + gap_open;
+        trace[0][j] = Trace::Left;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let diagonal = matrix[i - 1][j - 1] + score(q[i - 1], t[j - 1]);
+            let up = matrix[i - 1][j] + gap_open;
+            let left = matrix[i][j - 1] + gap_open;
+            let (best, direction) = if diagonal >= up && diagonal >= left {
+                (diagonal, Trace::Diagonal)
+            } else if up >= left {
+                (up, Trace::Up)
+            } else {
+                (left, Trace::Left)
+            };
+            matrix[i][j] = best;
+            trace[i][j] = direction;
+        }
+    }
+
+    let (mut i, mut j) = (n, m);
+    let mut columns: Vec<(char, char)> = Vec::with_capacity(n + m);
+    while i > 0 || j > 0 {
+        match trace[i][j] {
+            Trace::Diagonal => {
+                i -= 1;
+                j -= 1;
+                columns.push((q[i], t[j]));
+            }
+            Trace::Up => {
+                i -= 1;
+                columns.push((q[i], '-'));
+            }
+            Trace::Left => {
+                j -= 1;
+                columns.push(('-', t[j]));
+            }
+        }
+    }
+    columns.reverse();
+    build_alignment(&columns, matrix[n][m], (0, n), (0, m))
+}
+
+/// Smith-Waterman 局部比对，使用线性空位罚分 `gap_open`
+/// 从得分最高的单元格开始回溯，直到得分降为 0
+/// 比对区域在原序列中的位置记录在 `query_start` 等字段中
+pub fn local_align(
+    query: &Sequence,
+    target: &Sequence,
+    match_score: i32,
+    mismatch: i32,
+    gap_open: i32,
+) -> Alignment {
+    let q: Vec<char> = query.seq.chars().collect();
+    let t: Vec<char> = target.seq.chars().collect();
+    let (n, m) = (q.len(), t.len());
+    let score = |a: char, b: char| {
+        if a.eq_ignore_ascii_case(&b) {
+            match_score
+        } else {
+            mismatch
+        }
+    };
+
+    let mut matrix = vec![vec![0i32; m + 1]; n + 1];
+    let mut trace = vec![vec![Trace::Diagonal; m + 1]; n + 1];
+    let (mut best, mut best_i, mut best_j) = (0, 0, 0);
+    for i in 1..=n {
+        for j in 1..=m {
+            let diagonal = matrix[i - 1][j - 1] + score(q[i - 1], t[j - 1]);
+            let up = matrix[i - 1][j] + gap_open;
+            let left = matrix[i][j - 1] + gap_open;
+            let (cell, direction) = if diagonal >= up && diagonal >= left {
+                (diagonal, Trace::Diagonal)
+            } else if up >= left {
+                (up, Trace::Up)
+            } else {
+                (left, Trace::Left)
+            };
+            matrix[i][j] = cell.max(0);
+            trace[i][j] = direction;
+            if matrix[i][j] > best {
+                (best, best_i, best_j) = (matrix[i][j], i, j);
+            }
+        }
+    }
+
+    let (mut i, mut j) = (best_i, best_j);
+    let mut columns: Vec<(char, char)> = Vec::new();
+    while i > 0 && j > 0 && matrix[i][j] > 0 {
+        match trace[i][j] {
+            Trace::Diagonal => {
+                i -= 1;
+                j -= 1;
+                columns.push((q[i], t[j]));
+            }
+            Trace::Up => {
+                i -= 1;
+                columns.push((q[i], '-'));
+            }
+            Trace::Left => {
+                j -= 1;
+                columns.push(('-', t[j]));
+            }
+        }
+    }
+    columns.reverse();
+    build_alignment(&columns, best, (i, best_i), (j, best_j))
+}
+
+/// BLOSUM62 中氨基酸的顺序，B、Z、X 为简并代码，`*` 为终止符
+const BLOSUM62_ORDER: &str = "ARNDCQEGHILKMFPSTWYVBZX*";
+
+/// BLOSUM62 替换矩阵 (Henikoff & Henikoff 1992)，行列顺序见 `BLOSUM62_ORDER`
+#[rustfmt::skip]
+const BLOSUM62: [[i32; 24]; 24] = [
+    [ 4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0, -2, -1,  0, -4], // A
+    [-1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3, -1,  0, -1, -4], // R
+    [-2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3,  3,  0, -1, -4], // N
+    [-2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3,  4,  1, -1, -4], // D
+    [ 0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1, -3, -3, -2, -4], // C
+    [-1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2,  0,  3, -1, -4], // Q
+    [-1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4], // E
+    [ 0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3, -1, -2, -1, -4], // G
+    [-2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3,  0,  0, -1, -4], // H
+    [-1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3, -3, -3, -1, -4], // I
+    [-1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1, -4, -3, -1, -4], // L
+    [-1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2,  0,  1, -1, -4], // K
+    [-1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1, -3, -1, -1, -4], // M
+    [-2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1, -3, -3, -1, -4], // F
+    [-1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2, -2, -1, -2, -4], // P
+    [ 1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2,  0,  0,  0, -4], // S
+    [ 0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0, -1, -1,  0, -4], // T
+    [-3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3, -4, -3, -2, -4], // W
+    [-2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1, -3, -2, -1, -4], // Y
+    [ 0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4, -3, -2, -1, -4], // V
+    [-2, -1,  3,  4, -3,  0,  1, -1,  0, -3, -4,  0, -3, -3, -2,  0, -1, -4, -3, -3,  4,  1, -1, -4], // B
+    [-1,  0,  0,  1, -3,  3,  4, -2,  0, -3, -3,  1, -1, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4], // Z
+    [ 0, -1, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2,  0,  0, -2, -1, -1, -1, -1, -1, -4], // X
+    [-4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1], // *
+];
+
+/// PAM250 替换矩阵 (Dayhoff et al. 1978)，适合亲缘关系较远的蛋白质，行列顺序与 BLOSUM62 相同
+#[rustfmt::skip]
+const PAM250: [[i32; 24]; 24] = [
+    [ 2, -2,  0,  0, -2,  0,  0,  1, -1, -1, -2, -1, -1, -3,  1,  1,  1, -6, -3,  0,  0,  0,  0, -8], // A
+    [-2,  6,  0, -1, -4,  1, -1, -3,  2, -2, -3,  3,  0, -4,  0,  0, -1,  2, -4, -2, -1,  0, -1, -8], // R
+    [ 0,  0,  2,  2, -4,  1,  1,  0,  2, -2, -3,  1, -2, -3,  0,  1,  0, -4, -2, -2,  2,  1,  0, -8], // N
+    [ 0, -1,  2,  4, -5,  2,  3,  1,  1, -2, -4,  0, -3, -6, -1,  0,  0, -7, -4, -2,  3,  3, -1, -8], // D
+    [-2, -4, -4, -5, 12, -5, -5, -3, -3, -2, -6, -5, -5, -4, -3,  0, -2, -8,  0, -2, -4, -5, -3, -8], // C
+    [ 0,  1,  1,  2, -5,  4,  2, -1,  3, -2, -2,  1, -1, -5,  0, -1, -1, -5, -4, -2,  1,  3, -1, -8], // Q
+    [ 0, -1,  1,  3, -5,  2,  4,  0,  1, -2, -3,  0, -2, -5, -1,  0,  0, -7, -4, -2,  3,  3, -1, -8], // E
+    [ 1, -3,  0,  1, -3, -1,  0,  5, -2, -3, -4, -2, -3, -5,  0,  1,  0, -7, -5, -1,  0,  0, -1, -8], // G
+    [-1,  2,  2,  1, -3,  3,  1, -2,  6, -2, -2,  0, -2, -2,  0, -1, -1, -3,  0, -2,  1,  2, -1, -8], // H
+    [-1, -2, -2, -2, -2, -2, -2, -3, -2,  5,  2, -2,  2,  1, -2, -1,  0, -5, -1,  4, -2, -2, -1, -8], // I
+    [-2, -3, -3, -4, -6, -2, -3, -4, -2,  2,  6, -3,  4,  2, -3, -3, -2, -2, -1,  2, -3, -3, -1, -8], // L
+    [-1,  3,  1,  0, -5,  1,  0, -2,  0, -2, -3,  5,  0, -5, -1,  0,  0, -3, -4, -2,  1,  0, -1, -8], // K
+    [-1,  0, -2, -3, -5, -1, -2, -3, -2,  2,  4,  0,  6,  0, -2, -2, -1, -4, -2,  2, -2, -2, -1, -8], // M
+    [-3, -4, -3, -6, -4, -5, -5, -5, -2,  1,  2, -5,  0,  9, -5, -3, -3,  0,  7, -1, -4, -5, -2, -8], // F
+    [ 1,  0,  0, -1, -3,  0, -1,  0,  0, -2, -3, -1, -2, -5,  6,  1,  0, -6, -5, -1, -1,  0, -1, -8], // P
+    [ 1,  0,  1,  0,  0, -1,  0,  1, -1, -1, -3,  0, -2, -3,  1,  2,  1, -2, -3, -1,  0,  0,  0, -8], // S
+    [ 1, -1,  0,  0, -2, -1,  0,  0, -1,  0, -2,  0, -1, -3,  0,  1,  3, -5, -3,  0,  0, -1,  0, -8], // T
+    [-6,  2, -4, -7, -8, -5, -7, -7, -3, -5, -2, -3, -4,  0, -6, -2, -5, 17,  0, -6, -5, -6, -4, -8], // W
+    [-3, -4, -2, -4,  0, -4, -4, -5,  0, -1, -1, -4, -2,  7, -5, -3, -3,  0, 10, -2, -3, -4, -2, -8], // Y
+    [ 0, -2, -2, -2, -2, -2, -2, -1, -2,  4,  2, -2,  2, -1, -1, -1,  0, -6, -2,  4, -2, -2, -1, -8], // V
+    [ 0, -1,  2,  3, -4,  1,  3,  0,  1, -2, -3,  1, -2, -4, -1,  0,  0, -5, -3, -2,  3,  2, -1, -8], // B
+    [ 0,  0,  1,  3, -5,  3,  3,  0,  2, -2, -3,  0, -2, -5,  0,  0, -1, -6, -4, -2,  2,  3, -1, -8], // Z
+    [ 0, -1,  0, -1, -3, -1, -1, -1, -1, -1, -1, -1, -1, -2, -1,  0,  0, -4, -2, -1, -1, -1, -1, -8], // X
+    [-8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8,  1], // *
+];
+
+/// 氨基酸替换矩阵，查询时不区分大小写，表中没有的字符按 X 计分，矩阵中也没有 X 时为 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutionMatrix {
+    alphabet: Vec<char>,
+    scores: Vec<Vec<i32>>,
+}
+
+impl SubstitutionMatrix {
+    fn from_table(order: &str, table: &[[i32; 24]; 24]) -> Self {
+        SubstitutionMatrix {
+            alphabet: order.chars().collect(),
+            scores: table.iter().map(|row| row.to_vec()).collect(),
+        }
+    }
+
+    /// BLOSUM62 矩阵
+    pub fn blosum62() -> Self {
+        Self::from_table(BLOSUM62_ORDER, &BLOSUM62)
+    }
+
+    /// PAM250 矩阵
+    pub fn pam250() -> Self {
+        Self::from_table(BLOSUM62_ORDER, &PAM250)
+    }
+
+    /// 读取 AAindex (第 3 部分，替换矩阵) 格式的文件，只读取第一个条目
+    /// `M rows = ..., cols = ...` 行给出字母表，其后为下三角或完整的矩阵，小数得分四舍五入为整数
+    pub fn from_file(path: impl AsRef<Path>) -> Result<SubstitutionMatrix, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("无法读取 {}: {}", path.as_ref().display(), e))?;
+        Self::from_aaindex(&text)
+    }
+
+    fn from_aaindex(text: &str) -> Result<SubstitutionMatrix, String> {
+        let mut lines = text.lines();
+        let header = lines
+            .by_ref()
+            .find(|line| line.starts_with("M "))
+            .ok_or("没有找到 AAindex 矩阵的 M 行")?;
+        let (rows, cols) = header[1..]
+            .split_once(',')
+            .and_then(|(rows, cols)| {
+                let rows = rows.trim().strip_prefix("rows")?.trim().strip_prefix('=')?;
+                let cols = cols.trim().strip_prefix("cols")?.trim().strip_prefix('=')?;
+                Some((rows.trim(), cols.trim()))
+            })
+            .ok_or(format!("无法解析 AAindex 矩阵的字母表: {}", header))?;
+        if rows != cols {
+            return Err(format!("行与列的字母表不同: {} 与 {}", rows, cols));
+        }
+        let alphabet: Vec<char> = rows.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let values = lines
+            .take_while(|line| !line.starts_with("//"))
+            .flat_map(str::split_whitespace)
+            .map(|value| {
+                value
+                    .parse::<f64>()
+                    .map(|v| v.round() as i32)
+                    .map_err(|_| format!("无效的得分: {}", value))
+            })
+            .collect::<Result<Vec<i32>, String>>()?;
+
+        let n = alphabet.len();
+        let mut scores = vec![vec![0; n]; n];
+        if values.len() == n * (n + 1) / 2 {
+            let cells = (0..n).flat_map(|i| (0..=i).map(move |j| (i, j)));
+            for ((i, j), value) in cells.zip(values) {
+                scores[i][j] = value;
+                scores[j][i] = value;
+            }
+        } else if values.len() == n * n {
+            for (i, row) in values.chunks(n).enumerate() {
+                scores[i].copy_from_slice(row);
+            }
+        } else {
+            return Err(format!(
+                "{} 个字符的矩阵需要 {} 或 {} 个得分，实际为 {}",
+                n,
+                n * (n + 1) / 2,
+                n * n,
+                values.len()
+            ));
+        }
+        Ok(SubstitutionMatrix { alphabet, scores })
+    }
+
+    /// 两个氨基酸之间的替换得分
+    pub fn score(&self, a: char, b: char) -> i32 {
+        match (self.position(a), self.position(b)) {
+            (Some(i), Some(j)) => self.scores[i][j],
+            _ => 0,
+        }
+    }
+
+    fn position(&self, c: char) -> Option<usize> {
+        let c = c.to_ascii_uppercase();
+        self.alphabet
+            .iter()
+            .position(|&a| a == c)
+            .or_else(|| self.alphabet.iter().position(|&a| a == 'X'))
+    }
+}
+
+impl Default for SubstitutionMatrix {
+    fn default() -> Self {
+        Self::blosum62()
+    }
+}
+
+/// 仿射空位罚分中的三种状态
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// 两个字符对齐
+    Match,
+    /// 查询序列的字符对齐到空位
+    Up,
+    /// 目标序列的字符对齐到空位
+    Left,
+}
+
+/// 使用 BLOSUM62 与仿射空位罚分的蛋白质全局比对 (Gotoh 1982)
+/// 长度为 L 的空位得分为 `gap_open + (L - 1) * gap_extend`，两者都应为负数，例如 -11 与 -1
+pub fn align_protein(
+    query: &Sequence,
+    target: &Sequence,
+    gap_open: i32,
+    gap_extend: i32,
+) -> Alignment {
+    align_protein_with_matrix(
+        query,
+        target,
+        &SubstitutionMatrix::blosum62(),
+        gap_open,
+        gap_extend,
+    )
+}
+
+/// 与 `align_protein` 相同，但使用指定的替换矩阵，例如亲缘关系较远时使用 PAM250
+pub fn align_protein_with_matrix(
+    query: &Sequence,
+    target: &Sequence,
+    matrix: &SubstitutionMatrix,
+    gap_open: i32,
+    gap_extend: i32,
+) -> Alignment {
+    let q: Vec<char> = query.seq.chars().collect();
+    let t: Vec<char> = target.seq.chars().collect();
+    let (n, m) = (q.len(), t.len());
+    // 足够小又不会在相加时溢出
+    let minus_infinity = i32::MIN / 4;
+
+    // 三个矩阵分别记录以对齐、查询空位、目标空位结尾的最佳得分，以及各自的前一个状态
+    let mut scores = vec![vec![[minus_infinity; 3]; m + 1]; n + 1];
+    let mut trace = vec![vec![[State::Match; 3]; m + 1]; n + 1];
+    scores[0][0][State::Match as usize] = 0;
+    let best_of = |cell: [i32; 3], state: State, open: i32, extend: i32| {
+        [State::Match, State::Up, State::Left]
+            .into_iter()
+            .map(|previous| {
+                let penalty = if previous == state { extend } else { open };
+                (cell[previous as usize] + penalty, previous)
+            })
+            .max_by_key(|&(score, _)| score)
+            .unwrap()
+    };
+    for i in 0..=n {
+        for j in 0..=m {
+            if i > 0 && j > 0 {
+                let (score, previous) = best_of(scores[i - 1][j - 1], State::Match, 0, 0);
+                scores[i][j][State::Match as usize] = score + matrix.score(q[i - 1], t[j - 1]);
+                trace[i][j][State::Match as usize] = previous;
+            }
+            if i > 0 {
+                let (score, previous) = best_of(scores[i - 1][j], State::Up, gap_open, gap_extend);
+                scores[i][j][State::Up as usize] = score;
+                trace[i][j][State::Up as usize] = previous;
+            }
+            if j > 0 {
+                let (score, previous) =
+                    best_of(scores[i][j - 1], State::Left, gap_open, gap_extend);
+                scores[i][j][State::Left as usize] = score;
+                trace[i][j][State::Left as usize] = previous;
+            }
+        }
+    }
+
+    let (score, mut state) = best_of(scores[n][m], State::Match, 0, 0);
+    let (mut i, mut j) = (n, m);
+    let mut columns: Vec<(char, char)> = Vec::with_capacity(n + m);
+    while i > 0 || j > 0 {
+        let previous = trace[i][j][state as usize];
+        match state {
+            State::Match => {
+                i -= 1;
+                j -= 1;
+                columns.push((q[i], t[j]));
+            }
+            State::Up => {
+                i -= 1;
+                columns.push((q[i], '-'));
+            }
+            State::Left => {
+                j -= 1;
+                columns.push(('-', t[j]));
+            }
+        }
+        state = previous;
+    }
+    columns.reverse();
+    build_alignment(&columns, score, (0, n), (0, m))
+}
+
+/// 按替换矩阵为已有的比对重新计分，只累加两侧都不是空位 `-` 的列，空位不计罚分
+/// 可用于多序列比对中逐对重新计分；两条字符串的长度不同时会 panic
+pub fn score_alignment(
+    aligned_query: &str,
+    aligned_target: &str,
+    matrix: &SubstitutionMatrix,
+) -> i32 {
+    let (query, target): (Vec<char>, Vec<char>) = (
+        aligned_query.chars().collect(),
+        aligned_target.chars().collect(),
+    );
+    if query.len() != target.len() {
+        panic!("比对序列的长度不同: {} 与 {}", query.len(), target.len());
+    }
+    query
+        .into_iter()
+        .zip(target)
+        .filter(|&(q, t)| q != '-' && t != '-')
+        .map(|(q, t)| matrix.score(q, t))
+        .sum()
+}
+
+/// 由等长的已比对序列生成一致序列，比较时不区分大小写，空位 `-` 不参与计数
+/// 每一列中出现频率 (相对于序列条数) 超过 `threshold` 的字符直接作为该列的结果；
+/// 否则按频率从高到低取字符，直到累计频率超过 `threshold`，输出代表这些碱基的 IUPAC 简并碱基，
+/// 输入中的简并碱基按其代表的碱基合并，蛋白质序列输出 X，整列都是空位时输出 `-`
+/// 序列为空、长度不同或类型不一致时返回错误
+pub fn consensus(sequences: &[Sequence], threshold: f64) -> Result<Sequence, String> {
+    let first = sequences
+        .first()
+        .ok_or_else(|| String::from("至少需要一条序列才能生成一致序列"))?;
+    let columns: Vec<Vec<char>> = sequences.iter().map(|s| s.seq.chars().collect()).collect();
+    let length = columns[0].len();
+    if let Some(other) = sequences.iter().find(|s| s.biotype != first.biotype) {
+        return Err(format!(
+            "序列类型不一致: {} 与 {}",
+            first.biotype, other.biotype
+        ));
+    }
+    if columns.iter().any(|c| c.len() != length) {
+        return Err(String::from("生成一致序列的各条序列长度必须相同"));
+    }
+
+    let total = sequences.len() as f64;
+    let mut result = String::with_capacity(length);
+    for i in 0..length {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for column in &columns {
+            let c = column[i].to_ascii_uppercase();
+            if c != '-' {
+                *counts.entry(c).or_insert(0) += 1;
+            }
+        }
+        let mut ranked: Vec<(char, usize)> = counts.into_iter().collect();
+        // 次数相同时按字符排序，保证结果稳定
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let Some(&(top, top_count)) = ranked.first() else {
+            result.push('-');
+            continue;
+        };
+        if top_count as f64 / total > threshold {
+            result.push(top);
+            continue;
+        }
+
+        // 同频率的字符一起加入，直到累计频率超过阈值
+        let mut chosen: Vec<char> = Vec::new();
+        let mut cumulative = 0;
+        for (j, &(c, count)) in ranked.iter().enumerate() {
+            chosen.push(c);
+            cumulative += count;
+            let tied = ranked.get(j + 1).is_some_and(|next| next.1 == count);
+            if cumulative as f64 / total > threshold && !tied {
+                break;
+            }
+        }
+        result.push(ambiguity_code(&chosen, &first.biotype));
+    }
+    Ok(Sequence::new(first.biotype.clone(), result))
+}
+
+/// 一组字符对应的一致序列字符
+fn ambiguity_code(chars: &[char], biotype: &BioType) -> char {
+    if *biotype == BioType::Protein {
+        return if chars.len() == 1 { chars[0] } else { 'X' };
+    }
+    let mut bases: Vec<char> = Vec::new();
+    for &c in chars {
+        match AmbiguousBase::from_char(c) {
+            Some(code) => bases.extend_from_slice(code.bases()),
+            None => bases.push(if c == 'U' { 'T' } else { c }),
+        }
+    }
+    bases.sort_unstable();
+    bases.dedup();
+    match AmbiguousBase::from_bases(&bases) {
+        Some(code) => code.to_char(),
+        None if bases.len() == 1 && *biotype == BioType::Rna && bases[0] == 'T' => 'U',
+        None if bases.len() == 1 => bases[0],
+        None => 'N',
+    }
+}
+
+/// 星形 (center star) 渐进多序列比对
+/// 先选出与其它序列编辑距离之和最小的序列作为中心，再将其余序列依次与中心做全局比对，
+/// 按 "一旦是空位就始终是空位" 的原则合并到结果中
+/// 返回与输入顺序一致、以 `-` 补齐的等长字符串
+pub fn progressive_align(
+    sequences: &[Sequence],
+    match_score: i32,
+    mismatch: i32,
+    gap: i32,
+) -> Result<Vec<String>, AlignmentError> {
+    let first = sequences.first().ok_or(AlignmentError::Empty)?;
+    if let Some((index, other)) = sequences
+        .iter()
+        .enumerate()
+        .find(|(_, s)| s.biotype != first.biotype)
+    {
+        return Err(AlignmentError::BiotypeMismatch {
+            index,
+            expected: first.biotype.clone(),
+            found: other.biotype.clone(),
+        });
+    }
+
+    let n = sequences.len();
+    let mut distances = vec![0usize; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let d = levenshtein(&sequences[i].seq, &sequences[j].seq);
+            distances[i] += d;
+            distances[j] += d;
+        }
+    }
+    let center = (0..n).min_by_key(|&i| distances[i]).unwrap_or(0);
+
+    // rows[0] 为中心序列，其余按加入顺序排列
+    let mut rows: Vec<Vec<char>> = vec![sequences[center].seq.chars().collect()];
+    let mut order: Vec<usize> = vec![center];
+    for (index, sequence) in sequences.iter().enumerate() {
+        if index == center {
+            continue;
+        }
+        let pair = global_align(&sequences[center], sequence, match_score, mismatch, gap);
+        let pair_center: Vec<char> = pair.aligned_query.chars().collect();
+        let pair_other: Vec<char> = pair.aligned_target.chars().collect();
+
+        let mut merged: Vec<Vec<char>> = vec![Vec::new(); rows.len() + 1];
+        let (mut i, mut j) = (0, 0);
+        while i < rows[0].len() || j < pair_center.len() {
+            let msa_gap = i < rows[0].len() && rows[0][i] == '-';
+            let pair_gap = j < pair_center.len() && pair_center[j] == '-';
+            if i < rows[0].len() && (msa_gap || j == pair_center.len()) {
+                // 已有结果中的插入列，新序列在此补空位
+                for (row, column) in merged.iter_mut().zip(&rows) {
+                    row.push(column[i]);
+                }
+                merged[rows.len()].push('-');
+                i += 1;
+            } else if pair_gap || i == rows[0].len() {
+                // 新序列相对中心序列的插入，为已有序列补空位
+                for row in merged.iter_mut().take(rows.len()) {
+                    row.push('-');
+                }
+                merged[rows.len()].push(pair_other[j]);
+                j += 1;
+            } else {
+                for (row, column) in merged.iter_mut().zip(&rows) {
+                    row.push(column[i]);
+                }
+                merged[rows.len()].push(pair_other[j]);
+                i += 1;
+                j += 1;
+            }
+        }
+        rows = merged;
+        order.push(index);
+    }
+
+    let mut result = vec![String::new(); n];
+    for (row, index) in rows.into_iter().zip(order) {
+        result[index] = row.into_iter().collect();
+    }
+    Ok(result)
+}
+
+/// 由比对列构建比对结果与 CIGAR 字符串
+fn build_alignment(
+    columns: &[(char, char)],
+    score: i32,
+    query_range: (usize, usize),
+    target_range: (usize, usize),
+) -> Alignment {
+    Alignment {
+        aligned_query: columns.iter().map(|&(q, _)| q).collect(),
+        aligned_target: columns.iter().map(|&(_, t)| t).collect(),
+        score,
+        cigar: cigar(columns),
+        query_start: query_range.0,
+        query_end: query_range.1,
+        target_start: target_range.0,
+        target_end: target_range.1,
+    }
+}
+
+/// 将比对列压缩为 CIGAR 字符串
+fn cigar(columns: &[(char, char)]) -> String {
+    let mut cigar = String::new();
+    let mut last: Option<(char, usize)> = None;
+    for &(q, t) in columns {
+        let op = match (q, t) {
+            (_, '-') => 'I',
+            ('-', _) => 'D',
+            _ => 'M',
+        };
+        last = match last {
+            Some((last_op, count)) if last_op == op => Some((op, count + 1)),
+            Some((last_op, count)) => {
+                cigar.push_str(&format!("{}{}", count, last_op));
+                Some((op, 1))
+            }
+            None => Some((op, 1)),
+        };
+    }
+    if let Some((op, count)) = last {
+        cigar.push_str(&format!("{}{}", count, op));
+    }
+    cigar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_gattaca() {
+        let query = Sequence::new(BioType::Dna, String::from("GATTACA"));
+        let target = Sequence::new(BioType::Dna, String::from("GCATGCU"));
+        let alignment = global_align(&query, &target, 1, -1, -1);
+        assert_eq!(alignment.score, 0);
+        assert_eq!(alignment.aligned_query.replace('-', ""), "GATTACA");
+        assert_eq!(alignment.aligned_target.replace('-', ""), "GCATGCU");
+        assert_eq!(
+            alignment.aligned_query.len(),
+            alignment.aligned_target.len()
+        );
+
+        let identical = global_align(&query, &query, 1, -1, -1);
+        assert_eq!(identical.score, 7);
+        assert_eq!(identical.cigar, "7M");
+        assert_eq!(identical.to_string(), "GATTACA\n|||||||\nGATTACA");
+    }
+
+    #[test]
+    fn global_gaps() {
+        let query = Sequence::new(BioType::Dna, String::from("ACGTACGT"));
+        let target = Sequence::new(BioType::Dna, String::from("ACGACGT"));
+        let alignment = global_align(&query, &target, 2, -1, -2);
+        assert_eq!(alignment.score, 12);
+        assert_eq!(alignment.aligned_target.matches('-').count(), 1);
+        assert_eq!(alignment.identity(), 7);
+        assert_eq!(alignment.cigar, "3M1I4M");
+    }
+
+    #[test]
+    fn local_alignment() {
+        let query = Sequence::new(BioType::Dna, String::from("TTTTGATTACATTTT"));
+        let target = Sequence::new(BioType::Dna, String::from("CCGATTACACC"));
+        let alignment = local_align(&query, &target, 2, -1, -2);
+        assert_eq!(alignment.score, 14);
+        assert_eq!(alignment.aligned_query, "GATTACA");
+        assert_eq!((alignment.query_start, alignment.query_end), (4, 11));
+        assert_eq!((alignment.target_start, alignment.target_end), (2, 9));
+        assert_eq!(
+            &query.seq[alignment.query_start..alignment.query_end],
+            &target.seq[alignment.target_start..alignment.target_end]
+        );
+
+        let poly_t = Sequence::new(BioType::Dna, String::from("TTTT"));
+        let poly_g = Sequence::new(BioType::Dna, String::from("GGGG"));
+        let empty = local_align(&poly_t, &poly_g, 2, -1, -2);
+        assert_eq!(empty.score, 0);
+        assert!(empty.aligned_query.is_empty());
+    }
+
+    #[test]
+    fn consensus_with_ambiguity_codes() {
+        let dna = |s: &str| Sequence::new(BioType::Dna, String::from(s));
+        let alignment = [dna("ACGT-"), dna("ACGA-"), dna("AAGC-"), dna("actc-")];
+        let result = consensus(&alignment, 0.6).unwrap();
+        assert_eq!(result.biotype, BioType::Dna);
+        // 第四列 C 占 50%，A 与 T 各占 25%，合并为 H (A/C/T)
+        assert_eq!(result.seq, "ACGH-");
+        assert_eq!(consensus(&[dna("AG"), dna("GG")], 0.5).unwrap().seq, "RG");
+        assert_eq!(consensus(&[dna("AR"), dna("GC")], 0.5).unwrap().seq, "RV");
+
+        let rna = |s: &str| Sequence::new(BioType::Rna, String::from(s));
+        assert_eq!(
+            consensus(&[rna("U-"), rna("U-"), rna("C-")], 0.7)
+                .unwrap()
+                .seq,
+            "Y-"
+        );
+        let protein = |s: &str| Sequence::new(BioType::Protein, String::from(s));
+        assert_eq!(
+            consensus(&[protein("MK"), protein("ML")], 0.5).unwrap().seq,
+            "MX"
+        );
+
+        assert!(consensus(&[dna("ACGT"), dna("ACG")], 0.5).is_err());
+        assert!(consensus(&[dna("ACGT"), rna("ACGU")], 0.5).is_err());
+        assert!(consensus(&[], 0.5).is_err());
+    }
+
+    #[test]
+    fn star_progressive_alignment() {
+        let dna = |s: &str| Sequence::new(BioType::Dna, String::from(s));
+        let sequences = [
+            dna("ACGTTG"),
+            dna("ACGTACGT"),
+            dna("ACGTAGT"),
+            dna("ACGTACG"),
+        ];
+        let msa = progressive_align(&sequences, 1, -1, -2).unwrap();
+        assert_eq!(msa.len(), sequences.len());
+        let width = msa[0].len();
+        assert!(msa.iter().all(|row| row.chars().count() == width));
+        for (row, sequence) in msa.iter().zip(&sequences) {
+            assert_eq!(row.replace('-', ""), sequence.seq);
+        }
+        // 中心序列 ACGTACGT 没有空位
+        assert_eq!(msa[1], "ACGTACGT");
+
+        assert_eq!(
+            progressive_align(&sequences[..1], 1, -1, -2).unwrap(),
+            vec!["ACGTTG"]
+        );
+        assert_eq!(
+            progressive_align(&[], 1, -1, -2),
+            Err(AlignmentError::Empty)
+        );
+        let mixed = [
+            dna("ACGT"),
+            Sequence::new(BioType::Rna, String::from("ACGU")),
+        ];
+        assert!(matches!(
+            progressive_align(&mixed, 1, -1, -2),
+            Err(AlignmentError::BiotypeMismatch { index: 1, .. })
+        ));
+        assert_eq!(levenshtein("kitten", "SITTING"), 3);
+    }
+
+    #[test]
+    fn blosum62_protein_alignment() {
+        let matrix = SubstitutionMatrix::blosum62();
+        assert_eq!(matrix.score('W', 'W'), 11);
+        assert_eq!(matrix.score('a', 'S'), 1);
+        assert_eq!(matrix.score('I', 'V'), 3);
+        assert_eq!(matrix.score('U', 'A'), 0);
+        for a in BLOSUM62_ORDER.chars() {
+            for b in BLOSUM62_ORDER.chars() {
+                assert_eq!(matrix.score(a, b), matrix.score(b, a));
+            }
+        }
+
+        let query = Sequence::new(BioType::Protein, String::from("HEAGAWGHEE"));
+        let target = Sequence::new(BioType::Protein, String::from("PAWHEAE"));
+        let alignment = align_protein(&query, &target, -10, -1);
+        assert_eq!(alignment.aligned_query.replace('-', ""), "HEAGAWGHEE");
+        assert_eq!(alignment.aligned_target.replace('-', ""), "PAWHEAE");
+        // 比对得分等于替换得分加上每段空位的罚分
+        let gaps: i32 = alignment
+            .aligned_query
+            .split(|c| c != '-')
+            .chain(alignment.aligned_target.split(|c| c != '-'))
+            .filter(|gap| !gap.is_empty())
+            .map(|gap| -10 - (gap.len() as i32 - 1))
+            .sum();
+        assert_eq!(
+            alignment.score,
+            score_alignment(&alignment.aligned_query, &alignment.aligned_target, &matrix) + gaps
+        );
+
+        // 一段长空位比两段短空位罚分更少
+        let query = Sequence::new(BioType::Protein, String::from("MKWVTFISLLFLFSSAYS"));
+        let target = Sequence::new(BioType::Protein, String::from("MKWVTFSAYS"));
+        let alignment = align_protein(&query, &target, -10, -1);
+        assert_eq!(alignment.aligned_target, "MKWVTF--------SAYS");
+        assert_eq!(
+            alignment.score,
+            5 + 5 + 11 + 4 + 5 + 6 + 4 + 4 + 7 + 4 - 10 - 7
+        );
+        assert_eq!(alignment.cigar, "6M8I4M");
+    }
+
+    #[test]
+    fn pam250_and_aaindex_matrices() {
+        let pam = SubstitutionMatrix::pam250();
+        assert_eq!(pam.score('W', 'W'), 17);
+        assert_eq!(pam.score('c', 'C'), 12);
+        assert_eq!(pam.score('F', 'Y'), 7);
+        for a in BLOSUM62_ORDER.chars() {
+            for b in BLOSUM62_ORDER.chars() {
+                assert_eq!(pam.score(a, b), pam.score(b, a));
+            }
+        }
+        let query = Sequence::new(BioType::Protein, String::from("MKWVTFISLL"));
+        let target = Sequence::new(BioType::Protein, String::from("MKWVTFLSLL"));
+        let alignment = align_protein_with_matrix(&query, &target, &pam, -10, -1);
+        assert_eq!(
+            alignment.score,
+            score_alignment(&alignment.aligned_query, &alignment.aligned_target, &pam)
+        );
+        assert_eq!(alignment.cigar, "10M");
+
+        // 下三角形式的 AAindex 条目
+        let text = "H TEST\nD test matrix\nM rows = ACW, cols = ACW\n      2.\n     -1.      9.\n     -3.   -2.6     15.\n//\n";
+        let path = std::env::temp_dir().join(format!("biocirc-{}.aaindex", std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        let custom = SubstitutionMatrix::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(custom.score('A', 'A'), 2);
+        assert_eq!(custom.score('W', 'C'), -3);
+        assert_eq!(custom.score('C', 'W'), -3);
+        // 没有 X 时未知字符为 0
+        assert_eq!(custom.score('K', 'A'), 0);
+        assert!(SubstitutionMatrix::from_aaindex("M rows = ACW, cols = ACW\n 1 2\n//").is_err());
+        assert!(SubstitutionMatrix::from_file("tests/data/missing.aaindex").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use super::protein::{side_chain_charge, PkaTable, AMINO_ACID_WEIGHTS};
+use super::sequence::{BioType, Sequence};
+
+/// 侧链极性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Polarity {
+    Polar,
+    Nonpolar,
+}
+
+/// 单个标准氨基酸的理化性质
+#[derive(Debug, Clone, PartialEq)]
+pub struct AminoAcidProperties {
+    /// Kyte-Doolittle 疏水性指数
+    pub hydrophobicity_kd: f64,
+    /// 侧链在 pH 7 时的电荷，按 Lehninger pKa 计算
+    pub charge_at_ph7: f64,
+    pub polarity: Polarity,
+    /// 游离氨基酸的平均分子量 (Da)
+    pub molecular_weight: f64,
+    pub three_letter_code: &'static str,
+}
+
+/// 跨膜螺旋预测的标准窗口长度，约为跨过脂双层的 α 螺旋的残基数 (Kyte & Doolittle 1982)
+pub const TM_WINDOW: usize = 19;
+/// 跨膜螺旋预测的标准阈值：窗口平均疏水性超过该值时视为跨膜区段
+pub const TM_THRESHOLD: f64 = 1.6;
+
+/// 预测的跨膜螺旋，`start..end` 为以 0 开始的半开区间
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmHelix {
+    pub start: usize,
+    pub end: usize,
+    /// 区间内所有残基 Kyte-Doolittle 疏水性指数的平均值
+    pub mean_hydrophobicity: f64,
+}
+
+/// 单字母代码、三字母代码、Kyte-Doolittle 疏水性指数 (Kyte & Doolittle 1982) 与极性
+const AMINO_ACIDS: [(char, &str, f64, Polarity); 20] = [
+    ('A', "Ala", 1.8, Polarity::Nonpolar),
+    ('R', "Arg", -4.5, Polarity::Polar),
+    ('N', "Asn", -3.5, Polarity::Polar),
+    ('D', "Asp", -3.5, Polarity::Polar),
+    ('C', "Cys", 2.5, Polarity::Polar),
+    ('E', "Glu", -3.5, Polarity::Polar),
+    ('Q', "Gln", -3.5, Polarity::Polar),
+    ('G', "Gly", -0.4, Polarity::Nonpolar),
+    ('H', "His", -3.2, Polarity::Polar),
+    ('I', "Ile", 4.5, Polarity::Nonpolar),
+    ('L', "Leu", 3.8, Polarity::Nonpolar),
+    ('K', "Lys", -3.9, Polarity::Polar),
+    ('M', "Met", 1.9, Polarity::Nonpolar),
+    ('F', "Phe", 2.8, Polarity::Nonpolar),
+    ('P', "Pro", -1.6, Polarity::Nonpolar),
+    ('S', "Ser", -0.8, Polarity::Polar),
+    ('T', "Thr", -0.7, Polarity::Polar),
+    ('W', "Trp", -0.9, Polarity::Nonpolar),
+    ('Y', "Tyr", -1.3, Polarity::Polar),
+    ('V', "Val", 4.2, Polarity::Nonpolar),
+];
+
+/// 查询单个氨基酸 (单字母代码，不区分大小写) 的理化性质，非标准氨基酸返回错误
+pub fn properties(aa: char) -> Result<AminoAcidProperties, String> {
+    let aa = aa.to_ascii_uppercase();
+    let &(_, three_letter_code, hydrophobicity_kd, polarity) = AMINO_ACIDS
+        .iter()
+        .find(|(code, ..)| *code == aa)
+        .ok_or_else(|| format!("未知的氨基酸: {}", aa))?;
+    let molecular_weight = AMINO_ACID_WEIGHTS
+        .iter()
+        .find(|(code, _)| *code == aa)
+        .map(|&(_, weight)| weight)
+        .unwrap_or_default();
+    Ok(AminoAcidProperties {
+        hydrophobicity_kd,
+        charge_at_ph7: side_chain_charge(aa, 7.0, &PkaTable::lehninger()),
+        polarity,
+        molecular_weight,
+        three_letter_code,
+    })
+}
+
+impl Sequence {
+    /// 以步长 1 滑动长度为 `window` 的窗口，计算每个窗口 Kyte-Doolittle 疏水性指数的平均值
+    /// 窗口长度超过序列长度时返回空数组
+    /// 非蛋白质序列、`window` 为 0 或含有非标准氨基酸 (包括 `*`) 时返回错误
+    pub fn hydrophobicity_profile(&self, window: usize) -> Result<Vec<f64>, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能计算 {} 序列的疏水性", BioType::Protein));
+        }
+        if window == 0 {
+            return Err(String::from("窗口长度必须大于 0"));
+        }
+        let scores = self
+            .seq
+            .chars()
+            .map(|aa| properties(aa).map(|p| p.hydrophobicity_kd))
+            .collect::<Result<Vec<f64>, String>>()?;
+        Ok(scores
+            .windows(window)
+            .map(|w| w.iter().sum::<f64>() / window as f64)
+            .collect())
+    }
+
+    /// 用 Kyte-Doolittle 疏水性预测跨膜螺旋，标准参数为 `TM_WINDOW` 与 `TM_THRESHOLD`
+    /// 平均疏水性超过 `threshold` 的连续窗口合并为一个螺旋，区间从第一个窗口的起点到最后一个窗口的终点
+    /// 序列短于窗口时返回空数组；错误条件与 `hydrophobicity_profile` 相同
+    pub fn transmembrane_helices(
+        &self,
+        window: usize,
+        threshold: f64,
+    ) -> Result<Vec<TmHelix>, String> {
+        let profile = self.hydrophobicity_profile(window)?;
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (i, &score) in profile.iter().enumerate() {
+            if score <= threshold {
+                continue;
+            }
+            match spans.last_mut() {
+                Some((_, end)) if *end == i + window - 1 => *end = i + window,
+                _ => spans.push((i, i + window)),
+            }
+        }
+        let scores: Vec<f64> = self
+            .seq
+            .chars()
+            .map(|aa| {
+                properties(aa)
+                    .map(|p| p.hydrophobicity_kd)
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(spans
+            .into_iter()
+            .map(|(start, end)| TmHelix {
+                start,
+                end,
+                mean_hydrophobicity: scores[start..end].iter().sum::<f64>() / (end - start) as f64,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amino_acid_properties() {
+        let lys = properties('k').unwrap();
+        assert_eq!(lys.three_letter_code, "Lys");
+        assert_eq!(lys.hydrophobicity_kd, -3.9);
+        assert_eq!(lys.polarity, Polarity::Polar);
+        assert!((lys.molecular_weight - 146.1876).abs() < 1e-9);
+        assert!(lys.charge_at_ph7 > 0.99);
+        assert!(properties('D').unwrap().charge_at_ph7 < -0.99);
+        assert_eq!(properties('L').unwrap().charge_at_ph7, 0.0);
+        assert_eq!(properties('I').unwrap().polarity, Polarity::Nonpolar);
+        assert!(properties('X').is_err());
+    }
+
+    #[test]
+    fn kyte_doolittle_profile() {
+        let protein = Sequence::new(BioType::Protein, String::from("IVKR"));
+        let profile = protein.hydrophobicity_profile(2).unwrap();
+        let expected = [4.35, 0.15, -4.2];
+        assert_eq!(profile.len(), expected.len());
+        for (got, want) in profile.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-9);
+        }
+        assert!(protein.hydrophobicity_profile(5).unwrap().is_empty());
+        assert!(protein.hydrophobicity_profile(0).is_err());
+        assert!(Sequence::new(BioType::Dna, String::from("ACGT"))
+            .hydrophobicity_profile(2)
+            .is_err());
+    }
+
+    #[test]
+    fn transmembrane_prediction() {
+        // 两段 20 个疏水残基 (L 为 3.8) 被亲水区段隔开
+        let seq = format!(
+            "{}{}{}{}{}",
+            "KDEKDEKDER",
+            "L".repeat(20),
+            "KDEKDEKDERKDEKDEKDER",
+            "L".repeat(20),
+            "KDEKD"
+        );
+        let protein = Sequence::new(BioType::Protein, seq);
+        let helices = protein
+            .transmembrane_helices(TM_WINDOW, TM_THRESHOLD)
+            .unwrap();
+        // 重叠的窗口合并后向两侧各延伸到 5 个亲水残基
+        let spans: Vec<(usize, usize)> = helices.iter().map(|h| (h.start, h.end)).collect();
+        assert_eq!(spans, vec![(5, 35), (45, 75)]);
+        assert!((helices[0].mean_hydrophobicity - 38.8 / 30.0).abs() < 1e-9);
+
+        let short = Sequence::new(BioType::Protein, "L".repeat(10));
+        assert!(short
+            .transmembrane_helices(TM_WINDOW, TM_THRESHOLD)
+            .unwrap()
+            .is_empty());
+        assert!(Sequence::new(BioType::Dna, String::from("ACGT"))
+            .transmembrane_helices(TM_WINDOW, TM_THRESHOLD)
+            .is_err());
+    }
+}
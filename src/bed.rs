@@ -0,0 +1,327 @@
+use super::sequence::{Sequence, Strand};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+// BED 文件的读取，CIRI2、find_circ、circRNA_finder 等工具都以 BED 输出环状 RNA
+// 至少 3 列：chrom、start (以 0 开始)、end (不含)，可选 name、score、strand
+// BED12 的第 10 ~ 12 列为 blockCount、blockSizes、blockStarts，描述外显子结构
+// thickStart、thickEnd 与 itemRgb (第 7 ~ 9 列) 不会被保存
+
+/// BED 读取与序列提取过程中可能出现的错误
+#[derive(Debug)]
+pub enum BedError {
+    /// 文件不存在
+    NotFound(PathBuf),
+    /// 其他读写错误
+    Io(io::Error),
+    /// 列数少于 3 列
+    InvalidColumns { line: usize, found: usize },
+    /// 起点或终点不是整数，或起点大于终点
+    InvalidPosition { line: usize },
+    /// score 列既不是 `.` 也不是数字
+    InvalidScore { line: usize },
+    /// strand 列不是 `+`、`-` 或 `.`
+    InvalidStrand { line: usize },
+    /// BED12 的 block 信息不完整或超出区间
+    InvalidBlocks { line: usize },
+    /// 找不到记录所在的序列
+    MissingSequence(String),
+    /// 记录的终点超出序列长度
+    OutOfRange {
+        chrom: String,
+        end: usize,
+        len: usize,
+    },
+    /// 无法对序列取反向互补 (例如蛋白质序列)
+    InvalidSequence(String),
+}
+
+impl fmt::Display for BedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BedError::NotFound(path) => write!(f, "找不到 BED 文件: {}", path.display()),
+            BedError::Io(err) => write!(f, "BED 读写错误: {}", err),
+            BedError::InvalidColumns { line, found } => {
+                write!(f, "第 {} 行: 至少需要 3 列，实际为 {} 列", line, found)
+            }
+            BedError::InvalidPosition { line } => write!(f, "第 {} 行: 起止位置无效", line),
+            BedError::InvalidScore { line } => write!(f, "第 {} 行: score 无效", line),
+            BedError::InvalidStrand { line } => write!(f, "第 {} 行: strand 无效", line),
+            BedError::InvalidBlocks { line } => write!(f, "第 {} 行: block 信息无效", line),
+            BedError::MissingSequence(chrom) => write!(f, "找不到序列: {}", chrom),
+            BedError::OutOfRange { chrom, end, len } => {
+                write!(f, "{} 上的终点 {} 超出序列长度 {}", chrom, end, len)
+            }
+            BedError::InvalidSequence(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for BedError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            BedError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BedError {
+    fn from(err: io::Error) -> Self {
+        BedError::Io(err)
+    }
+}
+
+/// BED 文件中的一条记录
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BedRecord {
+    pub chrom: String,
+    /// 起点，以 0 开始
+    pub start: usize,
+    /// 终点，不含
+    pub end: usize,
+    pub name: Option<String>,
+    pub score: Option<f64>,
+    /// `.` 或缺失时为 None
+    pub strand: Option<Strand>,
+    /// BED12 中的外显子 block，保存为相对 `start` 的 (起点, 长度)，其他格式为空
+    pub blocks: Vec<(usize, usize)>,
+}
+
+impl BedRecord {
+    /// 区间长度 (包括内含子)
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// 区间是否为空
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// 读取 BED 文件中的全部记录
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<BedRecord>, BedError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => BedError::NotFound(path.to_path_buf()),
+        _ => BedError::Io(err),
+    })?;
+    parse(BufReader::new(file))
+}
+
+/// 从任意 BufRead 中解析 BED 记录
+/// 列之间以制表符分隔，没有制表符时按空白分隔；空行、`#` 注释以及 track / browser 行会被忽略
+pub fn parse(reader: impl BufRead) -> Result<Vec<BedRecord>, BedError> {
+    let mut records = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty()
+            || line.starts_with('#')
+            || line.starts_with("track")
+            || line.starts_with("browser")
+        {
+            continue;
+        }
+        records.push(parse_line(line, number + 1)?);
+    }
+    Ok(records)
+}
+
+fn parse_line(line: &str, number: usize) -> Result<BedRecord, BedError> {
+    let columns: Vec<&str> = if line.contains('\t') {
+        line.split('\t').collect()
+    } else {
+        line.split_whitespace().collect()
+    };
+    if columns.len() < 3 {
+        return Err(BedError::InvalidColumns {
+            line: number,
+            found: columns.len(),
+        });
+    }
+    let position = |text: &str| {
+        text.parse::<usize>()
+            .map_err(|_| BedError::InvalidPosition { line: number })
+    };
+    let start = position(columns[1])?;
+    let end = position(columns[2])?;
+    if start > end {
+        return Err(BedError::InvalidPosition { line: number });
+    }
+    let name = columns
+        .get(3)
+        .filter(|name| !name.is_empty() && **name != ".")
+        .map(|name| name.to_string());
+    let score = match columns.get(4) {
+        None | Some(&".") => None,
+        Some(text) => Some(
+            text.parse()
+                .map_err(|_| BedError::InvalidScore { line: number })?,
+        ),
+    };
+    let strand = match columns.get(5) {
+        Some(&"+") => Some(Strand::Forward),
+        Some(&"-") => Some(Strand::Reverse),
+        None | Some(&".") => None,
+        Some(_) => return Err(BedError::InvalidStrand { line: number }),
+    };
+    let blocks = if columns.len() >= 12 {
+        parse_blocks(&columns[9..12], end - start)
+            .ok_or(BedError::InvalidBlocks { line: number })?
+    } else {
+        Vec::new()
+    };
+    Ok(BedRecord {
+        chrom: columns[0].to_string(),
+        start,
+        end,
+        name,
+        score,
+        strand,
+        blocks,
+    })
+}
+
+/// 解析 blockCount、blockSizes、blockStarts 三列，要求每个 block 都位于区间之内
+fn parse_blocks(columns: &[&str], len: usize) -> Option<Vec<(usize, usize)>> {
+    let count: usize = columns[0].parse().ok()?;
+    let list = |text: &str| -> Option<Vec<usize>> {
+        text.trim_end_matches(',')
+            .split(',')
+            .map(|value| value.trim().parse().ok())
+            .collect()
+    };
+    let sizes = list(columns[1])?;
+    let starts = list(columns[2])?;
+    if sizes.len() != count || starts.len() != count {
+        return None;
+    }
+    let blocks: Vec<(usize, usize)> = starts.into_iter().zip(sizes).collect();
+    if blocks.iter().any(|&(start, size)| start + size > len) {
+        return None;
+    }
+    Some(blocks)
+}
+
+/// 提取记录对应的序列，BED12 记录会按 block 拼接外显子，负链记录取反向互补
+pub fn extract(
+    record: &BedRecord,
+    sequences: &HashMap<String, Sequence>,
+) -> Result<Sequence, BedError> {
+    let chromosome = sequences
+        .get(&record.chrom)
+        .ok_or_else(|| BedError::MissingSequence(record.chrom.clone()))?;
+    if record.end > chromosome.len() {
+        return Err(BedError::OutOfRange {
+            chrom: record.chrom.clone(),
+            end: record.end,
+            len: chromosome.len(),
+        });
+    }
+    let mut sequence = if record.blocks.is_empty() {
+        chromosome.subsequence(record.start..record.end)
+    } else {
+        let mut spliced = Sequence::new(chromosome.biotype.clone(), String::new());
+        for &(start, size) in record.blocks.iter() {
+            let start = record.start + start;
+            spliced
+                .seq
+                .push_str(&chromosome.subsequence(start..start + size).seq);
+        }
+        spliced
+    };
+    if record.strand == Some(Strand::Reverse) {
+        sequence = sequence
+            .reverse_complementary()
+            .map_err(BedError::InvalidSequence)?;
+    }
+    Ok(sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+    use std::io::Cursor;
+
+    const BED: &str = "track name=circRNA
+# CIRI2 style output
+chr1\t4\t20\tcirc1\t12\t+
+chr1\t4\t21\tcirc2\t0\t-\t4\t21\t0\t2\t3,4,\t0,13,
+chr2 0 4
+";
+
+    fn genome() -> HashMap<String, Sequence> {
+        let mut genome = HashMap::new();
+        genome.insert(
+            String::from("chr1"),
+            Sequence::new(BioType::Dna, String::from("NNNNATGccccccccccGTACNNNN")),
+        );
+        genome
+    }
+
+    #[test]
+    fn parse_bed_records() {
+        let records = parse(Cursor::new(BED)).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].chrom, "chr1");
+        assert_eq!((records[0].start, records[0].end), (4, 20));
+        assert_eq!(records[0].name.as_deref(), Some("circ1"));
+        assert_eq!(records[0].score, Some(12.0));
+        assert_eq!(records[0].strand, Some(Strand::Forward));
+        assert!(records[0].blocks.is_empty());
+        assert_eq!(records[1].blocks, vec![(0, 3), (13, 4)]);
+        assert_eq!(records[2].len(), 4);
+        assert_eq!(records[2].name, None);
+        assert_eq!(records[2].strand, None);
+    }
+
+    #[test]
+    fn extract_bed_sequences() {
+        let records = parse(Cursor::new(BED)).unwrap();
+        let genome = genome();
+        assert_eq!(
+            extract(&records[0], &genome).unwrap().seq,
+            "ATGccccccccccGTA"
+        );
+        // 两个 block (ATG 与 GTAC) 拼接后取反向互补
+        assert_eq!(extract(&records[1], &genome).unwrap().seq, "GTACCAT");
+        assert!(matches!(
+            extract(&records[2], &genome),
+            Err(BedError::MissingSequence(_))
+        ));
+        let mut long = records[0].clone();
+        long.end = 100;
+        assert!(matches!(
+            extract(&long, &genome),
+            Err(BedError::OutOfRange { len: 25, .. })
+        ));
+    }
+
+    #[test]
+    fn malformed_bed() {
+        assert!(matches!(
+            parse(Cursor::new("chr1\t5\n")),
+            Err(BedError::InvalidColumns { line: 1, found: 2 })
+        ));
+        assert!(matches!(
+            parse(Cursor::new("chr1\t5\t2\n")),
+            Err(BedError::InvalidPosition { line: 1 })
+        ));
+        assert!(matches!(
+            parse(Cursor::new("chr1\t0\t9\tx\t0\t*\n")),
+            Err(BedError::InvalidStrand { line: 1 })
+        ));
+        assert!(matches!(
+            parse(Cursor::new("chr1\t0\t9\tx\t0\t+\t0\t9\t0\t2\t3,4\t0,8\n")),
+            Err(BedError::InvalidBlocks { line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+use super::sequence::{BioType, Sequence, Strand};
+
+use std::collections::HashSet;
+
+// 亚硫酸氢盐处理的模拟，用于设计甲基化测序 (BS-seq) 引物和测试甲基化相关的分析流程
+// 亚硫酸氢盐把未甲基化的 C 脱氨为 U，PCR 之后读作 T；5-甲基胞嘧啶 (5mC) 不受影响
+// 结果保持原有的大小写与拓扑结构
+
+impl Sequence {
+    /// 模拟亚硫酸氢盐处理，假设 CpG 中的 C 全部甲基化，其他 C 全部未甲基化
+    /// - `Strand::Forward`：正链 (Watson) 上后面不是 G 的 C 转换为 T
+    /// - `Strand::Reverse`：负链 (Crick) 上的 C 转换为 T，按正链方向表示即前面不是 C 的 G 转换为 A
+    ///
+    /// 环状序列的 CpG 可以跨过起点
+    /// RNA 只有一条链，未甲基化的 C 转换为 U；蛋白质序列、RNA 的负链以及 `Strand::Both` 返回错误
+    pub fn bisulfite_convert(&self, strand: Strand) -> Result<Sequence, String> {
+        let chars: Vec<char> = self.seq.chars().collect();
+        let n = chars.len();
+        let circular = self.is_circular();
+        let next = |i: usize| match i + 1 {
+            j if j < n => Some(chars[j]),
+            _ if circular => chars.first().copied(),
+            _ => None,
+        };
+        let previous = |i: usize| match i {
+            0 if circular => chars.last().copied(),
+            0 => None,
+            _ => Some(chars[i - 1]),
+        };
+        let is = |c: Option<char>, base: char| c.is_some_and(|c| c.eq_ignore_ascii_case(&base));
+
+        let converted: Vec<char> = match (&self.biotype, strand) {
+            (BioType::Protein, _) => {
+                return Err(format!("不能对 {} 序列进行亚硫酸氢盐转换", self.biotype))
+            }
+            (_, Strand::Both) => {
+                return Err(String::from("一次只能转换一条链"));
+            }
+            (BioType::Rna, Strand::Reverse) => {
+                return Err(format!("{} 序列没有负链", self.biotype));
+            }
+            (BioType::Dna | BioType::Rna, Strand::Forward) => {
+                let target = if self.biotype == BioType::Rna {
+                    'U'
+                } else {
+                    'T'
+                };
+                (0..n)
+                    .map(|i| match chars[i] {
+                        c @ ('C' | 'c') if !is(next(i), 'G') => convert_case(c, target),
+                        c => c,
+                    })
+                    .collect()
+            }
+            (BioType::Dna, Strand::Reverse) => (0..n)
+                .map(|i| match chars[i] {
+                    c @ ('G' | 'g') if !is(previous(i), 'C') => convert_case(c, 'A'),
+                    c => c,
+                })
+                .collect(),
+        };
+        Ok(Sequence {
+            biotype: self.biotype.clone(),
+            seq: converted.into_iter().collect(),
+            topology: self.topology,
+        })
+    }
+
+    /// 模拟正链的亚硫酸氢盐处理，只有 `methylated_positions` (以 0 开始) 上的 C 不被转换
+    /// 其余 C 不论序列上下文都转换为 T (RNA 为 U)，位置上不是 C 时忽略
+    /// 蛋白质序列或位置超出序列长度时返回错误
+    pub fn bisulfite_convert_with_methylation(
+        &self,
+        methylated_positions: &[usize],
+    ) -> Result<Sequence, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("不能对 {} 序列进行亚硫酸氢盐转换", self.biotype));
+        }
+        let n = self.seq.chars().count();
+        if let Some(&position) = methylated_positions.iter().find(|&&p| p >= n) {
+            return Err(format!("甲基化位置 {} 超出序列长度 {}", position, n));
+        }
+        let methylated: HashSet<usize> = methylated_positions.iter().copied().collect();
+        let target = if self.biotype == BioType::Rna {
+            'U'
+        } else {
+            'T'
+        };
+        let seq = self
+            .seq
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                'C' | 'c' if !methylated.contains(&i) => convert_case(c, target),
+                c => c,
+            })
+            .collect();
+        Ok(Sequence {
+            biotype: self.biotype.clone(),
+            seq,
+            topology: self.topology,
+        })
+    }
+}
+
+/// 转换为 `target` (大写)，保持 `c` 的大小写
+fn convert_case(c: char, target: char) -> char {
+    if c.is_ascii_lowercase() {
+        target.to_ascii_lowercase()
+    } else {
+        target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bisulfite_conversion() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGTCCAgcG"));
+        assert_eq!(
+            dna.bisulfite_convert(Strand::Forward).unwrap().seq,
+            "ACGTTTAgcG"
+        );
+        // 负链：CpG 中的 G 保留，其他 G 转换为 A
+        assert_eq!(
+            dna.bisulfite_convert(Strand::Reverse).unwrap().seq,
+            "ACGTCCAacG"
+        );
+        // 环状序列末尾的 C 与开头的 G 组成 CpG
+        let circular = Sequence::new_circular(BioType::Dna, String::from("GATC"));
+        let converted = circular.bisulfite_convert(Strand::Forward).unwrap();
+        assert_eq!(converted.seq, "GATC");
+        assert!(converted.is_circular());
+        assert_eq!(
+            circular.bisulfite_convert(Strand::Reverse).unwrap().seq,
+            "GATC"
+        );
+
+        let rna = Sequence::new(BioType::Rna, String::from("CCGA"));
+        assert_eq!(rna.bisulfite_convert(Strand::Forward).unwrap().seq, "UCGA");
+        assert!(rna.bisulfite_convert(Strand::Reverse).is_err());
+        assert!(dna.bisulfite_convert(Strand::Both).is_err());
+
+        assert_eq!(
+            dna.bisulfite_convert_with_methylation(&[4]).unwrap().seq,
+            "ATGTCTAgtG"
+        );
+        assert!(dna.bisulfite_convert_with_methylation(&[10]).is_err());
+        assert!(Sequence::new(BioType::Protein, String::from("MC"))
+            .bisulfite_convert(Strand::Forward)
+            .is_err());
+    }
+}
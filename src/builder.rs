@@ -0,0 +1,112 @@
+use super::sequence::{BioType, Sequence, ValidationError};
+
+/// 以链式调用构建 `Sequence`
+/// 未指定类型时在 build 时根据字符推断，例如 `SequenceBuilder::new().dna().seq("ATCG").validate().build()`
+#[derive(Debug, Clone, Default)]
+pub struct SequenceBuilder {
+    biotype: Option<BioType>,
+    seq: String,
+    validate: bool,
+}
+
+impl SequenceBuilder {
+    /// 新建空的构建器
+    pub fn new() -> Self {
+        SequenceBuilder::default()
+    }
+
+    /// 指定序列类型，不指定时由 `BioType::infer` 推断
+    pub fn biotype(mut self, biotype: BioType) -> Self {
+        self.biotype = Some(biotype);
+        self
+    }
+
+    /// 指定为 DNA 序列
+    pub fn dna(self) -> Self {
+        self.biotype(BioType::Dna)
+    }
+
+    /// 指定为 RNA 序列
+    pub fn rna(self) -> Self {
+        self.biotype(BioType::Rna)
+    }
+
+    /// 指定为蛋白质序列
+    pub fn protein(self) -> Self {
+        self.biotype(BioType::Protein)
+    }
+
+    /// 设置序列字符串，接受 `&str` 与 `String`
+    pub fn seq(mut self, seq: impl Into<String>) -> Self {
+        self.seq = seq.into();
+        self
+    }
+
+    /// 以字节设置序列，每个字节视为一个字符
+    pub fn bytes(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.seq = bytes.as_ref().iter().map(|&b| b as char).collect();
+        self
+    }
+
+    /// 从字符迭代器设置序列
+    pub fn chars(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        self.seq = chars.into_iter().collect();
+        self
+    }
+
+    /// 在 build 时检查字符是否符合序列类型，见 `Sequence::validate`
+    pub fn validate(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+
+    /// 构建序列，要求检查时返回第一个非法字符
+    pub fn build(self) -> Result<Sequence, ValidationError> {
+        let biotype = self.biotype.unwrap_or_else(|| BioType::infer(&self.seq));
+        let sequence = Sequence::new(biotype, self.seq);
+        if self.validate {
+            sequence.validate()?;
+        }
+        Ok(sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_with_inference() {
+        let protein = SequenceBuilder::new()
+            .seq(String::from("MKV*"))
+            .build()
+            .unwrap();
+        assert_eq!(protein.biotype, BioType::Protein);
+        let peptide = SequenceBuilder::new().seq("MKVHRDSW").build().unwrap();
+        assert_eq!(peptide.biotype, BioType::Protein);
+        let dna = SequenceBuilder::new().bytes(b"ACGT").build().unwrap();
+        assert_eq!(dna, Sequence::new(BioType::Dna, String::from("ACGT")));
+        let rna = SequenceBuilder::new().chars("AUG".chars()).build().unwrap();
+        assert_eq!(rna.biotype, BioType::Rna);
+    }
+
+    #[test]
+    fn build_with_validation() {
+        let err = SequenceBuilder::new()
+            .rna()
+            .seq("AUGT")
+            .validate()
+            .build()
+            .unwrap_err();
+        assert_eq!(err.position, 3);
+        assert_eq!(err.base, 'T');
+        // 不调用 validate 时不检查
+        assert!(SequenceBuilder::new().rna().seq("AUGT").build().is_ok());
+        assert!(SequenceBuilder::new()
+            .dna()
+            .bytes(vec![b'A', 0xff])
+            .validate()
+            .build()
+            .is_err());
+    }
+}
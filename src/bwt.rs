@@ -0,0 +1,224 @@
+use super::sequence::Sequence;
+use super::suffix::{rank_chars, sa_is};
+
+// Burrows-Wheeler 变换与 FM-index
+// FM-index 是 BWA、Bowtie 等短序列比对工具使用的全文索引
+
+/// FM-index 中每隔多少个字符保存一次 occ 计数
+const OCC_INTERVAL: usize = 64;
+
+/// 对序列做 Burrows-Wheeler 变换 (按循环移位排序，不添加终止符)，字符按大写处理
+/// 返回变换后的序列以及原序列在排好序的循环移位中所在的行
+pub fn bwt(seq: &Sequence) -> (Sequence, usize) {
+    let chars: Vec<char> = seq.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let n = chars.len();
+    if n == 0 {
+        return (Sequence::new(seq.biotype.clone(), String::new()), 0);
+    }
+    // 序列自身拼接后，起点小于 n 的后缀顺序即为循环移位的顺序
+    let doubled: Vec<char> = chars.iter().chain(chars.iter()).cloned().collect();
+    let (ranks, upper) = rank_chars(&doubled);
+    let rotations: Vec<usize> = sa_is(&ranks, upper)
+        .into_iter()
+        .filter(|&start| start < n)
+        .collect();
+    let primary = rotations.iter().position(|&start| start == 0).unwrap_or(0);
+    let transformed = rotations
+        .iter()
+        .map(|&start| chars[(start + n - 1) % n])
+        .collect();
+    (Sequence::new(seq.biotype.clone(), transformed), primary)
+}
+
+/// Burrows-Wheeler 逆变换，`primary` 为 `bwt` 返回的行号
+/// `primary` 超出序列长度时会 panic
+pub fn ibwt(bwt: &Sequence, primary: usize) -> Sequence {
+    let last: Vec<char> = bwt.seq.chars().collect();
+    let n = last.len();
+    if n == 0 {
+        return bwt.clone();
+    }
+    if primary >= n {
+        panic!("BWT 的行号 {} 超出序列长度 {}", primary, n);
+    }
+    // LF 映射：last 中第 k 次出现的字符 c 对应首列中第 k 个 c
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| last[i]);
+    let mut lf = vec![0; n];
+    for (row, &i) in order.iter().enumerate() {
+        lf[i] = row;
+    }
+    let mut seq = vec![' '; n];
+    let mut row = primary;
+    for position in (0..n).rev() {
+        seq[position] = last[row];
+        row = lf[row];
+    }
+    Sequence::new(bwt.biotype.clone(), seq.into_iter().collect())
+}
+
+/// FM-index 全文索引，查询不区分大小写
+#[derive(Debug, Clone)]
+pub struct FmIndex {
+    /// 序列中出现的字符，按字典序排列，编号从 1 开始，0 为终止符
+    alphabet: Vec<char>,
+    /// 添加终止符后的 BWT，保存字符编号
+    bwt: Vec<usize>,
+    /// `c[x]` 为编号小于 x 的字符总数
+    c: Vec<usize>,
+    /// 每隔 `OCC_INTERVAL` 个位置保存的各字符累计出现次数
+    checkpoints: Vec<Vec<usize>>,
+    /// 添加终止符后的后缀数组
+    sa: Vec<usize>,
+}
+
+impl FmIndex {
+    /// 为序列构建 FM-index
+    pub fn build(seq: &Sequence) -> FmIndex {
+        let chars: Vec<char> = seq.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let mut alphabet = chars.clone();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let (mut text, _) = rank_chars(&chars);
+        for symbol in text.iter_mut() {
+            *symbol += 1;
+        }
+        text.push(0);
+        let sigma = alphabet.len() + 1;
+        let sa = sa_is(&text, sigma - 1);
+        let bwt: Vec<usize> = sa
+            .iter()
+            .map(|&i| if i == 0 { 0 } else { text[i - 1] })
+            .collect();
+
+        let mut c = vec![0; sigma + 1];
+        for &symbol in text.iter() {
+            c[symbol + 1] += 1;
+        }
+        for x in 1..=sigma {
+            c[x] += c[x - 1];
+        }
+        let mut checkpoints = Vec::with_capacity(bwt.len() / OCC_INTERVAL + 1);
+        let mut counts = vec![0; sigma];
+        for (i, &symbol) in bwt.iter().enumerate() {
+            if i % OCC_INTERVAL == 0 {
+                checkpoints.push(counts.clone());
+            }
+            counts[symbol] += 1;
+        }
+        // 长度为 OCC_INTERVAL 的整数倍时，occ(_, bwt.len()) 需要末尾的检查点
+        if bwt.len() % OCC_INTERVAL == 0 {
+            checkpoints.push(counts);
+        }
+        FmIndex {
+            alphabet,
+            bwt,
+            c,
+            checkpoints,
+            sa,
+        }
+    }
+
+    /// 编号为 `symbol` 的字符在 `bwt[..i]` 中出现的次数
+    fn occ(&self, symbol: usize, i: usize) -> usize {
+        let checkpoint = i / OCC_INTERVAL;
+        let start = checkpoint * OCC_INTERVAL;
+        self.checkpoints[checkpoint][symbol]
+            + self.bwt[start..i].iter().filter(|&&x| x == symbol).count()
+    }
+
+    /// 反向搜索，返回所有以 pattern 开头的后缀在后缀数组中的区间
+    fn backward_search(&self, pattern: &str) -> Option<(usize, usize)> {
+        let (mut lower, mut upper) = (0, self.bwt.len());
+        for c in pattern.chars().rev() {
+            let symbol = self.alphabet.binary_search(&c.to_ascii_uppercase()).ok()? + 1;
+            lower = self.c[symbol] + self.occ(symbol, lower);
+            upper = self.c[symbol] + self.occ(symbol, upper);
+            if lower >= upper {
+                return None;
+            }
+        }
+        Some((lower, upper))
+    }
+
+    /// pattern 出现的次数 (允许重叠)，时间复杂度 O(m)，空 pattern 返回 0
+    pub fn count(&self, pattern: &str) -> usize {
+        if pattern.is_empty() {
+            return 0;
+        }
+        self.backward_search(pattern)
+            .map_or(0, |(lower, upper)| upper - lower)
+    }
+
+    /// pattern 所有出现的位置 (允许重叠)，按位置从小到大排序，空 pattern 返回空数组
+    pub fn locate(&self, pattern: &str) -> Vec<usize> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        let mut positions = match self.backward_search(pattern) {
+            Some((lower, upper)) => self.sa[lower..upper].to_vec(),
+            None => Vec::new(),
+        };
+        positions.sort_unstable();
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn bwt_round_trip() {
+        let banana = Sequence::new(BioType::Rna, String::from("banana"));
+        let (transformed, primary) = bwt(&banana);
+        assert_eq!(transformed.seq, "NNBAAA");
+        assert_eq!(primary, 3);
+        assert_eq!(ibwt(&transformed, primary).seq, "BANANA");
+
+        for text in ["ACGTACGT", "AAAA", "GATTACA", "A", ""] {
+            let seq = Sequence::new(BioType::Dna, String::from(text));
+            let (transformed, primary) = bwt(&seq);
+            assert_eq!(ibwt(&transformed, primary), seq);
+        }
+    }
+
+    #[test]
+    fn fm_index_queries() {
+        let text = "GATTACAgattacaTTAGGATTACA".repeat(5);
+        let seq = Sequence::new(BioType::Dna, text.clone());
+        let index = FmIndex::build(&seq);
+        let expected: Vec<usize> = (0..text.len())
+            .filter(|&i| text[i..].to_ascii_uppercase().starts_with("ATTACA"))
+            .collect();
+        assert_eq!(index.count("attaca"), 15);
+        assert_eq!(index.locate("ATTACA"), expected);
+        assert_eq!(index.count("TTAG"), 5);
+        assert_eq!(index.count("GGG"), 0);
+        assert_eq!(index.count("ACGN"), 0);
+        assert!(index.locate("").is_empty());
+    }
+
+    #[test]
+    fn fm_index_checkpoint_boundaries() {
+        // 加上终止符后 BWT 长度为 64、65、128
+        for len in [63, 64, 127] {
+            let text: String = (0..len)
+                .map(|i| ['A', 'C', 'G', 'T'][(i * i + 3 * i) % 7 % 4])
+                .collect();
+            let index = FmIndex::build(&Sequence::new(BioType::Dna, text.clone()));
+            for pattern in ["A", "C", "GT", "ACG", "TTA", &text[..5], &text[len - 4..]] {
+                let expected: Vec<usize> = (0..text.len())
+                    .filter(|&i| text[i..].starts_with(pattern))
+                    .collect();
+                assert_eq!(index.count(pattern), expected.len());
+                assert_eq!(index.locate(pattern), expected);
+            }
+            assert_eq!(index.locate(&text), vec![0]);
+        }
+        let index = FmIndex::build(&Sequence::new(BioType::Dna, "A".repeat(63)));
+        assert_eq!(index.count("A"), 63);
+        assert_eq!(index.count("AA"), 62);
+    }
+}
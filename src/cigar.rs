@@ -0,0 +1,299 @@
+use super::sequence::Sequence;
+
+use std::str::FromStr;
+use std::{error, fmt};
+
+// SAM 格式中的 CIGAR 字符串
+
+/// CIGAR 中的一种操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CigarOp {
+    /// M：比对上 (可能相同也可能不同)
+    Match,
+    /// I：相对参考序列的插入
+    Insertion,
+    /// D：相对参考序列的缺失
+    Deletion,
+    /// N：跳过参考序列中的区域，例如内含子
+    Skip,
+    /// S：软剪切，碱基保留在序列中
+    SoftClip,
+    /// H：硬剪切，碱基不在序列中
+    HardClip,
+    /// P：填充
+    Padding,
+    /// =：比对上且相同
+    Equal,
+    /// X：比对上但不同
+    Diff,
+}
+
+impl CigarOp {
+    /// 从 CIGAR 字符解析操作
+    pub fn from_char(ch: char) -> Option<CigarOp> {
+        match ch {
+            'M' => Some(CigarOp::Match),
+            'I' => Some(CigarOp::Insertion),
+            'D' => Some(CigarOp::Deletion),
+            'N' => Some(CigarOp::Skip),
+            'S' => Some(CigarOp::SoftClip),
+            'H' => Some(CigarOp::HardClip),
+            'P' => Some(CigarOp::Padding),
+            '=' => Some(CigarOp::Equal),
+            'X' => Some(CigarOp::Diff),
+            _ => None,
+        }
+    }
+
+    /// 对应的 CIGAR 字符
+    pub fn to_char(self) -> char {
+        match self {
+            CigarOp::Match => 'M',
+            CigarOp::Insertion => 'I',
+            CigarOp::Deletion => 'D',
+            CigarOp::Skip => 'N',
+            CigarOp::SoftClip => 'S',
+            CigarOp::HardClip => 'H',
+            CigarOp::Padding => 'P',
+            CigarOp::Equal => '=',
+            CigarOp::Diff => 'X',
+        }
+    }
+
+    /// 是否消耗查询序列
+    pub fn consumes_query(self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match
+                | CigarOp::Insertion
+                | CigarOp::SoftClip
+                | CigarOp::Equal
+                | CigarOp::Diff
+        )
+    }
+
+    /// 是否消耗参考序列
+    pub fn consumes_reference(self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match | CigarOp::Deletion | CigarOp::Skip | CigarOp::Equal | CigarOp::Diff
+        )
+    }
+}
+
+/// 解析或应用 CIGAR 时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CigarError {
+    /// 未知的操作字符
+    InvalidOperation { position: usize, op: char },
+    /// 操作前缺少长度
+    MissingLength { position: usize },
+    /// 末尾的长度之后缺少操作
+    MissingOperation,
+    /// CIGAR 需要的长度与序列长度不符
+    LengthMismatch {
+        query_len: usize,
+        query_expected: usize,
+        reference_len: usize,
+        reference_expected: usize,
+    },
+}
+
+impl fmt::Display for CigarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CigarError::InvalidOperation { position, op } => {
+                write!(
+                    f,
+                    "第 {} 个字符 '{}' 不是合法的 CIGAR 操作",
+                    position + 1,
+                    op
+                )
+            }
+            CigarError::MissingLength { position } => {
+                write!(f, "第 {} 个字符处的 CIGAR 操作缺少长度", position + 1)
+            }
+            CigarError::MissingOperation => write!(f, "CIGAR 末尾的长度之后缺少操作"),
+            CigarError::LengthMismatch {
+                query_len,
+                query_expected,
+                reference_len,
+                reference_expected,
+            } => write!(
+                f,
+                "CIGAR 需要 {} 个查询碱基与至少 {} 个参考碱基，实际为 {} 与 {}",
+                query_expected, reference_expected, query_len, reference_len
+            ),
+        }
+    }
+}
+
+impl error::Error for CigarError {}
+
+/// 由 CIGAR 重建的两条带空位的比对字符串
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlignedPair {
+    pub query: String,
+    pub reference: String,
+}
+
+/// 解析后的 CIGAR，由 (长度, 操作) 组成
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cigar {
+    pub ops: Vec<(usize, CigarOp)>,
+}
+
+impl Cigar {
+    /// 比对在参考序列上覆盖的长度 (M D N = X)
+    pub fn reference_length(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| op.consumes_reference())
+            .map(|(len, _)| len)
+            .sum()
+    }
+
+    /// 查询序列的长度 (M I S = X)，即 SAM 中 SEQ 的长度
+    pub fn query_length(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| op.consumes_query())
+            .map(|(len, _)| len)
+            .sum()
+    }
+
+    /// 由查询序列与参考序列重建比对，参考序列应从比对起点 (SAM 中的 POS) 开始
+    /// 插入与缺失处以 `-` 补齐，N 跳过的参考区域在查询中以 `.` 表示
+    /// 软剪切的碱基不属于比对，H 与 P 不产生任何列
+    /// 查询序列长度与 CIGAR 不符或参考序列不够长时返回错误
+    pub fn apply(&self, seq: &Sequence, reference: &Sequence) -> Result<AlignedPair, CigarError> {
+        let query: Vec<char> = seq.seq.chars().collect();
+        let target: Vec<char> = reference.seq.chars().collect();
+        if query.len() != self.query_length() || target.len() < self.reference_length() {
+            return Err(CigarError::LengthMismatch {
+                query_len: query.len(),
+                query_expected: self.query_length(),
+                reference_len: target.len(),
+                reference_expected: self.reference_length(),
+            });
+        }
+
+        let mut pair = AlignedPair {
+            query: String::new(),
+            reference: String::new(),
+        };
+        let (mut q, mut r) = (0, 0);
+        for &(len, op) in &self.ops {
+            match op {
+                CigarOp::Match | CigarOp::Equal | CigarOp::Diff => {
+                    pair.query.extend(&query[q..q + len]);
+                    pair.reference.extend(&target[r..r + len]);
+                }
+                CigarOp::Insertion => {
+                    pair.query.extend(&query[q..q + len]);
+                    pair.reference.extend(std::iter::repeat_n('-', len));
+                }
+                CigarOp::Deletion => {
+                    pair.query.extend(std::iter::repeat_n('-', len));
+                    pair.reference.extend(&target[r..r + len]);
+                }
+                CigarOp::Skip => {
+                    pair.query.extend(std::iter::repeat_n('.', len));
+                    pair.reference.extend(&target[r..r + len]);
+                }
+                CigarOp::SoftClip | CigarOp::HardClip | CigarOp::Padding => {}
+            }
+            if op.consumes_query() {
+                q += len;
+            }
+            if op.consumes_reference() {
+                r += len;
+            }
+        }
+        Ok(pair)
+    }
+}
+
+/// 解析 CIGAR 字符串，SAM 中表示缺失的 `*` 与空字符串解析为空的 CIGAR
+impl FromStr for Cigar {
+    type Err = CigarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cigar = Cigar::default();
+        if s == "*" {
+            return Ok(cigar);
+        }
+        let mut len: Option<usize> = None;
+        for (position, ch) in s.chars().enumerate() {
+            if let Some(digit) = ch.to_digit(10) {
+                len = Some(len.unwrap_or(0) * 10 + digit as usize);
+                continue;
+            }
+            let op =
+                CigarOp::from_char(ch).ok_or(CigarError::InvalidOperation { position, op: ch })?;
+            let len = len.take().ok_or(CigarError::MissingLength { position })?;
+            cigar.ops.push((len, op));
+        }
+        if len.is_some() {
+            return Err(CigarError::MissingOperation);
+        }
+        Ok(cigar)
+    }
+}
+
+impl fmt::Display for Cigar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ops.is_empty() {
+            return write!(f, "*");
+        }
+        for (len, op) in &self.ops {
+            write!(f, "{}{}", len, op.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn parse_cigar() {
+        let cigar: Cigar = "2S3M1I2M2D1N2=1X5H".parse().unwrap();
+        assert_eq!(cigar.ops.len(), 9);
+        assert_eq!(cigar.ops[0], (2, CigarOp::SoftClip));
+        assert_eq!(cigar.reference_length(), 3 + 2 + 2 + 1 + 2 + 1);
+        assert_eq!(cigar.query_length(), 2 + 3 + 1 + 2 + 2 + 1);
+        assert_eq!(cigar.to_string(), "2S3M1I2M2D1N2=1X5H");
+        assert_eq!(Cigar::from_str("*").unwrap().to_string(), "*");
+        assert_eq!(
+            Cigar::from_str("3M2Q"),
+            Err(CigarError::InvalidOperation {
+                position: 3,
+                op: 'Q'
+            })
+        );
+        assert_eq!(
+            Cigar::from_str("M"),
+            Err(CigarError::MissingLength { position: 0 })
+        );
+        assert_eq!(Cigar::from_str("3M2"), Err(CigarError::MissingOperation));
+    }
+
+    #[test]
+    fn apply_cigar() {
+        let cigar: Cigar = "2S3M1I2M2D2N2M".parse().unwrap();
+        let query = Sequence::new(BioType::Dna, String::from("TTACGTAAGG"));
+        let reference = Sequence::new(BioType::Dna, String::from("ACGAACCGTGGAAA"));
+        let pair = cigar.apply(&query, &reference).unwrap();
+        assert_eq!(pair.query, "ACGTAA--..GG");
+        assert_eq!(pair.reference, "ACG-AACCGTGG");
+
+        let short = Sequence::new(BioType::Dna, String::from("ACG"));
+        assert!(matches!(
+            cigar.apply(&query, &short),
+            Err(CigarError::LengthMismatch { .. })
+        ));
+    }
+}
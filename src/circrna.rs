@@ -0,0 +1,383 @@
+use super::sequence::{count_kmers, find_positions_circular, kmer_frequency, Sequence};
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+// CircRNA 相关的数据结构与操作
+// 环状序列以线性形式存储，末尾与开头相接即为反向剪接位点 (BSJ)
+
+/// 环状序列
+/// 按基因组方向存储成环的外显子序列，最后一个碱基 (donor 侧) 与第一个碱基 (acceptor 侧) 相连
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircSequence {
+    pub sequence: Sequence,
+}
+
+impl CircSequence {
+    /// 新建 CircSequence 对象
+    pub fn new(sequence: Sequence) -> Self {
+        CircSequence { sequence }
+    }
+
+    /// 返回长度
+    pub fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    /// 序列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// 获取对应索引的字符，索引超过长度时绕回开头
+    /// 空序列会 panic
+    pub fn index(&self, index: usize) -> char {
+        self.sequence.index(index % self.len())
+    }
+
+    /// 截取一段区间，区间终点可以超过长度，此时跨过接头从开头继续
+    /// 例如长度为 10 时 `8..12` 得到位置 8、9、0、1 的碱基
+    pub fn subsequence(&self, range: Range<usize>) -> Sequence {
+        if range.start > range.end {
+            panic!("区间起点 {} 大于终点 {}", range.start, range.end);
+        }
+        let len = self.len();
+        if range.is_empty() {
+            return self.sequence.subsequence(0..0);
+        }
+        if len == 0 {
+            panic!("不能从空的环状序列中截取区间");
+        }
+        let mut seq = String::with_capacity(range.len());
+        let mut position = range.start;
+        while position < range.end {
+            let start = position % len;
+            let end = (start + range.end - position).min(len);
+            seq.push_str(&self.sequence.seq[start..end]);
+            position += end - start;
+        }
+        Sequence::new(self.sequence.biotype.clone(), seq)
+    }
+
+    /// 从 `start` 处打开环，得到对应的线性序列
+    pub fn linearize(&self, start: usize) -> Sequence {
+        if self.is_empty() {
+            return self.sequence.clone();
+        }
+        let start = start % self.len();
+        self.subsequence(start..start + self.len())
+    }
+
+    /// 提取跨越反向剪接位点的序列
+    /// 从 donor 侧的位置 `donor` 开始，跨过接头，到 acceptor 侧的位置 `acceptor` (不含) 结束
+    /// 例如 `back_splice_junction(len - 20, 20)` 得到以 BSJ 为中心的 40 个碱基
+    pub fn back_splice_junction(&self, donor: usize, acceptor: usize) -> Sequence {
+        let len = self.len();
+        if donor >= len || acceptor > donor {
+            panic!(
+                "反向剪接位点两侧的位置无效: donor {}, acceptor {}, 长度 {}",
+                donor, acceptor, len
+            );
+        }
+        self.subsequence(donor..len + acceptor)
+    }
+
+    /// 查找 pattern 所有互不重叠的出现位置，匹配可以跨过接头，不区分大小写
+    /// 跨接头的匹配若与开头的匹配重叠则不计入
+    pub fn find_all(&self, pattern: &str) -> Vec<usize> {
+        find_positions_circular(&self.sequence.seq, pattern, false)
+    }
+
+    /// 查找 pattern 的所有出现位置，允许重叠，匹配可以跨过接头
+    pub fn find_all_overlapping(&self, pattern: &str) -> Vec<usize> {
+        find_positions_circular(&self.sequence.seq, pattern, true)
+    }
+
+    /// 统计环状序列中的 k-mer，末尾的 k-1 个碱基会与开头相接，因此共有 len 个 k-mer
+    /// k 为 0 或大于序列长度时返回空表
+    pub fn kmer_count(&self, k: usize) -> HashMap<String, usize> {
+        if k > self.len() {
+            return HashMap::new();
+        }
+        let chars: Vec<char> = self
+            .sequence
+            .seq
+            .chars()
+            .chain(self.sequence.seq.chars().take(k.saturating_sub(1)))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        count_kmers(&chars, k)
+    }
+
+    /// 环状序列中 k-mer 出现的频率
+    pub fn kmer_frequency(&self, k: usize) -> HashMap<String, f64> {
+        kmer_frequency(self.kmer_count(k))
+    }
+}
+
+impl From<Sequence> for CircSequence {
+    fn from(sequence: Sequence) -> Self {
+        CircSequence::new(sequence)
+    }
+}
+
+/// 从基因组序列中提取跨越反向剪接位点的序列
+/// `donor_exon_end` 为下游外显子 (donor 侧) 的终点 (不含)，`acceptor_exon_start` 为上游外显子 (acceptor 侧) 的起点，
+/// 坐标以 0 开始；结果为 acceptor 侧开头的 `junction_flank` 个碱基接上 donor 侧末端的 `junction_flank` 个碱基，
+/// 与 `CircSequence` 一样按基因组方向排列，BSJ 位于结果的末尾与开头相接处，
+/// 转录本中跨越 BSJ 读到的序列为后半段接上前半段
+/// 两侧的区间超出基因组范围时会 panic
+pub fn extract_bsj_sequence(
+    genome: &Sequence,
+    donor_exon_end: usize,
+    acceptor_exon_start: usize,
+    junction_flank: usize,
+) -> Sequence {
+    if junction_flank > donor_exon_end || acceptor_exon_start + junction_flank > genome.len() {
+        panic!(
+            "BSJ 两侧的区间超出序列范围: donor {}, acceptor {}, flank {}, 长度 {}",
+            donor_exon_end,
+            acceptor_exon_start,
+            junction_flank,
+            genome.len()
+        );
+    }
+    genome.subsequence(acceptor_exon_start..acceptor_exon_start + junction_flank)
+        + genome
+            .subsequence(donor_exon_end - junction_flank..donor_exon_end)
+            .seq
+}
+
+/// 统计支持反向剪接位点的 reads 数
+/// `bsj_seq` 的前半段为 acceptor 侧外显子的开头，后半段为 donor 侧外显子的末端 (例如 `extract_bsj_sequence` 的结果)，
+/// read 或其反向互补序列中包含 donor 侧最后 `min_overlap` 个碱基接上 acceptor 侧最前 `min_overlap` 个碱基时计为支持，
+/// 不区分大小写，T 与 U 视为相同
+/// `min_overlap` 为 0 或超过任一侧的长度时返回 0
+pub fn bsj_score(reads: &[Sequence], bsj_seq: &Sequence, min_overlap: usize) -> usize {
+    let half = bsj_seq.len() / 2;
+    if min_overlap == 0 || min_overlap > half || half + min_overlap > bsj_seq.len() {
+        return 0;
+    }
+    let normalize = |seq: &str| seq.to_ascii_uppercase().replace('U', "T");
+    let core = normalize(
+        &(bsj_seq.subsequence(bsj_seq.len() - min_overlap..bsj_seq.len())
+            + bsj_seq.subsequence(0..min_overlap).seq)
+            .seq,
+    );
+    reads
+        .iter()
+        .filter(|read| {
+            normalize(&read.seq).contains(&core)
+                || read
+                    .reverse_complementary()
+                    .is_ok_and(|reverse| normalize(&reverse.seq).contains(&core))
+        })
+        .count()
+}
+
+/// `bsj_coverage` 的统计结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverageResult {
+    /// 跨越反向剪接位点的 reads 数
+    pub bsj_reads: usize,
+    /// 跨越同一 donor 或 acceptor 位点、但以线性方式连接的 reads 数
+    pub linear_reads: usize,
+    /// `bsj_reads / (bsj_reads + linear_reads)`，两者都为 0 时为 0
+    pub bsj_ratio: f64,
+}
+
+/// read 在 BSJ 处的比对结果
+enum JunctionSpan {
+    BackSplice,
+    Linear,
+}
+
+/// 统计跨越反向剪接位点与对应线性剪接位点的 reads 数，用于估计 CircRNA 的相对表达量
+/// `bsj_sequence` 的前半段为 acceptor 侧外显子的开头，后半段为 donor 侧外显子的末端 (例如 `extract_bsj_sequence` 的结果)，
+/// BSJ 为后半段的末尾接上前半段的开头；read 或其反向互补序列按无空位比对：
+/// - BSJ 两侧各有至少 `min_overlap` 个碱基与 `bsj_sequence` 相符时计为 BSJ read
+/// - 只有一侧在 BSJ 处相符 (至少 `min_overlap` 个碱基)，另一侧接上的至少 `min_overlap` 个碱基
+///   与 `bsj_sequence` 不符时，说明 read 经由 donor 或 acceptor 位点线性连接到其他序列，计为线性 read
+///
+/// 每一侧的错配数不超过 `max_mismatches` 时视为相符，同一条 read 只计一次且优先计为 BSJ read；
+/// 不区分大小写，T 与 U 视为相同，比对时只比较 read 落在 `bsj_sequence` 范围内的部分
+/// `min_overlap` 为 0 或超过 BSJ 任一侧的长度时结果全为 0
+pub fn bsj_coverage(
+    reads: &[Sequence],
+    bsj_sequence: &Sequence,
+    min_overlap: usize,
+    max_mismatches: usize,
+) -> CoverageResult {
+    let normalize = |seq: &str| -> Vec<char> {
+        seq.chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'U' => 'T',
+                c => c,
+            })
+            .collect()
+    };
+    let bsj = normalize(&bsj_sequence.seq);
+    // 跨越 BSJ 时先读到 donor 侧，再读到 acceptor 侧
+    let (right, left) = bsj.split_at(bsj.len() / 2);
+    let (mut bsj_reads, mut linear_reads) = (0, 0);
+    if min_overlap > 0 && min_overlap <= right.len().min(left.len()) {
+        for read in reads {
+            let forward = normalize(&read.seq);
+            let reverse = read
+                .reverse_complementary()
+                .map(|reverse| normalize(&reverse.seq))
+                .unwrap_or_default();
+            let spans = [&forward, &reverse]
+                .map(|read| junction_span(read, left, right, min_overlap, max_mismatches));
+            if spans
+                .iter()
+                .any(|span| matches!(span, Some(JunctionSpan::BackSplice)))
+            {
+                bsj_reads += 1;
+            } else if spans
+                .iter()
+                .any(|span| matches!(span, Some(JunctionSpan::Linear)))
+            {
+                linear_reads += 1;
+            }
+        }
+    }
+    let total = bsj_reads + linear_reads;
+    CoverageResult {
+        bsj_reads,
+        linear_reads,
+        bsj_ratio: if total == 0 {
+            0.0
+        } else {
+            bsj_reads as f64 / total as f64
+        },
+    }
+}
+
+/// 依次尝试把 read 的第 i 个碱基对齐到 BSJ，判断 read 是否跨越反向剪接位点或线性连接
+fn junction_span(
+    read: &[char],
+    left: &[char],
+    right: &[char],
+    min_overlap: usize,
+    max_mismatches: usize,
+) -> Option<JunctionSpan> {
+    let mismatches = |a: &[char], b: &[char]| a.iter().zip(b).filter(|(x, y)| x != y).count();
+    let mut linear = false;
+    for i in min_overlap..=read.len().saturating_sub(min_overlap) {
+        let before = i.min(left.len());
+        let after = (read.len() - i).min(right.len());
+        let left_match =
+            mismatches(&read[i - before..i], &left[left.len() - before..]) <= max_mismatches;
+        let right_match = mismatches(&read[i..i + after], &right[..after]) <= max_mismatches;
+        match (left_match, right_match) {
+            (true, true) => return Some(JunctionSpan::BackSplice),
+            (true, false) | (false, true) => linear = true,
+            (false, false) => {}
+        }
+    }
+    linear.then_some(JunctionSpan::Linear)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn circular_access() {
+        let circ = CircSequence::new(Sequence::new(BioType::Rna, String::from("AUGGCCAUUG")));
+        assert_eq!(circ.index(12), 'G');
+        assert_eq!(circ.subsequence(8..12).seq, "UGAU");
+        assert_eq!(circ.subsequence(2..5).seq, "GGC");
+        assert_eq!(circ.subsequence(9..31).seq, "GAUGGCCAUUGAUGGCCAUUGA");
+        assert_eq!(circ.linearize(7).seq, "UUGAUGGCCA");
+        assert_eq!(circ.linearize(0), circ.sequence);
+        assert_eq!(circ.back_splice_junction(7, 3).seq, "UUGAUG");
+        assert_eq!(circ.back_splice_junction(7, 3).biotype, BioType::Rna);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_junction() {
+        let circ = CircSequence::new(Sequence::new(BioType::Rna, String::from("AUGG")));
+        circ.back_splice_junction(1, 2);
+    }
+
+    #[test]
+    fn circular_kmers() {
+        let circ = CircSequence::new(Sequence::new(BioType::Dna, String::from("AACG")));
+        let counts = circ.kmer_count(3);
+        assert_eq!(counts.values().sum::<usize>(), 4);
+        assert_eq!(counts["CGA"], 1);
+        assert_eq!(counts["GAA"], 1);
+        assert!(circ.kmer_count(5).is_empty());
+        let total: f64 = circ.kmer_frequency(2).values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn circular_find_all() {
+        let circ = CircSequence::new(Sequence::new(BioType::Dna, String::from("TTCAAAGAA")));
+        assert_eq!(circ.find_all("gaattc"), vec![6]);
+        assert_eq!(circ.find_all_overlapping("AA"), vec![3, 4, 7]);
+        assert_eq!(circ.find_all("AA"), vec![3, 7]);
+        let repeat = CircSequence::new(Sequence::new(BioType::Dna, String::from("ATA")));
+        assert_eq!(repeat.find_all_overlapping("AA"), vec![2]);
+        assert_eq!(repeat.find_all("AT"), vec![0]);
+        assert!(repeat.find_all("ATAT").is_empty());
+    }
+
+    #[test]
+    fn bsj_sequence_and_support() {
+        // 上游外显子 10..20，下游外显子 30..40，成环后 39 号碱基与 10 号碱基相连
+        let genome = Sequence::new(
+            BioType::Dna,
+            String::from("NNNNNNNNNNACGTTGCAAGnnnnnnnnnnCCGGAATTCGNNNNNNNNNN"),
+        );
+        let bsj = extract_bsj_sequence(&genome, 40, 10, 4);
+        assert_eq!(bsj.seq, "ACGTTTCG");
+        // 与成环的外显子按基因组方向排列一致，跨越 BSJ 读到的是后半段接上前半段
+        let circ = CircSequence::new(genome.subsequence(10..20) + genome.subsequence(30..40).seq);
+        assert_eq!(circ.back_splice_junction(16, 4).seq, "TTCGACGT");
+        let reads = vec![
+            Sequence::new(BioType::Dna, String::from("GGAATTCGACGTTG")),
+            // 反向互补的 read 同样支持
+            Sequence::new(BioType::Dna, String::from("CAACGTCGAATT")),
+            // 只覆盖 BSJ 一侧
+            Sequence::new(BioType::Dna, String::from("CCGGAATTCG")),
+            Sequence::new(BioType::Rna, String::from("UCGACG")),
+        ];
+        assert_eq!(bsj_score(&reads, &bsj, 3), 3);
+        assert_eq!(bsj_score(&reads, &bsj, 4), 2);
+        assert_eq!(bsj_score(&reads, &bsj, 5), 0);
+        assert_eq!(bsj_score(&reads, &bsj, 0), 0);
+    }
+
+    #[test]
+    fn bsj_and_linear_coverage() {
+        let bsj = Sequence::new(BioType::Dna, String::from("ACGTTGCAGGAATTCG"));
+        let reads = vec![
+            Sequence::new(BioType::Dna, String::from("ATTCGACGTT")),
+            // 反向互补的 read 同样计入
+            Sequence::new(BioType::Dna, String::from("CAACGTCGAA")),
+            // BSJ 右侧有一个错配
+            Sequence::new(BioType::Dna, String::from("ATTCGACCTT")),
+            // donor 侧外显子接上内含子
+            Sequence::new(BioType::Dna, String::from("AATTCGGTAAGT")),
+            // 内含子接上 acceptor 侧外显子
+            Sequence::new(BioType::Rna, String::from("UUUCAGACGUUG")),
+            Sequence::new(BioType::Dna, String::from("CCCCCCCCCC")),
+        ];
+        let strict = bsj_coverage(&reads, &bsj, 4, 0);
+        assert_eq!((strict.bsj_reads, strict.linear_reads), (2, 3));
+        assert!((strict.bsj_ratio - 0.4).abs() < 1e-9);
+        let tolerant = bsj_coverage(&reads, &bsj, 4, 1);
+        assert_eq!((tolerant.bsj_reads, tolerant.linear_reads), (3, 2));
+
+        let none = bsj_coverage(&reads, &bsj, 9, 0);
+        assert_eq!((none.bsj_reads, none.linear_reads), (0, 0));
+        assert_eq!(none.bsj_ratio, 0.0);
+    }
+}
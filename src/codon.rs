@@ -1,3 +1,9 @@
+use super::sequence::{BioType, Sequence};
+
+use std::collections::HashMap;
+
+/// 标准遗传密码表，(RNA 密码子, 氨基酸)
+#[deprecated(note = "使用 `CodonTable::standard()`")]
 pub const CODON_TABLE: [(&str, &str); 64] = [
     ("UUU", "F"),
     ("UCU", "S"),
@@ -64,3 +70,632 @@ pub const CODON_TABLE: [(&str, &str); 64] = [
     ("GAG", "E"),
     ("GGG", "G"),
 ];
+
+// NCBI 遗传密码表
+// 每张表按 TCAG 顺序排列 64 个密码子，第一位碱基变化最慢，第三位变化最快
+// 例如第 0 个为 TTT，第 1 个为 TTC，第 63 个为 GGG
+// 数据来源: https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi
+
+/// 密码子表中碱基的排列顺序
+const BASE_ORDER: [char; 4] = ['U', 'C', 'A', 'G'];
+
+/// (NCBI 编号, 名称, 氨基酸, 起始密码子)
+const NCBI_TABLES: [(u8, &str, &str, &str); 7] = [
+    (
+        1,
+        "Standard",
+        "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M---------------M---------------M----------------------------",
+    ),
+    (
+        2,
+        "Vertebrate Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        "--------------------------------MMMM---------------M------------",
+    ),
+    (
+        4,
+        "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--MM---------------M------------MMMM---------------M------------",
+    ),
+    (
+        5,
+        "Invertebrate Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        "---M----------------------------MMMM---------------M------------",
+    ),
+    (
+        9,
+        "Echinoderm and Flatworm Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        "-----------------------------------M---------------M------------",
+    ),
+    (
+        11,
+        "Bacterial, Archaeal and Plant Plastid",
+        "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M---------------M------------MMMM---------------M------------",
+    ),
+    (
+        16,
+        "Chlorophycean Mitochondrial",
+        "FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "-----------------------------------M----------------------------",
+    ),
+];
+
+/// 人类编码序列的密码子使用频率 (Kazusa 密码子使用数据库，每千个密码子中的次数 × 10)
+/// 顺序与 `BASE_ORDER` 一致，即 UUU、UUC、UUA、UUG、UCU ...
+#[rustfmt::skip]
+const HUMAN_CODON_USAGE: [usize; 64] = [
+    176, 203, 77, 129, // UUN
+    152, 177, 122, 44, // UCN
+    122, 153, 10, 8, // UAN
+    106, 126, 16, 132, // UGN
+    132, 196, 72, 396, // CUN
+    175, 198, 169, 69, // CCN
+    109, 151, 123, 342, // CAN
+    45, 104, 62, 114, // CGN
+    160, 208, 75, 220, // AUN
+    131, 189, 151, 61, // ACN
+    170, 191, 244, 319, // AAN
+    121, 195, 122, 120, // AGN
+    110, 145, 71, 281, // GUN
+    184, 277, 158, 74, // GCN
+    218, 251, 290, 396, // GAN
+    108, 222, 165, 165, // GGN
+];
+
+/// 遗传密码表，密码子可以用 DNA (T) 或 RNA (U) 表示，不区分大小写
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodonTable {
+    pub id: u8,
+    pub name: &'static str,
+    amino_acids: &'static str,
+    starts: &'static str,
+}
+
+impl CodonTable {
+    /// 按 NCBI 编号获取密码子表，目前支持 1、2、4、5、9、11、16
+    pub fn from_ncbi_id(id: u8) -> Result<CodonTable, String> {
+        NCBI_TABLES
+            .iter()
+            .find(|(table_id, ..)| *table_id == id)
+            .map(|&(id, name, amino_acids, starts)| CodonTable {
+                id,
+                name,
+                amino_acids,
+                starts,
+            })
+            .ok_or(format!("不支持的 NCBI 密码子表编号: {}", id))
+    }
+
+    /// 标准密码子表 (NCBI 1)
+    pub fn standard() -> CodonTable {
+        Self::from_ncbi_id(1).unwrap()
+    }
+
+    /// 脊椎动物线粒体密码子表 (NCBI 2)：UGA 编码 Trp，AUA 编码 Met，AGA 与 AGG 为终止密码子
+    pub fn vertebrate_mitochondrial() -> CodonTable {
+        Self::from_ncbi_id(2).unwrap()
+    }
+
+    /// 翻译单个密码子，长度不为 3 或含有非 ACGTU 字符时返回 None
+    pub fn translate_codon(&self, codon: &str) -> Option<char> {
+        codon_index(codon).map(|i| self.amino_acids.as_bytes()[i] as char)
+    }
+
+    /// 是否为终止密码子
+    pub fn is_stop(&self, codon: &str) -> bool {
+        self.translate_codon(codon) == Some('*')
+    }
+
+    /// 是否为起始密码子
+    pub fn is_start(&self, codon: &str) -> bool {
+        codon_index(codon).is_some_and(|i| self.starts.as_bytes()[i] == b'M')
+    }
+
+    /// 全部 64 个密码子 (RNA 形式) 及其编码的氨基酸
+    pub fn codons(&self) -> Vec<(String, char)> {
+        (0..64)
+            .map(|i| (index_codon(i), self.amino_acids.as_bytes()[i] as char))
+            .collect()
+    }
+
+    /// 编码氨基酸 `aa` (单字母代码，不区分大小写，终止符为 `*`) 的全部密码子 (RNA 形式)
+    /// 不在表中的字符返回空数组
+    pub fn synonymous_codons(&self, aa: char) -> Vec<String> {
+        let aa = aa.to_ascii_uppercase();
+        self.amino_acids
+            .bytes()
+            .enumerate()
+            .filter(|&(_, b)| b as char == aa)
+            .map(|(i, _)| index_codon(i))
+            .collect()
+    }
+
+    /// 与该密码子编码同一氨基酸的密码子个数 (包括自身)，Met 与 Trp 为 1
+    /// 无法识别的密码子返回 0
+    pub fn degeneracy(&self, codon: &str) -> u8 {
+        match self.translate_codon(codon) {
+            Some(aa) => self
+                .amino_acids
+                .bytes()
+                .filter(|&b| b as char == aa)
+                .count() as u8,
+            None => 0,
+        }
+    }
+
+    /// 全部起始密码子 (RNA 形式)
+    pub fn start_codons(&self) -> Vec<String> {
+        (0..64)
+            .filter(|&i| self.starts.as_bytes()[i] == b'M')
+            .map(index_codon)
+            .collect()
+    }
+}
+
+impl Default for CodonTable {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// 一段编码序列的密码子使用情况，使用标准密码子表将密码子归入同义密码子组
+/// 密码子以大写 RNA 形式 (U) 保存
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodonUsage {
+    pub counts: HashMap<String, usize>,
+    pub table: CodonTable,
+}
+
+impl CodonUsage {
+    /// 新建空的 CodonUsage 对象
+    pub fn new(table: CodonTable) -> Self {
+        CodonUsage {
+            counts: HashMap::new(),
+            table,
+        }
+    }
+
+    /// 人类编码序列的密码子使用频率 (Kazusa)，以每千个密码子中的次数 × 10 作为计数，使用标准密码子表
+    pub fn human() -> Self {
+        CodonUsage {
+            counts: (0..64)
+                .map(|i| (index_codon(i), HUMAN_CODON_USAGE[i]))
+                .collect(),
+            table: CodonTable::standard(),
+        }
+    }
+
+    /// 从密码子计数新建，密码子可以是 DNA 或 RNA 形式
+    pub fn from_counts(counts: HashMap<String, usize>, table: CodonTable) -> Self {
+        let mut usage = CodonUsage::new(table);
+        for (codon, count) in counts {
+            if let Some(index) = codon_index(&codon) {
+                *usage.counts.entry(index_codon(index)).or_insert(0) += count;
+            }
+        }
+        usage
+    }
+
+    /// 统计一段 DNA 或 RNA 序列 (读码框 0) 中的密码子，含有非 ACGTU 字符的密码子会被忽略
+    pub fn add_sequence(&mut self, seq: &Sequence) -> Result<(), String> {
+        if seq.biotype == BioType::Protein {
+            return Err(format!("不能统计 {} 序列的密码子使用", seq.biotype));
+        }
+        for codon in seq.codons(0)? {
+            if let Some(index) = codon_index(codon) {
+                *self.counts.entry(index_codon(index)).or_insert(0) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// 某个密码子出现的次数
+    pub fn count(&self, codon: &str) -> usize {
+        codon_index(codon)
+            .and_then(|index| self.counts.get(&index_codon(index)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// 密码子总数
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// 相对同义密码子使用度 (RSCU)：观察次数 / 同义密码子平均使用时的期望次数
+    /// 返回全部 64 个密码子，同义密码子组中没有任何观察值时为 0.0
+    pub fn rscu(&self) -> HashMap<String, f64> {
+        let mut family_totals: HashMap<char, (usize, usize)> = HashMap::new();
+        for (codon, amino_acid) in self.table.codons() {
+            let entry = family_totals.entry(amino_acid).or_insert((0, 0));
+            entry.0 += self.count(&codon);
+            entry.1 += 1;
+        }
+        self.table
+            .codons()
+            .into_iter()
+            .map(|(codon, amino_acid)| {
+                let (total, size) = family_totals[&amino_acid];
+                let rscu = if total == 0 {
+                    0.0
+                } else {
+                    self.count(&codon) as f64 * size as f64 / total as f64
+                };
+                (codon, rscu)
+            })
+            .collect()
+    }
+
+    /// 以 `reference` (例如高表达基因) 为参考计算密码子适应指数 (CAI, Sharp & Li 1987)
+    /// 每个密码子的相对适应度 w 为其在参考中的次数除以同义密码子中的最大次数，
+    /// CAI 为本序列所有密码子 w 的几何平均；Met、Trp 等没有同义密码子的氨基酸与终止密码子不参与计算
+    /// 参考中未出现的密码子按 0.5 次计算以避免结果为 0，没有可计算的密码子时返回 0.0
+    pub fn cai(&self, reference: &CodonUsage) -> f64 {
+        let mut family_max: HashMap<char, f64> = HashMap::new();
+        for (codon, amino_acid) in reference.table.codons() {
+            let count = reference.count(&codon) as f64;
+            let max = family_max.entry(amino_acid).or_insert(0.0);
+            *max = max.max(count);
+        }
+
+        let (mut log_sum, mut codons) = (0.0, 0usize);
+        for (codon, &count) in &self.counts {
+            let amino_acid = match reference.table.translate_codon(codon) {
+                Some(aa) if aa != '*' && reference.table.degeneracy(codon) > 1 => aa,
+                _ => continue,
+            };
+            let observed = (reference.count(codon) as f64).max(0.5);
+            let w = observed / family_max[&amino_acid].max(0.5);
+            log_sum += count as f64 * w.ln();
+            codons += count;
+        }
+        if codons == 0 {
+            return 0.0;
+        }
+        (log_sum / codons as f64).exp()
+    }
+}
+
+impl Sequence {
+    /// 统计 DNA 或 RNA 序列在读码框 0 中的密码子使用情况
+    pub fn codon_usage(&self) -> Result<CodonUsage, String> {
+        let mut usage = CodonUsage::new(CodonTable::standard());
+        usage.add_sequence(self)?;
+        Ok(usage)
+    }
+
+    /// 将每个密码子替换为目标物种中最常用的同义密码子，编码的蛋白质不变
+    /// 序列必须是长度为 3 的倍数的 DNA 或 RNA，密码子按 `target_usage` 的密码子表翻译
+    /// 使用次数相同时保留原密码子，否则取表中靠前的密码子；同义密码子都没有出现过时保留原密码子
+    pub fn optimize_codons(&self, target_usage: &CodonUsage) -> Result<Sequence, String> {
+        self.replace_codons(target_usage, |candidates, original| {
+            let max = candidates.iter().map(|&(_, count)| count).max()?;
+            if max == 0 {
+                return None;
+            }
+            if candidates
+                .iter()
+                .any(|(codon, count)| codon == original && *count == max)
+            {
+                return None;
+            }
+            candidates
+                .iter()
+                .find(|&&(_, count)| count == max)
+                .map(|(codon, _)| codon.clone())
+        })
+    }
+
+    /// 将蛋白质反向翻译为 DNA，翻译结果与原蛋白质相同 (`*` 反向翻译为终止密码子)
+    /// 同义密码子由 `codon_table` 决定，每个密码子的权重取自 `usage`
+    /// - `usage` 为 None 时每个氨基酸都使用人类中最常用的密码子 (`CodonUsage::human`)
+    /// - 否则按使用次数的比例随机抽样同义密码子，随机数种子固定，相同的输入总是得到相同的结果；
+    ///   需要自己提供随机数生成器时请使用 `back_translate_stochastic` (`rand` 特性)
+    ///
+    /// 同义密码子都没有出现过时使用表中的第一个；DNA/RNA 序列或含有表中没有的氨基酸时返回错误
+    pub fn back_translate(
+        &self,
+        codon_table: &CodonTable,
+        usage: Option<&CodonUsage>,
+    ) -> Result<Sequence, String> {
+        let usage = match usage {
+            Some(usage) => usage,
+            None => {
+                let human = CodonUsage::human();
+                return self.back_translate_with(codon_table, &human, |candidates, _| {
+                    let max = candidates.iter().map(|&(_, count)| count).max()?;
+                    candidates
+                        .iter()
+                        .find(|&&(_, count)| count == max)
+                        .map(|(codon, _)| codon.clone())
+                });
+            }
+        };
+        let mut state = BACK_TRANSLATE_SEED;
+        self.back_translate_with(codon_table, usage, |candidates, _| {
+            let total: usize = candidates.iter().map(|&(_, count)| count).sum();
+            let mut pick = (splitmix64(&mut state) % total as u64) as usize;
+            for (codon, count) in candidates {
+                if pick < *count {
+                    return Some(codon.clone());
+                }
+                pick -= count;
+            }
+            None
+        })
+    }
+
+    /// 依次为每个氨基酸选择 `choose` 给出的密码子
+    /// `choose` 接收 (同义密码子, 在 `usage` 中的次数) 与大写的氨基酸，只在同义密码子中至少有一个出现过时调用，
+    /// 返回 None 时使用表中的第一个
+    pub(crate) fn back_translate_with(
+        &self,
+        codon_table: &CodonTable,
+        usage: &CodonUsage,
+        mut choose: impl FnMut(&[(String, usize)], char) -> Option<String>,
+    ) -> Result<Sequence, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能对 {} 序列进行反向翻译", BioType::Protein));
+        }
+        let mut seq = String::with_capacity(self.seq.len() * 3);
+        for amino_acid in self.seq.chars().map(|c| c.to_ascii_uppercase()) {
+            let candidates: Vec<(String, usize)> = codon_table
+                .synonymous_codons(amino_acid)
+                .into_iter()
+                .map(|codon| {
+                    let count = usage.count(&codon);
+                    (codon, count)
+                })
+                .collect();
+            if candidates.is_empty() {
+                return Err(format!("密码子表中没有编码 {} 的密码子", amino_acid));
+            }
+            let chosen = if candidates.iter().any(|&(_, count)| count > 0) {
+                choose(&candidates, amino_acid)
+            } else {
+                None
+            };
+            let codon = chosen.unwrap_or_else(|| candidates[0].0.clone());
+            seq.push_str(&codon.replace('U', "T"));
+        }
+        Ok(Sequence::new(BioType::Dna, seq))
+    }
+
+    /// 依次把每个密码子替换为 `choose` 选出的同义密码子
+    /// `choose` 接收 (同义密码子, 在目标物种中的次数) 与原密码子 (均为大写 RNA 形式)，返回 None 时保留原密码子
+    /// 结果为大写，类型与原序列相同
+    pub(crate) fn replace_codons(
+        &self,
+        target_usage: &CodonUsage,
+        mut choose: impl FnMut(&[(String, usize)], &str) -> Option<String>,
+    ) -> Result<Sequence, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("不能对 {} 序列进行密码子优化", self.biotype));
+        }
+        if self.len() % 3 != 0 {
+            return Err(format!("编码序列的长度 {} 不是 3 的倍数", self.len()));
+        }
+        let table = &target_usage.table;
+        let mut seq = String::with_capacity(self.seq.len());
+        for codon in self.codons(0)? {
+            let index = codon_index(codon).ok_or(format!("无法识别的密码子: {}", codon))?;
+            let original = index_codon(index);
+            let amino_acid = table.amino_acids.as_bytes()[index] as char;
+            let candidates: Vec<(String, usize)> = table
+                .synonymous_codons(amino_acid)
+                .into_iter()
+                .map(|codon| {
+                    let count = target_usage.count(&codon);
+                    (codon, count)
+                })
+                .collect();
+            let chosen = choose(&candidates, &original).unwrap_or(original);
+            match self.biotype {
+                BioType::Dna => seq.push_str(&chosen.replace('U', "T")),
+                _ => seq.push_str(&chosen),
+            }
+        }
+        Ok(Sequence {
+            biotype: self.biotype.clone(),
+            seq,
+            topology: self.topology,
+        })
+    }
+}
+
+/// 密码子在表中的位置
+fn codon_index(codon: &str) -> Option<usize> {
+    if codon.len() != 3 {
+        return None;
+    }
+    codon.chars().try_fold(0, |index, base| {
+        let base = match base.to_ascii_uppercase() {
+            'T' => 'U',
+            other => other,
+        };
+        BASE_ORDER
+            .iter()
+            .position(|&b| b == base)
+            .map(|position| index * 4 + position)
+    })
+}
+
+/// 表中位置对应的 RNA 密码子
+fn index_codon(index: usize) -> String {
+    [index / 16, index / 4 % 4, index % 4]
+        .iter()
+        .map(|&i| BASE_ORDER[i])
+        .collect()
+}
+
+/// `back_translate` 抽样时使用的固定随机数种子
+const BACK_TRANSLATE_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// SplitMix64 伪随机数生成器，更新 `state` 并返回下一个随机数
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn standard_table_matches_codon_table() {
+        let table = CodonTable::standard();
+        for (codon, amino_acid) in CODON_TABLE.iter() {
+            assert_eq!(
+                table.translate_codon(codon).map(String::from),
+                Some(amino_acid.to_string())
+            );
+        }
+        assert_eq!(table.translate_codon("atg"), Some('M'));
+        assert_eq!(table.translate_codon("ANG"), None);
+        assert_eq!(table.start_codons(), vec!["UUG", "CUG", "AUG"]);
+    }
+
+    #[test]
+    fn ncbi_tables() {
+        let mito = CodonTable::vertebrate_mitochondrial();
+        assert_eq!(mito, CodonTable::from_ncbi_id(2).unwrap());
+        assert_eq!(mito.translate_codon("TGA"), Some('W'));
+        assert_eq!(mito.translate_codon("AGG"), Some('*'));
+        assert_eq!(mito.translate_codon("AGA"), Some('*'));
+        assert_eq!(mito.translate_codon("ATA"), Some('M'));
+        let flatworm = CodonTable::from_ncbi_id(9).unwrap();
+        assert_eq!(flatworm.translate_codon("AAA"), Some('N'));
+        let chlorophycean = CodonTable::from_ncbi_id(16).unwrap();
+        assert_eq!(chlorophycean.translate_codon("TAG"), Some('L'));
+        assert!(CodonTable::from_ncbi_id(3).is_err());
+
+        // 人线粒体 MT-CO1 (NC_012920) 的前 34 个密码子，第 6 与第 25 个密码子为 TGA
+        let co1 = Sequence::new(
+            BioType::Dna,
+            String::from(
+                "ATGTTCGCCGACCGTTGACTATTCTCTACAAACCACAAAGACATTGGAACACTATACCTATTATTCGGCGCATGAGCTGGAGTCCTAGGCACAGCTCTAAGC",
+            ),
+        );
+        assert_eq!(
+            co1.translate_with_table(&mito).unwrap().seq,
+            "MFADRWLFSTNHKDIGTLYLLFGAWAGVLGTALS"
+        );
+        assert_eq!(co1.translate().unwrap().seq, "MFADR");
+        let stops = Sequence::new(BioType::Dna, String::from("ATAAAAAGGCCC"));
+        assert_eq!(stops.translate_with_table(&mito).unwrap().seq, "MK");
+        for id in [1, 2, 4, 5, 9, 11, 16] {
+            assert!(CodonTable::from_ncbi_id(id).is_ok());
+        }
+    }
+
+    #[test]
+    fn synonymous_codons_and_degeneracy() {
+        let table = CodonTable::standard();
+        assert_eq!(table.synonymous_codons('m'), vec!["AUG"]);
+        let mut leucine = table.synonymous_codons('L');
+        leucine.sort();
+        assert_eq!(leucine, vec!["CUA", "CUC", "CUG", "CUU", "UUA", "UUG"]);
+        assert_eq!(table.synonymous_codons('*').len(), 3);
+        assert!(table.synonymous_codons('B').is_empty());
+        assert_eq!(table.degeneracy("TGG"), 1);
+        assert_eq!(table.degeneracy("GAU"), 2);
+        assert_eq!(table.degeneracy("ATA"), 3);
+        assert_eq!(table.degeneracy("GCN"), 0);
+        // 脊椎动物线粒体密码子表中 UGA 编码 Trp
+        assert_eq!(CodonTable::from_ncbi_id(2).unwrap().degeneracy("UGG"), 2);
+    }
+
+    #[test]
+    fn codon_usage_and_rscu() {
+        // 两个 GCU、一个 GCC 与一个 AAA
+        let dna = Sequence::new(BioType::Dna, String::from("GCTGCTGCCAAAT"));
+        let usage = dna.codon_usage().unwrap();
+        assert_eq!(usage.total(), 4);
+        assert_eq!(usage.count("GCT"), 2);
+        assert_eq!(usage.count("GCU"), 2);
+        let rscu = usage.rscu();
+        // 丙氨酸有 4 个同义密码子，期望次数为 3 / 4
+        assert!((rscu["GCU"] - 8.0 / 3.0).abs() < 1e-9);
+        assert!((rscu["GCC"] - 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rscu["GCA"], 0.0);
+        assert_eq!(rscu["AAA"], 2.0);
+        assert_eq!(rscu["UUU"], 0.0);
+        assert!(Sequence::new(BioType::Protein, String::from("MK"))
+            .codon_usage()
+            .is_err());
+    }
+
+    #[test]
+    fn codon_adaptation_index() {
+        let reference = Sequence::new(BioType::Dna, String::from("GCTGCTGCTGCCAAAAAAAAG"))
+            .codon_usage()
+            .unwrap();
+        let optimal = Sequence::new(BioType::Dna, String::from("GCTAAAATG"))
+            .codon_usage()
+            .unwrap();
+        assert!((optimal.cai(&reference) - 1.0).abs() < 1e-9);
+        let suboptimal = Sequence::new(BioType::Dna, String::from("GCCAAG"))
+            .codon_usage()
+            .unwrap();
+        let expected = ((1.0f64 / 3.0) * 0.5).sqrt();
+        assert!((suboptimal.cai(&reference) - expected).abs() < 1e-9);
+        assert_eq!(CodonUsage::new(CodonTable::standard()).cai(&reference), 0.0);
+    }
+
+    #[test]
+    fn codon_optimization() {
+        // 目标物种偏好 GCC (Ala)、AAG (Lys) 与 CUG (Leu)
+        let target = Sequence::new(BioType::Dna, String::from("GCCGCCGCTAAGAAGCTGCTGCTTTGG"))
+            .codon_usage()
+            .unwrap();
+        let dna = Sequence::new(BioType::Dna, String::from("GCAAAACTTTTAATGTGG"));
+        let optimized = dna.optimize_codons(&target).unwrap();
+        // Met 没有同义密码子，Trp 同样保持不变
+        assert_eq!(optimized.seq, "GCCAAGCTGCTGATGTGG");
+        assert_eq!(optimized.translate().unwrap(), dna.translate().unwrap());
+        // 已经是最优密码子的保持不变
+        assert_eq!(optimized.optimize_codons(&target).unwrap(), optimized);
+        assert!(Sequence::new(BioType::Dna, String::from("GCAA"))
+            .optimize_codons(&target)
+            .is_err());
+    }
+
+    #[test]
+    fn back_translation() {
+        let table = CodonTable::standard();
+        let protein = Sequence::new(BioType::Protein, String::from("MAkLW*"));
+        let dna = protein.back_translate(&table, None).unwrap();
+        // 人类中最常用的密码子
+        assert_eq!(dna.seq, "ATGGCCAAGCTGTGGTGA");
+        assert_eq!(dna.translate_with_stop(true).unwrap().seq, "MAKLW*");
+
+        // Ala 中 GCU 与 GCC 为 3:1，Lys 只用过 AAA
+        let usage = Sequence::new(BioType::Dna, String::from("GCTGCTGCTGCCAAA"))
+            .codon_usage()
+            .unwrap();
+        let alanines = Sequence::new(BioType::Protein, "A".repeat(1000) + "KM");
+        let dna = alanines.back_translate(&table, Some(&usage)).unwrap();
+        assert_eq!(dna.translate().unwrap().seq, alanines.seq);
+        assert_eq!(dna, alanines.back_translate(&table, Some(&usage)).unwrap());
+        let codons: Vec<&str> = dna.codons(0).unwrap().collect();
+        let gct = codons.iter().filter(|&&c| c == "GCT").count();
+        let gcc = codons.iter().filter(|&&c| c == "GCC").count();
+        assert_eq!(gct + gcc, 1000);
+        assert!((700..=800).contains(&gct));
+        assert_eq!(&codons[1000..], ["AAA", "ATG"]);
+
+        assert!(dna.back_translate(&table, None).is_err());
+        assert!(Sequence::new(BioType::Protein, String::from("MJ"))
+            .back_translate(&table, None)
+            .is_err());
+    }
+}
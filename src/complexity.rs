@@ -0,0 +1,447 @@
+use super::sequence::{BioType, Sequence};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// 序列复杂度的度量
+// 低复杂度 (重复) 的区域在比对或 BLAST 之前通常需要过滤
+// DUST 屏蔽使用 sdust (Li 2016) 的对称 DUST 算法 (Morgulis et al. 2006)：
+// 屏蔽每个窗口中所有的 perfect interval，即得分超过阈值、且不低于其中任何子区间得分的区间
+
+/// DUST 的默认窗口长度，与 sdust 相同
+pub const DUST_WINDOW: usize = 64;
+/// DUST 的默认阈值，对应 sdust 的 `-t 20` (sdust 的得分放大了 10 倍)
+pub const DUST_THRESHOLD: f64 = 2.0;
+
+impl Sequence {
+    /// 字符组成的 Shannon 熵 (bit)，不区分大小写
+    /// 接近 0 表示低复杂度，DNA 的最大值为 log2(4) = 2，空序列返回 0.0
+    pub fn complexity(&self) -> f64 {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in self.seq.chars() {
+            *counts.entry(c.to_ascii_uppercase()).or_insert(0) += 1;
+        }
+        let total: usize = counts.values().sum();
+        counts
+            .values()
+            .map(|&count| {
+                let p = count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum::<f64>()
+            .abs() // 避免只有一种字符时得到 -0.0
+    }
+
+    /// Trifonov 语言复杂度：k 从 1 到序列长度时，实际出现的不同 k-mer 数之和
+    /// 除以可能出现的最多 k-mer 数之和，结果在 0 ~ 1 之间
+    /// 长度为 k 时最多有 min(字母表大小^k, L - k + 1) 种 k-mer，核酸字母表大小为 4，蛋白质为 20
+    /// 空序列返回 0.0
+    pub fn linguistic_complexity(&self) -> f64 {
+        let chars: Vec<char> = self.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let len = chars.len();
+        if len == 0 {
+            return 0.0;
+        }
+        let alphabet = match self.biotype {
+            BioType::Dna | BioType::Rna => 4.0,
+            BioType::Protein => 20.0,
+        };
+        let (mut observed, mut possible) = (0.0, 0.0);
+        for k in 1..=len {
+            let distinct: HashSet<&[char]> = chars.windows(k).collect();
+            observed += distinct.len() as f64;
+            possible += f64::powi(alphabet, k as i32).min((len - k + 1) as f64);
+        }
+        observed / possible
+    }
+
+    /// 以步长 1 滑动长度为 `window` 的窗口，计算每个窗口的 DUST 得分 (Morgulis et al. 2006)
+    /// 得分为 Σ c_t (c_t - 1) / 2 / (l - 1)，c_t 为窗口中每种三核苷酸出现的次数，l 为三核苷酸的个数
+    /// 不区分大小写，T 与 U 视为相同，含有 ACGTU 以外字符的三核苷酸不计入；l 小于 2 时得分为 0
+    /// 蛋白质序列、`window` 小于 4 或超过序列长度时返回空数组
+    pub fn dust_score(&self, window: usize) -> Vec<f64> {
+        if self.biotype == BioType::Protein || window < 4 {
+            return Vec::new();
+        }
+        let triplets: Vec<Option<usize>> = self
+            .seq
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'A' => Some(0),
+                'C' => Some(1),
+                'G' => Some(2),
+                'T' | 'U' => Some(3),
+                _ => None,
+            })
+            .collect::<Vec<Option<usize>>>()
+            .windows(3)
+            .map(|w| Some(w[0]? * 16 + w[1]? * 4 + w[2]?))
+            .collect();
+        triplets
+            .windows(window - 2)
+            .map(|w| {
+                let mut counts = [0usize; 64];
+                for &triplet in w.iter().flatten() {
+                    counts[triplet] += 1;
+                }
+                let l: usize = counts.iter().sum();
+                if l < 2 {
+                    return 0.0;
+                }
+                let pairs: usize = counts.iter().map(|&c| c * c.saturating_sub(1) / 2).sum();
+                pairs as f64 / (l - 1) as f64
+            })
+            .collect()
+    }
+
+    /// 对称 DUST 找到的低复杂度区域，即长度不超过 `window` 的窗口中所有 perfect interval 的并集
+    /// 区间的得分与 `dust_score` 相同，得分超过 `threshold` 且不低于其中任何子区间的得分时为 perfect interval；
+    /// 结果为以 0 开始的左闭右开区间，按位置排序，相互重叠或相邻的区间会合并，与 sdust 的输出一致
+    /// 不区分大小写，T 与 U 视为相同，ACGTU 以外的字符把序列分成互不相关的片段
+    /// 蛋白质序列或 `window` 小于 4 时返回空数组
+    pub fn dust_regions(&self, window: usize, threshold: f64) -> Vec<(usize, usize)> {
+        if self.biotype == BioType::Protein || window < 4 {
+            return Vec::new();
+        }
+        let bases: Vec<Option<usize>> = self
+            .seq
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'A' => Some(0),
+                'C' => Some(1),
+                'G' => Some(2),
+                'T' | 'U' => Some(3),
+                _ => None,
+            })
+            .collect();
+        let mut sdust = Sdust::new(window, threshold);
+        // run 为当前连续 ACGTU 片段的长度，triplet 为最后一个三核苷酸的编号
+        let (mut run, mut triplet) = (0usize, 0);
+        for i in 0..=bases.len() {
+            match bases.get(i).copied().flatten() {
+                Some(base) => {
+                    run += 1;
+                    triplet = (triplet << 2 | base) & 63;
+                    if run >= 3 {
+                        let start = run.saturating_sub(window) + i + 1 - run;
+                        sdust.save_regions(start);
+                        sdust.shift(triplet);
+                        if sdust.window_score as f64 > threshold * sdust.suffix as f64 {
+                            sdust.find_perfect(start);
+                        }
+                    }
+                }
+                None => {
+                    let mut start = (run + 1).saturating_sub(window) + i + 1 - run;
+                    while !sdust.perfect.is_empty() {
+                        sdust.save_regions(start);
+                        start += 1;
+                    }
+                    sdust.reset();
+                    run = 0;
+                    triplet = 0;
+                }
+            }
+        }
+        sdust.regions
+    }
+
+    /// 将 `dust_regions` 找到的低复杂度区域改为小写 (软屏蔽)，标准参数为 `DUST_WINDOW` 与 `DUST_THRESHOLD`
+    pub fn dust_mask(&self, window: usize, threshold: f64) -> Sequence {
+        self.soft_mask(&self.dust_regions(window, threshold))
+    }
+}
+
+/// 窗口中的 perfect interval，`r / l` 为区间的 DUST 得分
+struct PerfectInterval {
+    start: usize,
+    finish: usize,
+    r: usize,
+    l: usize,
+}
+
+/// sdust 的滑动窗口状态，窗口中保存三核苷酸的编号
+/// 窗口末尾的 `suffix` 个三核苷酸中每种出现的次数都不超过 2 * threshold，其中不会有 perfect interval
+struct Sdust {
+    window: usize,
+    threshold: f64,
+    triplets: VecDeque<usize>,
+    window_counts: [usize; 64],
+    suffix_counts: [usize; 64],
+    suffix: usize,
+    window_score: usize,
+    suffix_score: usize,
+    /// 按起点从大到小、起点相同时按终点从小到大排列
+    perfect: Vec<PerfectInterval>,
+    regions: Vec<(usize, usize)>,
+}
+
+impl Sdust {
+    fn new(window: usize, threshold: f64) -> Self {
+        Sdust {
+            window,
+            threshold,
+            triplets: VecDeque::with_capacity(window),
+            window_counts: [0; 64],
+            suffix_counts: [0; 64],
+            suffix: 0,
+            window_score: 0,
+            suffix_score: 0,
+            perfect: Vec::new(),
+            regions: Vec::new(),
+        }
+    }
+
+    /// 遇到 ACGTU 以外的字符时清空窗口
+    fn reset(&mut self) {
+        self.triplets.clear();
+        self.window_counts = [0; 64];
+        self.suffix_counts = [0; 64];
+        self.suffix = 0;
+        self.window_score = 0;
+        self.suffix_score = 0;
+    }
+
+    /// 窗口移到 `start` 之后，保存起点最小的 perfect interval 并移除落在窗口之外的区间
+    fn save_regions(&mut self, start: usize) {
+        let Some(last) = self.perfect.last() else {
+            return;
+        };
+        if last.start >= start {
+            return;
+        }
+        match self.regions.last_mut() {
+            Some((_, finish)) if last.start <= *finish => *finish = (*finish).max(last.finish),
+            _ => self.regions.push((last.start, last.finish)),
+        }
+        while self.perfect.last().is_some_and(|p| p.start < start) {
+            self.perfect.pop();
+        }
+    }
+
+    /// 把三核苷酸 `triplet` 加入窗口末尾，窗口已满时移出最前面的一个
+    fn shift(&mut self, triplet: usize) {
+        if self.triplets.len() > self.window - 3 {
+            let first = self.triplets.pop_front().unwrap();
+            self.window_counts[first] -= 1;
+            self.window_score -= self.window_counts[first];
+            if self.suffix > self.triplets.len() {
+                self.suffix -= 1;
+                self.suffix_counts[first] -= 1;
+                self.suffix_score -= self.suffix_counts[first];
+            }
+        }
+        self.triplets.push_back(triplet);
+        self.suffix += 1;
+        self.window_score += self.window_counts[triplet];
+        self.window_counts[triplet] += 1;
+        self.suffix_score += self.suffix_counts[triplet];
+        self.suffix_counts[triplet] += 1;
+        if self.suffix_counts[triplet] as f64 > 2.0 * self.threshold {
+            loop {
+                let removed = self.triplets[self.triplets.len() - self.suffix];
+                self.suffix_counts[removed] -= 1;
+                self.suffix_score -= self.suffix_counts[removed];
+                self.suffix -= 1;
+                if removed == triplet {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// 依次检查以窗口末尾结束、从 `suffix` 之前开始的区间，记录新的 perfect interval
+    /// `start` 为窗口第一个三核苷酸在序列中的位置
+    fn find_perfect(&mut self, start: usize) {
+        let mut counts = self.suffix_counts;
+        let mut r = self.suffix_score;
+        let (mut max_r, mut max_l) = (0, 0);
+        let len = self.triplets.len();
+        for i in (0..len - self.suffix).rev() {
+            let triplet = self.triplets[i];
+            r += counts[triplet];
+            counts[triplet] += 1;
+            let l = len - i - 1;
+            if r as f64 <= self.threshold * l as f64 {
+                continue;
+            }
+            // 已记录的起点不小于 i + start 的区间都是当前区间的子区间
+            let mut j = 0;
+            while j < self.perfect.len() && self.perfect[j].start >= i + start {
+                let p = &self.perfect[j];
+                if max_r == 0 || p.r * max_l > max_r * p.l {
+                    (max_r, max_l) = (p.r, p.l);
+                }
+                j += 1;
+            }
+            if max_r == 0 || r * max_l >= max_r * l {
+                (max_r, max_l) = (r, l);
+                self.perfect.insert(
+                    j,
+                    PerfectInterval {
+                        start: i + start,
+                        finish: len + 2 + start,
+                        r,
+                        l,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shannon_entropy() {
+        let uniform = Sequence::new(BioType::Dna, String::from("ACGTacgt"));
+        assert!((uniform.complexity() - 2.0).abs() < 1e-9);
+        let repeat = Sequence::new(BioType::Dna, String::from("AAAAAAAA"));
+        assert_eq!(repeat.complexity(), 0.0);
+        let binary = Sequence::new(BioType::Dna, String::from("ATATAT"));
+        assert!((binary.complexity() - 1.0).abs() < 1e-9);
+        assert_eq!(Sequence::new(BioType::Dna, String::new()).complexity(), 0.0);
+    }
+
+    #[test]
+    fn linguistic_complexity() {
+        let repeat = Sequence::new(BioType::Dna, String::from("AAAAAAAA"));
+        let mixed = Sequence::new(BioType::Dna, String::from("ACGTTGCA"));
+        assert!(repeat.linguistic_complexity() < mixed.linguistic_complexity());
+        // ACGT 中每种长度的 k-mer 都达到了可能的最大数目
+        let all_distinct = Sequence::new(BioType::Dna, String::from("ACGT"));
+        assert_eq!(all_distinct.linguistic_complexity(), 1.0);
+        // AAAA: 出现 1+1+1+1 = 4 种，最多 4+3+2+1 = 10 种
+        let poly_a = Sequence::new(BioType::Dna, String::from("AAAA"));
+        assert!((poly_a.linguistic_complexity() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dust() {
+        // 6 个碱基中 4 个 AAA：4 × 3 / 2 / 3 = 2
+        let poly_a = Sequence::new(BioType::Dna, String::from("AAAAAA"));
+        assert_eq!(poly_a.dust_score(6), vec![2.0]);
+        let distinct = Sequence::new(BioType::Rna, String::from("ACGUAC"));
+        assert_eq!(distinct.dust_score(6), vec![0.0]);
+        assert_eq!(poly_a.dust_score(4), vec![1.0, 1.0, 1.0]);
+        assert!(poly_a.dust_score(7).is_empty());
+
+        let seq = Sequence::new(BioType::Dna, format!("ACGTTGCA{}CGTAGCTA", "A".repeat(20)));
+        let scores = seq.dust_score(10);
+        assert_eq!(scores.len(), 27);
+        assert!((scores[6] - 3.0).abs() < 1e-9);
+        assert_eq!(scores[10], 4.0);
+        assert_eq!(seq.dust_regions(10, DUST_THRESHOLD), vec![(7, 28)]);
+        assert_eq!(
+            seq.dust_mask(DUST_WINDOW, DUST_THRESHOLD).seq,
+            "ACGTTGCaaaaaaaaaaaaaaaaaaaaaCGTAGCTA"
+        );
+    }
+
+    /// 按对称 DUST 的定义穷举所有不超过 `window` 的区间，返回 perfect interval 的并集
+    fn naive_dust(seq: &str, window: usize, threshold: f64) -> Vec<(usize, usize)> {
+        let bytes = seq.as_bytes();
+        let score = |start: usize, end: usize| {
+            let mut counts: HashMap<&[u8], usize> = HashMap::new();
+            for triplet in bytes[start..end].windows(3) {
+                *counts.entry(triplet).or_insert(0) += 1;
+            }
+            let r: usize = counts.values().map(|&c| c * (c - 1) / 2).sum();
+            (r, end - start - 3)
+        };
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+        for start in 0..bytes.len() {
+            for end in start + 4..=bytes.len().min(start + window) {
+                if bytes[start..end].contains(&b'N') {
+                    break;
+                }
+                let (r, l) = score(start, end);
+                if r as f64 <= threshold * l as f64 {
+                    continue;
+                }
+                let dominated = (start..end - 3).any(|sub_start| {
+                    (sub_start + 4..=end).any(|sub_end| {
+                        let (sub_r, sub_l) = score(sub_start, sub_end);
+                        sub_r * l > r * sub_l
+                    })
+                });
+                if dominated {
+                    continue;
+                }
+                match regions.last_mut() {
+                    Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+                    _ => regions.push((start, end)),
+                }
+            }
+        }
+        regions
+    }
+
+    #[test]
+    fn symmetric_dust() {
+        let dna = |seq: &str| Sequence::new(BioType::Dna, String::from(seq));
+        let microsatellite = dna(&format!(
+            "GATTACAGGCTTAC{}TTGACCGTAGGCATCGATG",
+            "CA".repeat(15)
+        ));
+        assert_eq!(
+            microsatellite.dust_regions(DUST_WINDOW, DUST_THRESHOLD),
+            vec![(14, 44)]
+        );
+        // N 把序列分成两段，各自独立屏蔽
+        let split = dna(&format!(
+            "GGCATTACGAT{}NN{}CTGACTTGCA",
+            "CAG".repeat(8),
+            "AAAT".repeat(6)
+        ));
+        assert_eq!(
+            split.dust_regions(DUST_WINDOW, DUST_THRESHOLD),
+            vec![(11, 35), (37, 61)]
+        );
+        let periodic = dna(&"ACGT".repeat(6));
+        assert_eq!(
+            periodic.dust_regions(DUST_WINDOW, DUST_THRESHOLD),
+            vec![(0, 24)]
+        );
+        assert!(periodic.dust_regions(DUST_WINDOW, 3.0).is_empty());
+        // 不区分大小写，U 与 T 相同
+        let rna = Sequence::new(BioType::Rna, "acgu".repeat(6));
+        assert_eq!(rna.dust_regions(DUST_WINDOW, DUST_THRESHOLD), vec![(0, 24)]);
+        assert!(Sequence::new(BioType::Protein, "A".repeat(20))
+            .dust_regions(DUST_WINDOW, DUST_THRESHOLD)
+            .is_empty());
+
+        // 随机的低复杂度序列与穷举的结果一致
+        let mut state: u64 = 7;
+        let mut next = |n: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % n) as usize
+        };
+        for _ in 0..40 {
+            let alphabet = ["AC", "ACG", "ACGT", "ACN", "AT"][next(5)].as_bytes();
+            let unit: String = (0..1 + next(3))
+                .map(|_| alphabet[next(alphabet.len() as u64)] as char)
+                .collect();
+            let mut seq = String::new();
+            while seq.len() < 40 {
+                if next(2) == 0 {
+                    seq.push_str(&unit);
+                } else {
+                    seq.push(alphabet[next(alphabet.len() as u64)] as char);
+                }
+            }
+            let (window, threshold) = ([8, 10, 16][next(3)], [1.0, 2.0, 3.0][next(3)]);
+            assert_eq!(
+                dna(&seq).dust_regions(window, threshold),
+                naive_dust(&seq, window, threshold),
+                "{} {} {}",
+                seq,
+                window,
+                threshold
+            );
+        }
+    }
+}
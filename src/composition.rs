@@ -0,0 +1,320 @@
+use super::sequence::{count_kmers, kmer_frequency, BioType, Sequence};
+
+use std::collections::{BTreeSet, HashMap};
+
+// 碱基组成相关的分析
+
+/// Gardiner-Garden & Frommer (1987) CpG 岛标准：窗口长度 200 bp
+pub const CPG_WINDOW: usize = 200;
+/// CpG 岛标准：GC 含量超过 50%
+pub const CPG_MIN_GC: f64 = 0.5;
+/// CpG 岛标准：CpG 观察/期望比超过 0.6
+pub const CPG_MIN_RATIO: f64 = 0.6;
+/// 相距不超过该距离 (bp) 的合格窗口会被合并为一个 CpG 岛
+pub const CPG_MERGE_DISTANCE: usize = 100;
+
+/// 一个 CpG 岛，`start..end` 为以 0 开始的半开区间
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpgIsland {
+    pub start: usize,
+    pub end: usize,
+    /// 区域内 CG 二核苷酸的个数
+    pub cpg_count: usize,
+    pub gc_content: f64,
+    /// CpG 观察/期望比：CpG 数 × 长度 / (C 数 × G 数)
+    pub obs_exp_ratio: f64,
+}
+
+impl CpgIsland {
+    /// CpG 岛的长度
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// 长度是否为 0
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// G、C 与 CpG 的前缀和，用于快速统计任意区间
+struct CpgCounts {
+    g: Vec<usize>,
+    c: Vec<usize>,
+    cpg: Vec<usize>,
+}
+
+impl CpgCounts {
+    fn new(bases: &[char]) -> Self {
+        let mut counts = CpgCounts {
+            g: vec![0; bases.len() + 1],
+            c: vec![0; bases.len() + 1],
+            cpg: vec![0; bases.len() + 1],
+        };
+        for (i, &base) in bases.iter().enumerate() {
+            counts.g[i + 1] = counts.g[i] + (base == 'G') as usize;
+            counts.c[i + 1] = counts.c[i] + (base == 'C') as usize;
+            // cpg[i + 1] 为起始位置小于 i 的 CG 个数
+            let is_cpg = i > 0 && bases[i - 1] == 'C' && base == 'G';
+            counts.cpg[i + 1] = counts.cpg[i] + is_cpg as usize;
+        }
+        counts
+    }
+
+    /// 统计 `start..end` 区间，返回 CpG 岛信息
+    fn island(&self, start: usize, end: usize) -> CpgIsland {
+        let g = self.g[end] - self.g[start];
+        let c = self.c[end] - self.c[start];
+        // 只统计两个碱基都位于区间内的 CG
+        let cpg = self.cpg[end] - self.cpg[start + 1];
+        let len = end - start;
+        let obs_exp_ratio = if g == 0 || c == 0 {
+            0.0
+        } else {
+            (cpg * len) as f64 / (c * g) as f64
+        };
+        CpgIsland {
+            start,
+            end,
+            cpg_count: cpg,
+            gc_content: (g + c) as f64 / len as f64,
+            obs_exp_ratio,
+        }
+    }
+}
+
+impl Sequence {
+    /// 以步长 `step` 滑动长度为 `window` 的窗口寻找 CpG 岛，不区分大小写
+    /// GC 含量超过 `min_gc` 且 CpG 观察/期望比超过 `min_cpg_ratio` 的窗口为合格窗口，
+    /// 相距不超过 100 bp 的合格窗口会被合并，合并后的区域重新统计
+    /// 只接受 DNA 序列，`window` 或 `step` 为 0 时返回 Err
+    pub fn find_cpg_islands(
+        &self,
+        window: usize,
+        step: usize,
+        min_gc: f64,
+        min_cpg_ratio: f64,
+    ) -> Result<Vec<CpgIsland>, String> {
+        if self.biotype != BioType::Dna {
+            return Err(format!("你不能在一段{}序列中寻找 CpG 岛", self.biotype));
+        }
+        if window == 0 || step == 0 {
+            return Err(String::from("窗口长度与步长必须大于 0"));
+        }
+        let bases: Vec<char> = self.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        if bases.len() < window {
+            return Ok(Vec::new());
+        }
+        let counts = CpgCounts::new(&bases);
+
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+        for start in (0..=bases.len() - window).step_by(step) {
+            let end = start + window;
+            let stats = counts.island(start, end);
+            if stats.gc_content <= min_gc || stats.obs_exp_ratio <= min_cpg_ratio {
+                continue;
+            }
+            match regions.last_mut() {
+                Some(last) if start <= last.1 + CPG_MERGE_DISTANCE => last.1 = end,
+                _ => regions.push((start, end)),
+            }
+        }
+        Ok(regions
+            .into_iter()
+            .map(|(start, end)| counts.island(start, end))
+            .collect())
+    }
+
+    /// 使用 Gardiner-Garden & Frommer 标准 (200 bp 窗口，步长 1，GC > 50%，观察/期望 > 0.6)
+    /// 寻找 CpG 岛
+    pub fn cpg_islands(&self) -> Result<Vec<CpgIsland>, String> {
+        self.find_cpg_islands(CPG_WINDOW, 1, CPG_MIN_GC, CPG_MIN_RATIO)
+    }
+}
+
+/// (x - y) / (x + y)，两种碱基都不存在时为 0.0，不区分大小写，U 视为 T
+fn skew(seq: &str, x: char, y: char) -> f64 {
+    let (mut nx, mut ny) = (0usize, 0usize);
+    for c in seq.chars() {
+        let c = match c.to_ascii_uppercase() {
+            'U' => 'T',
+            c => c,
+        };
+        if c == x {
+            nx += 1;
+        } else if c == y {
+            ny += 1;
+        }
+    }
+    if nx + ny == 0 {
+        0.0
+    } else {
+        (nx as f64 - ny as f64) / (nx + ny) as f64
+    }
+}
+
+impl Sequence {
+    /// 整条序列的 GC 偏斜 (G - C) / (G + C)，范围为 -1 ~ 1，没有 G 与 C 时为 0.0
+    pub fn gc_skew(&self) -> f64 {
+        skew(&self.seq, 'G', 'C')
+    }
+
+    /// 整条序列的 AT 偏斜 (A - T) / (A + T)，RNA 中的 U 按 T 计算，没有 A 与 T 时为 0.0
+    pub fn at_skew(&self) -> f64 {
+        skew(&self.seq, 'A', 'T')
+    }
+
+    /// 以步长 `step` 滑动长度为 `window` 的窗口计算 GC 偏斜，窗口规则同 `Sequence::windows`
+    pub fn gc_skew_profile(&self, window: usize, step: usize) -> Vec<f64> {
+        self.windows(window, step).map(|w| w.gc_skew()).collect()
+    }
+
+    /// 以步长 `step` 滑动长度为 `window` 的窗口计算 AT 偏斜，窗口规则同 `Sequence::windows`
+    pub fn at_skew_profile(&self, window: usize, step: usize) -> Vec<f64> {
+        self.windows(window, step).map(|w| w.at_skew()).collect()
+    }
+}
+
+/// 归一化的 k-mer 频率，不区分大小写，核酸序列中的 U 视为 T
+fn kmer_profile(seq: &Sequence, k: usize) -> HashMap<String, f64> {
+    let chars: Vec<char> = seq
+        .seq
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'U' if seq.biotype != BioType::Protein => 'T',
+            c => c,
+        })
+        .collect();
+    kmer_frequency(count_kmers(&chars, k))
+}
+
+/// 两个频率表在所有出现过的 k-mer 上组成的向量，没有出现的 k-mer 频率为 0
+fn profile_vectors(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> (Vec<f64>, Vec<f64>) {
+    let kmers: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    let value = |profile: &HashMap<String, f64>, kmer: &String| *profile.get(kmer).unwrap_or(&0.0);
+    kmers
+        .into_iter()
+        .map(|kmer| (value(a, kmer), value(b, kmer)))
+        .unzip()
+}
+
+fn euclidean(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let (x, y) = profile_vectors(a, b);
+    x.iter()
+        .zip(&y)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// 两条序列 k-mer 频率向量之间的欧氏距离，不需要比对，适用于长度相差很大的序列
+/// 不区分大小写，DNA 与 RNA 之间 U 视为 T；没有任何 k-mer 的序列 (k 为 0 或大于序列长度) 视为零向量
+pub fn composition_distance(a: &Sequence, b: &Sequence, k: usize) -> f64 {
+    euclidean(&kmer_profile(a, k), &kmer_profile(b, k))
+}
+
+/// 1 减去两条序列 k-mer 频率向量的 Pearson 相关系数，范围为 0 ~ 2
+/// 向量只包括至少在一条序列中出现过的 k-mer，其余规则同 `composition_distance`；
+/// 任一向量的方差为 0 (如只有一种 k-mer 或没有 k-mer) 时相关系数无定义，返回 NaN
+pub fn correlation_distance(a: &Sequence, b: &Sequence, k: usize) -> f64 {
+    let (x, y) = profile_vectors(&kmer_profile(a, k), &kmer_profile(b, k));
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in x.iter().zip(&y) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return f64::NAN;
+    }
+    1.0 - covariance / (variance_x * variance_y).sqrt()
+}
+
+/// 两两计算 `composition_distance`，返回 n×n 的对称矩阵
+pub fn composition_matrix(seqs: &[Sequence], k: usize) -> Vec<Vec<f64>> {
+    let profiles: Vec<HashMap<String, f64>> = seqs.iter().map(|seq| kmer_profile(seq, k)).collect();
+    let mut matrix = vec![vec![0.0; seqs.len()]; seqs.len()];
+    for i in 0..profiles.len() {
+        for j in i + 1..profiles.len() {
+            let distance = euclidean(&profiles[i], &profiles[j]);
+            matrix[i][j] = distance;
+            matrix[j][i] = distance;
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpg_island_detection() {
+        let flank = "AT".repeat(150);
+        let island = "CG".repeat(50) + &"ACGT".repeat(25);
+        let dna = Sequence::new(BioType::Dna, format!("{}{}{}", flank, island, flank));
+        let islands = dna.cpg_islands().unwrap();
+        assert_eq!(islands.len(), 1);
+        let found = &islands[0];
+        assert!(found.start <= 300 && found.end >= 500);
+        assert!(found.len() >= CPG_WINDOW);
+        assert!(found.obs_exp_ratio > CPG_MIN_RATIO);
+
+        let at_rich = Sequence::new(BioType::Dna, "AT".repeat(300));
+        assert!(at_rich.cpg_islands().unwrap().is_empty());
+    }
+
+    #[test]
+    fn cpg_island_window_stats_and_errors() {
+        let dna = Sequence::new(BioType::Dna, String::from("cgcgatat"));
+        let islands = dna.find_cpg_islands(4, 4, 0.5, 0.6).unwrap();
+        assert_eq!(
+            islands,
+            vec![CpgIsland {
+                start: 0,
+                end: 4,
+                cpg_count: 2,
+                gc_content: 1.0,
+                obs_exp_ratio: 2.0,
+            }]
+        );
+        let rna = Sequence::new(BioType::Rna, String::from("CGCG"));
+        assert!(rna.find_cpg_islands(2, 1, 0.5, 0.6).is_err());
+        assert!(dna.find_cpg_islands(0, 1, 0.5, 0.6).is_err());
+    }
+
+    #[test]
+    fn gc_and_at_skew() {
+        let dna = Sequence::new(BioType::Dna, String::from("GGGCAATTTT"));
+        assert!((dna.gc_skew() - 0.5).abs() < 1e-9);
+        assert!((dna.at_skew() + 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            Sequence::new(BioType::Dna, String::from("AATT")).gc_skew(),
+            0.0
+        );
+        let rna = Sequence::new(BioType::Rna, String::from("aaau"));
+        assert!((rna.at_skew() - 0.5).abs() < 1e-9);
+        assert_eq!(dna.gc_skew_profile(4, 6), vec![0.5, 0.0]);
+        assert_eq!(dna.at_skew_profile(4, 6), vec![0.0, -1.0]);
+        assert!(dna.gc_skew_profile(20, 1).is_empty());
+    }
+
+    #[test]
+    fn kmer_composition_distance() {
+        let a = Sequence::new(BioType::Dna, String::from("AAAC"));
+        let longer = Sequence::new(BioType::Rna, String::from("aaaaaacc"));
+        let reversed = Sequence::new(BioType::Dna, String::from("CCCA"));
+        assert_eq!(composition_distance(&a, &longer, 1), 0.0);
+        assert!(correlation_distance(&a, &longer, 1).abs() < 1e-12);
+        assert!((composition_distance(&a, &reversed, 1) - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!((correlation_distance(&a, &reversed, 1) - 2.0).abs() < 1e-12);
+        assert!(correlation_distance(&a, &reversed, 5).is_nan());
+
+        let matrix = composition_matrix(&[a, longer, reversed], 1);
+        assert_eq!(matrix[0][1], 0.0);
+        assert_eq!(matrix[2][0], matrix[0][2]);
+        assert!((matrix[1][2] - 0.5f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+// 坐标系统之间的转换
+// BED 使用以 0 开始的左闭右开区间；GFF3/GTF、GenBank 与 SAM 使用以 1 开始的闭区间
+// 同一个碱基在两种坐标系统中的位置相差 1，而区间终点的数值相同：BED 的 `0..10` 即 GFF 的 `1..=10`
+
+/// 区间的坐标系统
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoordinateSystem {
+    /// 以 0 开始、左闭右开，BED 与本库内部使用
+    #[default]
+    ZeroBasedHalfOpen,
+    /// 以 1 开始、闭区间，GFF3/GTF、GenBank 与 SAM 使用
+    OneBasedClosed,
+}
+
+impl CoordinateSystem {
+    /// 将以 0 开始的左闭右开区间 `start..end` 转换为该坐标系统中的 (起点, 终点)
+    pub fn from_zero_based(self, start: usize, end: usize) -> (usize, usize) {
+        match self {
+            CoordinateSystem::ZeroBasedHalfOpen => (start, end),
+            CoordinateSystem::OneBasedClosed => (to_1based(start), end),
+        }
+    }
+
+    /// 将该坐标系统中的 (起点, 终点) 转换为以 0 开始的左闭右开区间
+    /// 以 1 开始的起点为 0 时会 panic
+    pub fn to_zero_based(self, start: usize, end: usize) -> (usize, usize) {
+        match self {
+            CoordinateSystem::ZeroBasedHalfOpen => (start, end),
+            CoordinateSystem::OneBasedClosed => (to_0based(start), end),
+        }
+    }
+}
+
+/// 以 1 开始的位置转换为以 0 开始的位置，`pos` 为 0 时会 panic
+pub fn to_0based(pos: usize) -> usize {
+    pos.checked_sub(1)
+        .unwrap_or_else(|| panic!("以 1 开始的坐标不能为 0"))
+}
+
+/// 以 0 开始的位置转换为以 1 开始的位置
+pub fn to_1based(pos: usize) -> usize {
+    pos + 1
+}
+
+/// BED 坐标系统中一个碱基的位置 (以 0 开始)
+/// 与 `GffCoordinate` 之间只能通过 `From` 显式转换，避免混用两种坐标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BedCoordinate(pub usize);
+
+/// GFF 坐标系统中一个碱基的位置 (以 1 开始)，也用于 GenBank 与 SAM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GffCoordinate(pub usize);
+
+impl From<BedCoordinate> for GffCoordinate {
+    fn from(pos: BedCoordinate) -> Self {
+        GffCoordinate(to_1based(pos.0))
+    }
+}
+
+/// GFF 坐标为 0 时会 panic
+impl From<GffCoordinate> for BedCoordinate {
+    fn from(pos: GffCoordinate) -> Self {
+        BedCoordinate(to_0based(pos.0))
+    }
+}
+
+impl fmt::Display for BedCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for GffCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinate_conversion() {
+        assert_eq!(to_1based(0), 1);
+        assert_eq!(to_0based(1), 0);
+        assert_eq!(GffCoordinate::from(BedCoordinate(9)), GffCoordinate(10));
+        assert_eq!(BedCoordinate::from(GffCoordinate(10)), BedCoordinate(9));
+
+        let gff = CoordinateSystem::OneBasedClosed;
+        assert_eq!(gff.from_zero_based(0, 10), (1, 10));
+        assert_eq!(gff.to_zero_based(1, 10), (0, 10));
+        assert_eq!(CoordinateSystem::default().from_zero_based(0, 10), (0, 10));
+    }
+
+    #[test]
+    #[should_panic]
+    fn one_based_zero_panics() {
+        to_0based(0);
+    }
+}
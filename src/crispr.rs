@@ -0,0 +1,157 @@
+use super::sequence::{AmbiguousBase, BioType, Sequence, Strand};
+
+// CRISPR 向导 RNA 的设计：寻找紧邻 PAM 的靶位点
+// PAM 位于原间隔序列 (protospacer) 的 3' 端，例如 SpCas9 的 NGG
+
+/// SpCas9 的 PAM
+pub const SPCAS9_PAM: &str = "NGG";
+
+/// 一个候选的向导 RNA 靶位点
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuideSite {
+    /// 原间隔序列在正链上最左端的位置 (以 0 开始)，负链位点同样以正链坐标表示
+    pub position: usize,
+    pub strand: Strand,
+    /// 按 5' -> 3' 方向给出的原间隔序列 (DNA)
+    pub guide_seq: Sequence,
+    /// 紧随其后的 PAM 序列
+    pub pam_seq: String,
+    pub gc_content: f64,
+}
+
+/// 在 DNA 序列两条链上寻找长度为 `guide_length`、3' 端紧邻 `pam` 的靶位点，不区分大小写
+/// PAM 支持 IUPAC 简并碱基，序列中的简并碱基不与任何 PAM 或靶位点匹配
+/// 非 DNA 序列、`guide_length` 为 0 或 PAM 为空时返回空数组
+pub fn find_guide_sites(seq: &Sequence, pam: &str, guide_length: usize) -> Vec<GuideSite> {
+    let pam: Vec<char> = pam.chars().collect();
+    if seq.biotype != BioType::Dna || guide_length == 0 || pam.is_empty() {
+        return Vec::new();
+    }
+    let reverse = match seq.reverse_complementary() {
+        Ok(reverse) => reverse,
+        Err(_) => return Vec::new(),
+    };
+    let n = seq.len();
+    let site_len = guide_length + pam.len();
+    let mut sites = Vec::new();
+    for (strand, strand_seq) in [(Strand::Forward, seq), (Strand::Reverse, &reverse)] {
+        let bases: Vec<char> = strand_seq
+            .seq
+            .chars()
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        for start in 0..(n + 1).saturating_sub(site_len) {
+            let guide = &bases[start..start + guide_length];
+            let site_pam = &bases[start + guide_length..start + site_len];
+            let pam_ok = pam
+                .iter()
+                .zip(site_pam)
+                .all(|(&code, &base)| AmbiguousBase::matches(code, base));
+            if !pam_ok || !guide.iter().all(|b| matches!(b, 'A' | 'C' | 'G' | 'T')) {
+                continue;
+            }
+            let guide_seq = Sequence::new(BioType::Dna, guide.iter().collect());
+            let gc_content = guide_seq.gc_content().unwrap_or(0.0);
+            sites.push(GuideSite {
+                position: if strand == Strand::Forward {
+                    start
+                } else {
+                    n - start - guide_length
+                },
+                strand,
+                guide_seq,
+                pam_seq: site_pam.iter().collect(),
+                gc_content,
+            });
+        }
+    }
+    sites.sort_by_key(|site| (site.position, site.strand == Strand::Reverse));
+    sites
+}
+
+/// 粗略的靶向效率评分 (0 ~ 1)，越高越好
+/// 参考 Rule Set 2 / Azimuth 中最重要的几个特征的经验规则，并不是训练得到的模型：
+/// - GC 含量在 40% ~ 70% 之间加分，过高或过低减分
+/// - 含有 TTTT (Pol III 终止信号) 大幅减分
+/// - 紧邻 PAM 的最后一个碱基为 G 加分，为 T 减分
+/// - 靠近 PAM 的 10 nt 种子区 GC 含量适中 (30% ~ 80%) 加分
+pub fn on_target_score(guide: &GuideSite) -> f64 {
+    let seq = guide.guide_seq.seq.to_ascii_uppercase();
+    let mut score: f64 = 0.5;
+    if (0.4..=0.7).contains(&guide.gc_content) {
+        score += 0.2;
+    } else {
+        score -= 0.2;
+    }
+    if seq.contains("TTTT") {
+        score -= 0.3;
+    }
+    match seq.chars().last() {
+        Some('G') => score += 0.1,
+        Some('T') => score -= 0.1,
+        _ => {}
+    }
+    let seed: Vec<char> = seq.chars().rev().take(10).collect();
+    if !seed.is_empty() {
+        let seed_gc =
+            seed.iter().filter(|c| matches!(c, 'G' | 'C')).count() as f64 / seed.len() as f64;
+        if (0.3..=0.8).contains(&seed_gc) {
+            score += 0.1;
+        }
+    }
+    score.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_spcas9_sites() {
+        let guide = "ACGTTACGATCGTATCAATC";
+        let dna = Sequence::new(BioType::Dna, format!("AAAA{}TGGAAAA", guide));
+        let sites = find_guide_sites(&dna, SPCAS9_PAM, 20);
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].position, 4);
+        assert_eq!(sites[0].strand, Strand::Forward);
+        assert_eq!(sites[0].guide_seq.seq, guide);
+        assert_eq!(sites[0].pam_seq, "TGG");
+        assert_eq!(sites[0].gc_content, 0.4);
+        // PAM 中的 R 可以代表 G
+        assert_eq!(find_guide_sites(&dna, "NRG", 20), sites);
+
+        // 正链上的 CCA 对应负链上的 PAM TGG
+        let dna = Sequence::new(BioType::Dna, format!("aaaaCCA{}AAAA", guide));
+        let sites = find_guide_sites(&dna, SPCAS9_PAM, 20);
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].position, 7);
+        assert_eq!(sites[0].strand, Strand::Reverse);
+        assert_eq!(sites[0].pam_seq, "TGG");
+        let protospacer = dna.subsequence(7..27).reverse_complementary().unwrap();
+        assert_eq!(sites[0].guide_seq.seq, protospacer.seq);
+
+        let rna = Sequence::new(BioType::Rna, String::from("ACGUGG"));
+        assert!(find_guide_sites(&rna, SPCAS9_PAM, 2).is_empty());
+        assert!(find_guide_sites(&dna, SPCAS9_PAM, 100).is_empty());
+    }
+
+    #[test]
+    fn heuristic_on_target_score() {
+        let site = |guide: &str| {
+            let guide_seq = Sequence::new(BioType::Dna, String::from(guide));
+            GuideSite {
+                position: 0,
+                strand: Strand::Forward,
+                gc_content: guide_seq.gc_content().unwrap(),
+                guide_seq,
+                pam_seq: String::from("AGG"),
+            }
+        };
+        let good = on_target_score(&site("GACGCATAGCTAGCTTCGAG"));
+        let terminator = on_target_score(&site("GACGCATTTTTAGCTTCGAT"));
+        let at_rich = on_target_score(&site("ATATATATATATATATATAT"));
+        assert!((0.0..=1.0).contains(&good));
+        assert!(good > terminator);
+        assert!(good > at_rich);
+    }
+}
@@ -0,0 +1,81 @@
+use super::fasta::DEFAULT_LINE_WIDTH;
+use super::record::NamedSequence;
+use super::sequence::Sequence;
+
+use std::fmt;
+
+// 用于 `write!` 与 `format!` 的几种序列输出格式
+// `Sequence` 本身的 Display 会带上类型信息，这里的包装类型只输出需要的内容
+
+/// 按 fasta 格式输出一条记录：`>id description`，序列每 60 个字符换行，以换行结尾
+pub struct FastaDisplay<'a>(pub &'a NamedSequence);
+
+impl fmt::Display for FastaDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, ">{}", self.0.header())?;
+        for chunk in self.0.sequence.seq.as_bytes().chunks(DEFAULT_LINE_WIDTH) {
+            writeln!(f, "{}", String::from_utf8_lossy(chunk))?;
+        }
+        Ok(())
+    }
+}
+
+/// 只输出序列字符串本身，不换行
+pub struct OneLine<'a>(pub &'a Sequence);
+
+impl fmt::Display for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.seq)
+    }
+}
+
+/// 每行输出第二个参数指定数量的字符，行首是该行第一个字符的位置 (以 1 开始)
+/// 位置右对齐，例如 `  1 ACGTACGTAC`、` 11 GGCC`；数量为 0 时整条序列输出在一行
+pub struct NumberedDisplay<'a>(pub &'a Sequence, pub usize);
+
+impl fmt::Display for NumberedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chars: Vec<char> = self.0.seq.chars().collect();
+        let size = if self.1 == 0 {
+            chars.len().max(1)
+        } else {
+            self.1
+        };
+        let width = chars.len().max(1).to_string().len();
+        for (i, chunk) in chars.chunks(size).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let line: String = chunk.iter().collect();
+            write!(f, "{:>width$} {}", i * size + 1, line, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn sequence_formatters() {
+        let seq = Sequence::new(BioType::Dna, "ACGT".repeat(20));
+        let record = NamedSequence::new(String::from("seq1"), String::from("test"), seq.clone());
+        let fasta = FastaDisplay(&record).to_string();
+        assert_eq!(
+            fasta,
+            format!(">seq1 test\n{}\n{}\n", "ACGT".repeat(15), "ACGT".repeat(5))
+        );
+        assert_eq!(format!("{}", OneLine(&seq)), seq.seq);
+
+        let short = Sequence::new(BioType::Dna, String::from("ACGTACGTACGGCC"));
+        assert_eq!(
+            NumberedDisplay(&short, 10).to_string(),
+            " 1 ACGTACGTAC\n11 GGCC"
+        );
+        assert_eq!(NumberedDisplay(&short, 0).to_string(), " 1 ACGTACGTACGGCC");
+        // 原有的 Display 保持不变
+        assert!(seq.to_string().starts_with("Bio Sequence Type is :DNA"));
+    }
+}
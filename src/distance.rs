@@ -0,0 +1,208 @@
+use super::sequence::Sequence;
+
+// 序列之间的距离，用于聚类、去重与模糊匹配
+// 所有距离都不区分大小写，并要求两条序列的类型相同
+
+/// 检查两条序列的类型是否一致
+fn check_biotype(a: &Sequence, b: &Sequence) -> Result<(), String> {
+    if a.biotype != b.biotype {
+        return Err(format!("序列类型不一致: {} 与 {}", a.biotype, b.biotype));
+    }
+    Ok(())
+}
+
+/// Hamming 距离，即对应位置上不同字符的个数，两条序列长度不同时返回 Err
+pub fn hamming_distance(a: &Sequence, b: &Sequence) -> Result<usize, String> {
+    check_biotype(a, b)?;
+    if a.len() != b.len() {
+        return Err(format!(
+            "Hamming 距离要求序列等长: {} 与 {}",
+            a.len(),
+            b.len()
+        ));
+    }
+    Ok(a.seq
+        .chars()
+        .zip(b.seq.chars())
+        .filter(|(x, y)| !x.eq_ignore_ascii_case(y))
+        .count())
+}
+
+/// Newick 格式的系统发生树，以 `;` 结尾
+pub type NewickString = String;
+
+/// 两两计算 Hamming 距离，返回 n×n 的对称矩阵，`[i][j]` 为第 i 条与第 j 条序列的距离
+/// 序列长度或类型不一致时返回 Err
+pub fn hamming_matrix(seqs: &[Sequence]) -> Result<Vec<Vec<usize>>, String> {
+    let mut matrix = vec![vec![0; seqs.len()]; seqs.len()];
+    for i in 0..seqs.len() {
+        for j in i + 1..seqs.len() {
+            let distance = hamming_distance(&seqs[i], &seqs[j])?;
+            matrix[i][j] = distance;
+            matrix[j][i] = distance;
+        }
+    }
+    Ok(matrix)
+}
+
+/// 用 UPGMA 将距离矩阵聚类为有根树，返回 Newick 字符串
+/// 叶节点以其在矩阵中的下标 (以 0 开始) 命名，分支长度为两端节点高度之差，节点高度为合并时距离的一半；
+/// 距离相同时先合并下标靠前的一对，空矩阵返回 `;`
+/// 矩阵不是方阵时会 panic
+pub fn upgma_tree(dist_matrix: &[Vec<f64>]) -> NewickString {
+    let n = dist_matrix.len();
+    if let Some(row) = dist_matrix.iter().find(|row| row.len() != n) {
+        panic!("距离矩阵不是方阵: {} 行，其中一行有 {} 列", n, row.len());
+    }
+    // 每个簇为 (子树, 叶节点数, 高度)
+    let mut clusters: Vec<(String, usize, f64)> = (0..n).map(|i| (i.to_string(), 1, 0.0)).collect();
+    let mut dist: Vec<Vec<f64>> = dist_matrix.to_vec();
+    while clusters.len() > 1 {
+        let mut closest = (0, 1);
+        for i in 0..clusters.len() {
+            for j in i + 1..clusters.len() {
+                if dist[i][j] < dist[closest.0][closest.1] {
+                    closest = (i, j);
+                }
+            }
+        }
+        let (i, j) = closest;
+        let height = dist[i][j] / 2.0;
+        let (tree_j, size_j, height_j) = clusters.remove(j);
+        let (tree_i, size_i, height_i) = &clusters[i];
+        let merged = (
+            format!(
+                "({}:{},{}:{})",
+                tree_i,
+                height - height_i,
+                tree_j,
+                height - height_j
+            ),
+            size_i + size_j,
+            height,
+        );
+        // 新簇到其他簇的距离为两个子簇距离按叶节点数的加权平均
+        let mut row_j = dist.remove(j);
+        row_j.remove(j);
+        for row in dist.iter_mut() {
+            row.remove(j);
+        }
+        let total = (size_i + size_j) as f64;
+        for k in (0..clusters.len()).filter(|&k| k != i) {
+            let d = (dist[i][k] * *size_i as f64 + row_j[k] * size_j as f64) / total;
+            dist[i][k] = d;
+            dist[k][i] = d;
+        }
+        clusters[i] = merged;
+    }
+    clusters.pop().map(|(tree, _, _)| tree).unwrap_or_default() + ";"
+}
+
+/// Levenshtein 编辑距离，替换、插入、删除的代价均为 1
+pub fn edit_distance(a: &Sequence, b: &Sequence) -> Result<usize, String> {
+    check_biotype(a, b)?;
+    Ok(levenshtein(&a.seq, &b.seq))
+}
+
+/// 以较长序列的长度归一化的编辑距离，取值范围为 0 ~ 1，两条空序列的距离为 0
+pub fn normalized_edit_distance(a: &Sequence, b: &Sequence) -> Result<f64, String> {
+    let distance = edit_distance(a, b)?;
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return Ok(0.0);
+    }
+    Ok(distance as f64 / len as f64)
+}
+
+/// 不区分大小写的 Levenshtein 编辑距离
+pub(crate) fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + !ca.eq_ignore_ascii_case(cb) as usize;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// 编辑距离不超过 `max` 时返回距离，否则提前结束并返回 None
+/// 长度之差已经超过 `max`，或某一行的最小值超过 `max` 时即可结束
+pub(crate) fn levenshtein_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + !ca.eq_ignore_ascii_case(cb) as usize;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&min| min > max) {
+            return None;
+        }
+        previous = current;
+    }
+    Some(previous[b.len()]).filter(|&distance| distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn hamming() {
+        let a = Sequence::new(BioType::Dna, String::from("GATTACA"));
+        let b = Sequence::new(BioType::Dna, String::from("gactaca"));
+        assert_eq!(hamming_distance(&a, &b), Ok(1));
+        let short = Sequence::new(BioType::Dna, String::from("GATT"));
+        assert!(hamming_distance(&a, &short).is_err());
+        let rna = Sequence::new(BioType::Rna, String::from("GAUUACA"));
+        assert!(hamming_distance(&a, &rna).is_err());
+    }
+
+    #[test]
+    fn upgma() {
+        let seqs: Vec<Sequence> = ["AAAA", "AAAT", "ATTT", "TTTT"]
+            .iter()
+            .map(|s| Sequence::new(BioType::Dna, s.to_string()))
+            .collect();
+        let matrix = hamming_matrix(&seqs).unwrap();
+        assert_eq!(matrix[0], vec![0, 1, 3, 4]);
+        assert_eq!(matrix[3][2], 1);
+        let dist: Vec<Vec<f64>> = matrix
+            .iter()
+            .map(|row| row.iter().map(|&d| d as f64).collect())
+            .collect();
+        assert_eq!(upgma_tree(&dist), "((0:0.5,1:0.5):1,(2:0.5,3:0.5):1);");
+        assert_eq!(upgma_tree(&[vec![0.0]]), "0;");
+        assert_eq!(upgma_tree(&[]), ";");
+
+        let short = Sequence::new(BioType::Dna, String::from("AAA"));
+        assert!(hamming_matrix(&[seqs[0].clone(), short]).is_err());
+    }
+
+    #[test]
+    fn levenshtein_distance() {
+        let kitten = Sequence::new(BioType::Protein, String::from("KITTEN"));
+        let sitting = Sequence::new(BioType::Protein, String::from("SITTING"));
+        assert_eq!(edit_distance(&kitten, &sitting), Ok(3));
+        let d = normalized_edit_distance(&kitten, &sitting).unwrap();
+        assert!((d - 3.0 / 7.0).abs() < 1e-9);
+        let empty = Sequence::new(BioType::Protein, String::new());
+        assert_eq!(edit_distance(&empty, &kitten), Ok(6));
+        assert_eq!(normalized_edit_distance(&empty, &empty), Ok(0.0));
+        let dna = Sequence::new(BioType::Dna, String::from("ACGT"));
+        assert!(edit_distance(&kitten, &dna).is_err());
+        assert_eq!(levenshtein_within("KITTEN", "sitting", 3), Some(3));
+        assert_eq!(levenshtein_within("KITTEN", "SITTING", 2), None);
+        assert_eq!(levenshtein_within("A", "AAAA", 2), None);
+    }
+}
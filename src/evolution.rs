@@ -0,0 +1,267 @@
+use super::codon::CodonTable;
+use super::sequence::{BioType, Sequence};
+
+// 编码序列的分子进化分析
+
+/// RNA 形式的四种碱基
+const BASES: [char; 4] = ['U', 'C', 'A', 'G'];
+
+/// 非同义替换率 dN、同义替换率 dS 以及两者的比值 ω = dN / dS
+/// ω > 1 提示正选择，ω < 1 提示纯化选择
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DnDs {
+    pub dn: f64,
+    pub ds: f64,
+    /// dS 为 0 时为无穷大 (dN > 0) 或 NaN (dN 也为 0)
+    pub omega: f64,
+}
+
+/// 用 Nei-Gojobori (1986) 方法计算两条已按密码子对齐的编码序列之间的 dN/dS
+/// 两条序列必须是长度相同且为 3 的倍数的 DNA 或 RNA；含有非 ACGTU 字符 (包括比对空位) 或终止密码子的密码子对会被跳过
+/// - 同义位点数：每个位置上三种单碱基突变中同义突变所占的比例之和，突变为终止密码子视为非同义，两条序列取平均
+/// - 差异数：相差多个碱基的密码子对取所有突变路径的平均，经过终止密码子的路径不计入
+/// - 差异比例经 Jukes-Cantor 校正，比例不小于 0.75 时无法校正，返回错误
+pub fn dnds(seq1: &Sequence, seq2: &Sequence, codon_table: &CodonTable) -> Result<DnDs, String> {
+    for seq in [seq1, seq2] {
+        if seq.biotype == BioType::Protein {
+            return Err(format!("不能对 {} 序列计算 dN/dS", seq.biotype));
+        }
+    }
+    if seq1.len() != seq2.len() {
+        return Err(format!(
+            "两条序列的长度不同: {} 与 {}",
+            seq1.len(),
+            seq2.len()
+        ));
+    }
+    if seq1.len() % 3 != 0 {
+        return Err(format!("编码序列的长度 {} 不是 3 的倍数", seq1.len()));
+    }
+
+    let (mut synonymous_sites, mut sites) = (0.0, 0.0);
+    let (mut synonymous_differences, mut nonsynonymous_differences) = (0.0, 0.0);
+    for (a, b) in seq1.codons(0)?.zip(seq2.codons(0)?) {
+        let (a, b) = match (rna_codon(a), rna_codon(b)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => continue,
+        };
+        if amino_acid(&a, codon_table) == '*' || amino_acid(&b, codon_table) == '*' {
+            continue;
+        }
+        synonymous_sites +=
+            (synonymous_sites_of(&a, codon_table) + synonymous_sites_of(&b, codon_table)) / 2.0;
+        sites += 3.0;
+        let (sd, nd) = differences(&a, &b, codon_table);
+        synonymous_differences += sd;
+        nonsynonymous_differences += nd;
+    }
+    let nonsynonymous_sites = sites - synonymous_sites;
+    if synonymous_sites == 0.0 || nonsynonymous_sites == 0.0 {
+        return Err(String::from("没有可以比较的同义或非同义位点"));
+    }
+
+    let ds = jukes_cantor(synonymous_differences / synonymous_sites)?;
+    let dn = jukes_cantor(nonsynonymous_differences / nonsynonymous_sites)?;
+    Ok(DnDs {
+        dn,
+        ds,
+        omega: dn / ds,
+    })
+}
+
+impl Sequence {
+    /// 读码框 `frame` 中四重简并位点的位置 (以 0 开始)，即第三位突变为任意碱基都是同义突变的密码子第三位，
+    /// 如 GCN (Ala)、GGN (Gly)；常用于只在中性位点上估计替换速率
+    /// 使用标准密码子表，终止密码子与含有非 ACGTU 字符的密码子会被跳过，末尾不足三个碱基的部分会被忽略
+    /// 蛋白质序列或读码框不是 0、1 或 2 时返回错误
+    pub fn four_fold_degenerate_sites(&self, frame: usize) -> Result<Vec<usize>, String> {
+        self.degenerate_third_positions(frame, 3)
+    }
+
+    /// 读码框 `frame` 中二重简并位点的位置 (以 0 开始)，即第三位的三种突变中恰有一种为同义突变，
+    /// 如 AAR (Lys)、GAY (Asp)；只考虑密码子第三位，其余规则与 `four_fold_degenerate_sites` 相同
+    pub fn two_fold_degenerate_sites(&self, frame: usize) -> Result<Vec<usize>, String> {
+        self.degenerate_third_positions(frame, 1)
+    }
+
+    /// 第三位的三种突变中恰有 `synonymous` 种为同义突变的位置，突变为终止密码子视为非同义
+    fn degenerate_third_positions(
+        &self,
+        frame: usize,
+        synonymous: usize,
+    ) -> Result<Vec<usize>, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("{} 序列没有密码子", self.biotype));
+        }
+        let table = CodonTable::standard();
+        Ok(self
+            .codons(frame)?
+            .enumerate()
+            .filter_map(|(i, codon)| Some((frame + 3 * i + 2, rna_codon(codon)?)))
+            .filter(|(_, codon)| {
+                let original = amino_acid(codon, &table);
+                original != '*'
+                    && BASES
+                        .iter()
+                        .filter(|&&base| base != codon[2])
+                        .filter(|&&base| {
+                            amino_acid(&[codon[0], codon[1], base], &table) == original
+                        })
+                        .count()
+                        == synonymous
+            })
+            .map(|(position, _)| position)
+            .collect())
+    }
+}
+
+/// 大写 RNA 形式的密码子，含有非 ACGTU 字符时返回 None
+fn rna_codon(codon: &str) -> Option<[char; 3]> {
+    let mut bases = ['\0'; 3];
+    let mut chars = codon.chars();
+    for base in bases.iter_mut() {
+        *base = match chars.next()?.to_ascii_uppercase() {
+            'T' | 'U' => 'U',
+            c @ ('A' | 'C' | 'G') => c,
+            _ => return None,
+        };
+    }
+    Some(bases)
+}
+
+fn amino_acid(codon: &[char; 3], table: &CodonTable) -> char {
+    let codon: String = codon.iter().collect();
+    table.translate_codon(&codon).unwrap_or('X')
+}
+
+/// 一个密码子的同义位点数
+fn synonymous_sites_of(codon: &[char; 3], table: &CodonTable) -> f64 {
+    let original = amino_acid(codon, table);
+    let mut synonymous = 0;
+    for position in 0..3 {
+        for base in BASES {
+            if base == codon[position] {
+                continue;
+            }
+            let mut mutant = *codon;
+            mutant[position] = base;
+            if amino_acid(&mutant, table) == original {
+                synonymous += 1;
+            }
+        }
+    }
+    synonymous as f64 / 3.0
+}
+
+/// 两个密码子之间的同义与非同义差异数，相差多个碱基时取所有突变路径的平均
+/// 所有路径都经过终止密码子时改为对全部路径取平均
+fn differences(a: &[char; 3], b: &[char; 3], table: &CodonTable) -> (f64, f64) {
+    let positions: Vec<usize> = (0..3).filter(|&i| a[i] != b[i]).collect();
+    let mut orders: Vec<Vec<usize>> = Vec::new();
+    permutations(&positions, &mut Vec::new(), &mut orders);
+
+    let walk = |order: &[usize]| {
+        let (mut synonymous, mut nonsynonymous, mut through_stop) = (0.0, 0.0, false);
+        let mut current = *a;
+        for &position in order {
+            let mut next = current;
+            next[position] = b[position];
+            let next_amino_acid = amino_acid(&next, table);
+            if next_amino_acid == '*' {
+                through_stop = true;
+            }
+            if next_amino_acid == amino_acid(&current, table) {
+                synonymous += 1.0;
+            } else {
+                nonsynonymous += 1.0;
+            }
+            current = next;
+        }
+        (synonymous, nonsynonymous, through_stop)
+    };
+    let paths: Vec<(f64, f64, bool)> = orders.iter().map(|order| walk(order)).collect();
+    let valid: Vec<&(f64, f64, bool)> = paths.iter().filter(|path| !path.2).collect();
+    let used: Vec<&(f64, f64, bool)> = if valid.is_empty() {
+        paths.iter().collect()
+    } else {
+        valid
+    };
+    let count = used.len() as f64;
+    (
+        used.iter().map(|path| path.0).sum::<f64>() / count,
+        used.iter().map(|path| path.1).sum::<f64>() / count,
+    )
+}
+
+/// `items` 的全部排列
+fn permutations(items: &[usize], current: &mut Vec<usize>, result: &mut Vec<Vec<usize>>) {
+    if current.len() == items.len() {
+        result.push(current.clone());
+        return;
+    }
+    for &item in items {
+        if !current.contains(&item) {
+            current.push(item);
+            permutations(items, current, result);
+            current.pop();
+        }
+    }
+}
+
+/// Jukes-Cantor 校正：d = -3/4 ln(1 - 4p/3)
+fn jukes_cantor(p: f64) -> Result<f64, String> {
+    if p >= 0.75 {
+        return Err(format!(
+            "差异比例 {:.3} 过大，无法进行 Jukes-Cantor 校正",
+            p
+        ));
+    }
+    Ok(-0.75 * (1.0 - 4.0 * p / 3.0).ln())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nei_gojobori() {
+        let table = CodonTable::standard();
+        // 第 2 个密码子为同义替换 (CTG -> CTA)，第 4 个为非同义替换 (AAA -> GAA)
+        let seq1 = Sequence::new(BioType::Dna, String::from("ATGCTGGCTAAAGGCTTTCGTACC"));
+        let seq2 = Sequence::new(BioType::Dna, String::from("ATGCTAGCTGAAGGCTTTCGTACC"));
+        let result = dnds(&seq1, &seq2, &table).unwrap();
+        assert!((result.ds - 0.188_486).abs() < 1e-5);
+        assert!((result.dn - 0.057_721).abs() < 1e-5);
+        assert!((result.omega - result.dn / result.ds).abs() < 1e-12);
+
+        // GGA -> TGG 有两条路径，先突变第 1 位会经过终止密码子 TGA，只计入 GGA -> GGG -> TGG
+        let a = rna_codon("GGA").unwrap();
+        let b = rna_codon("TGG").unwrap();
+        assert_eq!(differences(&a, &b, &table), (1.0, 1.0));
+        assert!(
+            (synonymous_sites_of(&rna_codon("CTG").unwrap(), &table) - 4.0 / 3.0).abs() < 1e-12
+        );
+
+        let identical = dnds(&seq1, &seq1, &table).unwrap();
+        assert_eq!((identical.dn, identical.ds), (0.0, 0.0));
+        assert!(identical.omega.is_nan());
+        assert!(dnds(&seq1, &seq2.subsequence(..21), &table).is_err());
+        assert!(dnds(&seq1.subsequence(..4), &seq2.subsequence(..4), &table).is_err());
+    }
+
+    #[test]
+    fn degenerate_sites() {
+        // GCT (Ala) 与 GGC (Gly) 四重简并，AAA (Lys) 与 TTA (Leu) 二重简并，
+        // TGA 为终止密码子，TGG (Trp) 与 ATT (Ile) 两者都不是
+        let cds = Sequence::new(BioType::Dna, String::from("GCTAAATTAggcTGATGGATT"));
+        assert_eq!(cds.four_fold_degenerate_sites(0).unwrap(), vec![2, 11]);
+        assert_eq!(cds.two_fold_degenerate_sites(0).unwrap(), vec![5, 8]);
+        let shifted = Sequence::new(BioType::Rna, String::from("AGCUAAAN"));
+        assert_eq!(shifted.four_fold_degenerate_sites(1).unwrap(), vec![3]);
+        assert_eq!(shifted.two_fold_degenerate_sites(1).unwrap(), vec![6]);
+        assert!(cds.four_fold_degenerate_sites(3).is_err());
+        assert!(Sequence::new(BioType::Protein, String::from("MA"))
+            .two_fold_degenerate_sites(0)
+            .is_err());
+    }
+}
@@ -0,0 +1,651 @@
+use super::record::NamedSequence;
+use super::sequence::{BioType, Sequence};
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+// fasta 文件的读写
+// 每条记录以 `>` 开头的标题行起始，第一个空格前为 id，之后为描述
+// 序列可以跨越多行，读取时会拼接为一条
+
+/// fasta 读写过程中可能出现的错误
+#[derive(Debug)]
+pub enum FastaError {
+    /// 文件不存在
+    NotFound(PathBuf),
+    /// 其他读写错误
+    Io(io::Error),
+    /// 在第一个标题行之前出现了序列数据
+    MissingHeader { line: usize },
+    /// 标题行中没有 id
+    EmptyId { line: usize },
+    /// 建立索引时同一条序列中除最后一行外的行长度不一致
+    InconsistentLineLength { line: usize },
+    /// .fai 索引文件中的行格式不正确
+    InvalidIndex { line: usize },
+    /// 索引中没有该序列
+    UnknownSequence(String),
+    /// 请求的区间超出序列长度或起点大于终点
+    OutOfRange {
+        name: String,
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+}
+
+impl fmt::Display for FastaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FastaError::NotFound(path) => write!(f, "找不到 fasta 文件: {}", path.display()),
+            FastaError::Io(err) => write!(f, "fasta 读写错误: {}", err),
+            FastaError::MissingHeader { line } => {
+                write!(f, "第 {} 行: 序列数据出现在标题行 '>' 之前", line)
+            }
+            FastaError::EmptyId { line } => write!(f, "第 {} 行: 标题行缺少序列 id", line),
+            FastaError::InconsistentLineLength { line } => {
+                write!(f, "第 {} 行: 序列的行长度不一致，无法建立索引", line)
+            }
+            FastaError::InvalidIndex { line } => write!(f, "索引第 {} 行: 格式不正确", line),
+            FastaError::UnknownSequence(name) => write!(f, "索引中没有序列: {}", name),
+            FastaError::OutOfRange {
+                name,
+                start,
+                end,
+                len,
+            } => write!(
+                f,
+                "区间 {}..{} 超出序列 {} 的长度 {}",
+                start, end, name, len
+            ),
+        }
+    }
+}
+
+impl error::Error for FastaError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FastaError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FastaError {
+    fn from(err: io::Error) -> Self {
+        FastaError::Io(err)
+    }
+}
+
+/// 读取 fasta 文件中的全部记录，序列类型根据字符组成自动推断
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<NamedSequence>, FastaError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => FastaError::NotFound(path.to_path_buf()),
+        _ => FastaError::Io(err),
+    })?;
+    parse(BufReader::new(file))
+}
+
+/// 从任意 BufRead 中解析 fasta 记录
+/// 会去除行尾的 `\r` 以兼容 Windows 换行，空行与 `;` 开头的注释行会被忽略
+pub fn parse(reader: impl BufRead) -> Result<Vec<NamedSequence>, FastaError> {
+    let mut records: Vec<NamedSequence> = Vec::new();
+    let mut current: Option<NamedSequence> = None;
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some(record) = current.take() {
+                records.push(finish(record));
+            }
+            let header = header.trim();
+            let (id, description) = header
+                .split_once(char::is_whitespace)
+                .unwrap_or((header, ""));
+            if id.is_empty() {
+                return Err(FastaError::EmptyId { line: number + 1 });
+            }
+            current = Some(NamedSequence::new(
+                id.to_string(),
+                description.trim().to_string(),
+                Sequence::new(BioType::Dna, String::new()),
+            ));
+        } else if line.trim().is_empty() || line.starts_with(';') {
+            continue;
+        } else {
+            match current {
+                Some(ref mut record) => record.sequence.seq.push_str(line.trim()),
+                None => return Err(FastaError::MissingHeader { line: number + 1 }),
+            }
+        }
+    }
+
+    if let Some(record) = current {
+        records.push(finish(record));
+    }
+    Ok(records)
+}
+
+/// 写出 fasta 时默认的每行碱基数
+pub const DEFAULT_LINE_WIDTH: usize = 60;
+
+/// 将记录写入 fasta 文件，序列每 `line_width` 个字符换行，为 0 时整条序列写在一行
+pub fn write(
+    records: &[NamedSequence],
+    path: impl AsRef<Path>,
+    line_width: usize,
+) -> Result<(), FastaError> {
+    let file = File::create(path)?;
+    write_to(BufWriter::new(file), records, line_width)
+}
+
+/// 以默认的 60 字符行宽写入 fasta 文件
+pub fn write_default(records: &[NamedSequence], path: impl AsRef<Path>) -> Result<(), FastaError> {
+    write(records, path, DEFAULT_LINE_WIDTH)
+}
+
+/// 将记录以 fasta 格式写入任意 Write
+pub fn write_to(
+    mut writer: impl Write,
+    records: &[NamedSequence],
+    line_width: usize,
+) -> Result<(), FastaError> {
+    for record in records {
+        writeln!(writer, ">{}", record.header())?;
+        let seq = record.sequence.seq.as_bytes();
+        if line_width == 0 {
+            writer.write_all(seq)?;
+            writeln!(writer)?;
+        } else {
+            for chunk in seq.chunks(line_width) {
+                writer.write_all(chunk)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 读取 gzip 压缩的 fasta 文件，由多个 gzip 成员拼接而成的文件 (包括 samtools 使用的 BGZF) 同样可以读取
+#[cfg(feature = "gzip")]
+pub fn read_gz(path: impl AsRef<Path>) -> Result<Vec<NamedSequence>, FastaError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => FastaError::NotFound(path.to_path_buf()),
+        _ => FastaError::Io(err),
+    })?;
+    parse(BufReader::new(flate2::read::MultiGzDecoder::new(file)))
+}
+
+/// 以默认的 60 字符行宽写入 gzip 压缩的 fasta 文件
+#[cfg(feature = "gzip")]
+pub fn write_gz(records: &[NamedSequence], path: impl AsRef<Path>) -> Result<(), FastaError> {
+    let file = File::create(path)?;
+    let mut encoder =
+        flate2::write::GzEncoder::new(BufWriter::new(file), flate2::Compression::default());
+    write_to(&mut encoder, records, DEFAULT_LINE_WIDTH)?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+/// 以默认的 60 字符行宽写入 BGZF 压缩的 fasta 文件，可以直接用于 `samtools faidx`
+#[cfg(feature = "gzip")]
+pub fn write_bgzf(records: &[NamedSequence], path: impl AsRef<Path>) -> Result<(), FastaError> {
+    let file = File::create(path)?;
+    let mut writer = BgzfWriter::new(BufWriter::new(file));
+    write_to(&mut writer, records, DEFAULT_LINE_WIDTH)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// BGZF 每个块最多压缩的数据量，与 htslib 一致
+#[cfg(feature = "gzip")]
+const BGZF_BLOCK_SIZE: usize = 0xff00;
+
+/// BGZF 文件末尾的空块
+#[cfg(feature = "gzip")]
+const BGZF_EOF: [u8; 28] = [
+    0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0, 0x42, 0x43, 0x02, 0, 0x1b, 0, 0x03, 0, 0,
+    0, 0, 0, 0, 0, 0, 0,
+];
+
+/// BGZF 写入器：数据被切分为独立压缩的 gzip 块，每块的头部记录块的大小，便于随机访问
+#[cfg(feature = "gzip")]
+struct BgzfWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "gzip")]
+impl<W: Write> BgzfWriter<W> {
+    fn new(inner: W) -> Self {
+        BgzfWriter {
+            inner,
+            buffer: Vec::with_capacity(BGZF_BLOCK_SIZE),
+        }
+    }
+
+    /// 压缩并写出缓冲区中的数据
+    fn write_block(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&self.buffer)?;
+        let compressed = encoder.finish()?;
+        let mut crc = flate2::Crc::new();
+        crc.update(&self.buffer);
+        // 头部 18 字节 + 压缩数据 + CRC32 与原始长度 8 字节，BSIZE 为块大小减 1
+        let block_size = (18 + compressed.len() + 8 - 1) as u16;
+        self.inner.write_all(&[
+            0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0, 0x42, 0x43, 0x02, 0,
+        ])?;
+        self.inner.write_all(&block_size.to_le_bytes())?;
+        self.inner.write_all(&compressed)?;
+        self.inner.write_all(&crc.sum().to_le_bytes())?;
+        self.inner
+            .write_all(&(self.buffer.len() as u32).to_le_bytes())?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// 写出剩余数据与结尾的空块
+    fn finish(mut self) -> io::Result<()> {
+        self.write_block()?;
+        self.inner.write_all(&BGZF_EOF)?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<W: Write> Write for BgzfWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let len = data.len().min(BGZF_BLOCK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&data[..len]);
+        if self.buffer.len() == BGZF_BLOCK_SIZE {
+            self.write_block()?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_block()?;
+        self.inner.flush()
+    }
+}
+
+/// .fai 索引中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaiEntry {
+    pub name: String,
+    /// 序列长度
+    pub length: usize,
+    /// 序列第一个碱基在文件中的字节偏移
+    pub offset: u64,
+    /// 每行的碱基数
+    pub line_bases: usize,
+    /// 每行的字节数 (包括换行符)
+    pub line_bytes: usize,
+}
+
+/// fasta 文件的索引 (与 `samtools faidx` 生成的 .fai 格式相同)，可以不读入整个文件而随机访问序列
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaIndex {
+    pub entries: Vec<FaiEntry>,
+}
+
+impl FaIndex {
+    /// 读取已有的 .fai 索引文件
+    pub fn read(fai_path: impl AsRef<Path>) -> Result<FaIndex, FastaError> {
+        let path = fai_path.as_ref();
+        let file = File::open(path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => FastaError::NotFound(path.to_path_buf()),
+            _ => FastaError::Io(err),
+        })?;
+        let mut entries = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || FastaError::InvalidIndex { line: number + 1 };
+            let columns: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+            if columns.len() < 5 {
+                return Err(invalid());
+            }
+            let entry = FaiEntry {
+                name: columns[0].to_string(),
+                length: columns[1].parse().map_err(|_| invalid())?,
+                offset: columns[2].parse().map_err(|_| invalid())?,
+                line_bases: columns[3].parse().map_err(|_| invalid())?,
+                line_bytes: columns[4].parse().map_err(|_| invalid())?,
+            };
+            // 非空序列每行至少有一个碱基，且每行的字节数不少于碱基数
+            if entry.length > 0 && (entry.line_bases == 0 || entry.line_bytes < entry.line_bases) {
+                return Err(invalid());
+            }
+            entries.push(entry);
+        }
+        Ok(FaIndex { entries })
+    }
+
+    /// 将索引写入 .fai 文件
+    pub fn write(&self, fai_path: impl AsRef<Path>) -> Result<(), FastaError> {
+        let mut writer = BufWriter::new(File::create(fai_path)?);
+        for entry in self.entries.iter() {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                entry.name, entry.length, entry.offset, entry.line_bases, entry.line_bytes
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 按名称查找索引记录
+    pub fn get(&self, name: &str) -> Option<&FaiEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// 从建立索引的 fasta 文件中读取序列 `seqname` 的区间 `start..end` (以 0 开始，不含终点)
+    /// 只读取所需的字节，序列类型根据所取片段的字符组成推断
+    pub fn fetch(
+        &self,
+        fasta_path: impl AsRef<Path>,
+        seqname: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<Sequence, FastaError> {
+        let entry = self
+            .get(seqname)
+            .ok_or_else(|| FastaError::UnknownSequence(seqname.to_string()))?;
+        if start > end || end > entry.length {
+            return Err(FastaError::OutOfRange {
+                name: seqname.to_string(),
+                start,
+                end,
+                len: entry.length,
+            });
+        }
+        if start == end {
+            return Ok(Sequence::new(BioType::infer(""), String::new()));
+        }
+        let position = |base: usize| {
+            let base = base as u64;
+            let (line_bases, line_bytes) = (entry.line_bases as u64, entry.line_bytes as u64);
+            entry.offset + base / line_bases * line_bytes + base % line_bases
+        };
+        let first = position(start);
+        let last = position(end - 1);
+        let mut file = File::open(fasta_path)?;
+        file.seek(SeekFrom::Start(first))?;
+        let mut bytes = vec![0; (last - first + 1) as usize];
+        file.read_exact(&mut bytes)?;
+        let seq: String = bytes
+            .iter()
+            .filter(|&&b| b != b'\n' && b != b'\r')
+            .map(|&b| b as char)
+            .collect();
+        Ok(Sequence::new(BioType::infer(&seq), seq))
+    }
+}
+
+/// 为 fasta 文件建立索引，写入同目录下的 `<文件名>.fai` 并返回索引
+/// 与 samtools 一致，每条序列除最后一行外的行长度必须相同
+pub fn index(fasta_path: impl AsRef<Path>) -> Result<FaIndex, FastaError> {
+    let path = fasta_path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => FastaError::NotFound(path.to_path_buf()),
+        _ => FastaError::Io(err),
+    })?;
+    let index = build_index(BufReader::new(file))?;
+    let mut fai_path = path.as_os_str().to_owned();
+    fai_path.push(".fai");
+    index.write(PathBuf::from(fai_path))?;
+    Ok(index)
+}
+
+/// 逐行扫描并记录每条序列的偏移与行宽
+fn build_index(mut reader: impl BufRead) -> Result<FaIndex, FastaError> {
+    let mut entries: Vec<FaiEntry> = Vec::new();
+    // 当前序列是否已经出现过较短的行，之后只能是新的标题行
+    let mut ended = false;
+    let mut offset = 0u64;
+    let mut line = Vec::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        let bytes = reader.read_until(b'\n', &mut line)?;
+        if bytes == 0 {
+            break;
+        }
+        number += 1;
+        offset += bytes as u64;
+        if line[0] == b'>' {
+            let header = String::from_utf8_lossy(&line[1..]);
+            let name = header.split_whitespace().next().unwrap_or("");
+            if name.is_empty() {
+                return Err(FastaError::EmptyId { line: number });
+            }
+            entries.push(FaiEntry {
+                name: name.to_string(),
+                length: 0,
+                offset,
+                line_bases: 0,
+                line_bytes: 0,
+            });
+            ended = false;
+            continue;
+        }
+        let entry = entries
+            .last_mut()
+            .ok_or(FastaError::MissingHeader { line: number })?;
+        let bases = line
+            .iter()
+            .rev()
+            .skip_while(|&&b| b == b'\n' || b == b'\r')
+            .count();
+        if bases == 0 {
+            ended = true;
+            continue;
+        }
+        if ended {
+            return Err(FastaError::InconsistentLineLength { line: number });
+        }
+        if entry.line_bases == 0 {
+            entry.line_bases = bases;
+            entry.line_bytes = bytes;
+        } else if bases > entry.line_bases
+            || (bases == entry.line_bases && bytes != entry.line_bytes)
+        {
+            return Err(FastaError::InconsistentLineLength { line: number });
+        }
+        if bases < entry.line_bases || bytes == bases {
+            ended = true;
+        }
+        entry.length += bases;
+    }
+    Ok(FaIndex { entries })
+}
+
+/// 记录读取完毕后再推断类型
+fn finish(mut record: NamedSequence) -> NamedSequence {
+    record.sequence.biotype = BioType::infer(&record.sequence.seq);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn parse_wrapped_records() {
+        let data =
+            ">seq1 first record\r\nACGT\r\nACGT\r\n\n>seq2\nAUGC\nAU\n>prot protein\nMKV\nLE*\n>pep\nMKVHRDSW\n";
+        let records = parse(Cursor::new(data)).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].id, "seq1");
+        assert_eq!(records[0].description, "first record");
+        assert_eq!(records[0].sequence.seq, "ACGTACGT");
+        assert_eq!(records[0].sequence.biotype, BioType::Dna);
+        assert_eq!(records[1].description, "");
+        assert!(records[1].metadata.is_empty());
+        assert_eq!(records[1].sequence.seq, "AUGCAU");
+        assert_eq!(records[1].sequence.biotype, BioType::Rna);
+        assert_eq!(records[2].sequence.biotype, BioType::Protein);
+        // 没有终止符、只由 IUPAC 字母组成的短肽
+        assert_eq!(records[3].sequence.biotype, BioType::Protein);
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert!(matches!(
+            parse(Cursor::new("ACGT\n>seq1\nACGT\n")),
+            Err(FastaError::MissingHeader { line: 1 })
+        ));
+        assert!(matches!(
+            parse(Cursor::new(">seq1\nACGT\n> \nACGT\n")),
+            Err(FastaError::EmptyId { line: 3 })
+        ));
+        assert!(matches!(
+            read("tests/data/missing.fasta"),
+            Err(FastaError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn write_wrapped_records() {
+        let records = vec![
+            NamedSequence::new(
+                String::from("seq1"),
+                String::from("first record"),
+                Sequence::new(BioType::Dna, String::from("ACGTACGTAC")),
+            ),
+            NamedSequence::new(
+                String::from("seq2"),
+                String::new(),
+                Sequence::new(BioType::Rna, String::from("AUGC")),
+            ),
+        ];
+        let mut output: Vec<u8> = Vec::new();
+        write_to(&mut output, &records, 4).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">seq1 first record\nACGT\nACGT\nAC\n>seq2\nAUGC\n"
+        );
+
+        let mut output: Vec<u8> = Vec::new();
+        write_to(&mut output, &records[..1], 0).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">seq1 first record\nACGTACGTAC\n"
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_round_trip() {
+        let records = vec![
+            NamedSequence::new(
+                String::from("circ1"),
+                String::from("[organism=Homo sapiens]"),
+                Sequence::new(BioType::Rna, "AUGGCC".repeat(20_000)),
+            ),
+            NamedSequence::new(
+                String::from("circ2"),
+                String::new(),
+                Sequence::new(BioType::Dna, String::from("ACGT")),
+            ),
+        ];
+        let dir = std::env::temp_dir();
+        let gz = dir.join(format!("biocirc-{}.fasta.gz", std::process::id()));
+        let bgzf = dir.join(format!("biocirc-{}.fasta.bgz", std::process::id()));
+        write_gz(&records, &gz).unwrap();
+        write_bgzf(&records, &bgzf).unwrap();
+        for path in [&gz, &bgzf] {
+            let read = read_gz(path).unwrap();
+            assert_eq!(read.len(), 2);
+            assert_eq!(read[0].sequence, records[0].sequence);
+            assert_eq!(read[1].sequence, records[1].sequence);
+            assert_eq!(read[0].metadata.organism.as_deref(), Some("Homo sapiens"));
+        }
+        // BGZF 由多个块组成，并以固定的空块结尾
+        let data = std::fs::read(&bgzf).unwrap();
+        assert!(data.ends_with(&BGZF_EOF));
+        assert!(data.len() > 2 * BGZF_EOF.len());
+        std::fs::remove_file(gz).unwrap();
+        std::fs::remove_file(bgzf).unwrap();
+        assert!(matches!(
+            read_gz("tests/data/missing.fasta.gz"),
+            Err(FastaError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn fai_index_and_fetch() {
+        let path = std::env::temp_dir().join(format!("biocirc-{}.fa", std::process::id()));
+        let data =
+            ">chr1 test\nACGTACGTAC\nGGGGCCCCTT\nAAT\n>chr2\r\nAUGC\r\nAU\r\n>empty\n>chr3\nMKV";
+        std::fs::write(&path, data).unwrap();
+        let fai = index(&path).unwrap();
+        let mut fai_path = path.as_os_str().to_owned();
+        fai_path.push(".fai");
+        assert_eq!(
+            std::fs::read_to_string(&fai_path).unwrap(),
+            "chr1\t23\t11\t10\t11\nchr2\t6\t44\t4\t6\nempty\t0\t61\t0\t0\nchr3\t3\t67\t3\t3\n"
+        );
+        assert_eq!(FaIndex::read(&fai_path).unwrap(), fai);
+
+        assert_eq!(
+            fai.fetch(&path, "chr1", 8, 21).unwrap().seq,
+            "ACGGGGCCCCTTA"
+        );
+        assert_eq!(fai.fetch(&path, "chr1", 0, 23).unwrap().seq.len(), 23);
+        let rna = fai.fetch(&path, "chr2", 2, 6).unwrap();
+        assert_eq!(rna.seq, "GCAU");
+        assert_eq!(rna.biotype, BioType::Rna);
+        let protein = fai.fetch(&path, "chr3", 0, 3).unwrap();
+        assert_eq!(protein.seq, "MKV");
+        assert_eq!(protein.biotype, BioType::Protein);
+        assert!(fai.fetch(&path, "empty", 0, 0).unwrap().is_empty());
+        assert!(matches!(
+            fai.fetch(&path, "chr1", 20, 24),
+            Err(FastaError::OutOfRange { len: 23, .. })
+        ));
+        assert!(matches!(
+            fai.fetch(&path, "chrX", 0, 1),
+            Err(FastaError::UnknownSequence(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        for bad in [
+            "chr1\t23\t11\t0\t0\n",
+            "chr1\t23\t11\t10\t9\n",
+            "chr1\t23\t11\n",
+        ] {
+            std::fs::write(&fai_path, format!("chr2\t6\t44\t4\t6\n{}", bad)).unwrap();
+            assert!(matches!(
+                FaIndex::read(&fai_path),
+                Err(FastaError::InvalidIndex { line: 2 })
+            ));
+        }
+        std::fs::remove_file(&fai_path).unwrap();
+
+        assert!(matches!(
+            build_index(Cursor::new(">a\nACG\nACGT\n")),
+            Err(FastaError::InconsistentLineLength { line: 3 })
+        ));
+        assert!(matches!(
+            build_index(Cursor::new(">a\nACGT\nAC\nACGT\n")),
+            Err(FastaError::InconsistentLineLength { line: 4 })
+        ));
+    }
+}
@@ -0,0 +1,366 @@
+use super::sequence::{BioType, Sequence};
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+// fastq 文件的读取
+// 每条记录固定为四行：`@id 描述`、序列、`+` (可重复 id)、Phred+33 编码的质量值
+
+/// Phred+33 编码的偏移量
+const PHRED_OFFSET: u8 = 33;
+
+/// fastq 读取过程中可能出现的错误
+#[derive(Debug)]
+pub enum FastqError {
+    /// 文件不存在
+    NotFound(PathBuf),
+    /// 其他读写错误
+    Io(io::Error),
+    /// 标题行不是以 `@` 开头或缺少 id
+    InvalidHeader { line: usize },
+    /// 第三行不是以 `+` 开头，或重复的 id 与标题不一致
+    InvalidSeparator { line: usize },
+    /// 质量值中出现了 Phred+33 范围以外的字符
+    InvalidQuality { line: usize, ch: char },
+    /// 质量值长度与序列长度不一致
+    LengthMismatch {
+        id: String,
+        seq_len: usize,
+        qual_len: usize,
+    },
+    /// 文件在记录中途结束
+    Truncated { line: usize },
+}
+
+impl fmt::Display for FastqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FastqError::NotFound(path) => write!(f, "找不到 fastq 文件: {}", path.display()),
+            FastqError::Io(err) => write!(f, "fastq 读写错误: {}", err),
+            FastqError::InvalidHeader { line } => {
+                write!(f, "第 {} 行: 标题行应以 '@' 开头并包含 id", line)
+            }
+            FastqError::InvalidSeparator { line } => {
+                write!(f, "第 {} 行: 分隔行应以 '+' 开头且 id 与标题一致", line)
+            }
+            FastqError::InvalidQuality { line, ch } => {
+                write!(f, "第 {} 行: 非法的质量字符 '{}'", line, ch)
+            }
+            FastqError::LengthMismatch {
+                id,
+                seq_len,
+                qual_len,
+            } => write!(
+                f,
+                "记录 {}: 序列长度 {} 与质量值长度 {} 不一致",
+                id, seq_len, qual_len
+            ),
+            FastqError::Truncated { line } => write!(f, "第 {} 行: 记录不完整", line),
+        }
+    }
+}
+
+impl error::Error for FastqError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FastqError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FastqError {
+    fn from(err: io::Error) -> Self {
+        FastqError::Io(err)
+    }
+}
+
+/// 一条 fastq 记录，qual 中保存解码后的 Phred 质量值
+#[derive(Debug, Clone)]
+pub struct FastqRecord {
+    pub id: String,
+    pub description: String,
+    pub seq: Sequence,
+    pub qual: Vec<u8>,
+}
+
+impl FastqRecord {
+    /// 新建 FastqRecord 对象
+    pub fn new(id: String, description: String, seq: Sequence, qual: Vec<u8>) -> Self {
+        FastqRecord {
+            id,
+            description,
+            seq,
+            qual,
+        }
+    }
+
+    /// 平均质量值，空记录返回 0.0
+    pub fn mean_quality(&self) -> f64 {
+        if self.qual.is_empty() {
+            return 0.0;
+        }
+        self.qual.iter().map(|&q| q as f64).sum::<f64>() / self.qual.len() as f64
+    }
+}
+
+/// 逐条读取 fastq 记录的迭代器，不会一次性把整个文件载入内存
+pub struct Reader<R: BufRead> {
+    reader: R,
+    line: usize,
+    buffer: String,
+}
+
+impl Reader<BufReader<File>> {
+    /// 打开 fastq 文件
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FastqError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => FastqError::NotFound(path.to_path_buf()),
+            _ => FastqError::Io(err),
+        })?;
+        Ok(Reader::new(BufReader::new(file)))
+    }
+}
+
+impl<R: BufRead> Reader<R> {
+    /// 从任意 BufRead 新建 Reader
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader,
+            line: 0,
+            buffer: String::new(),
+        }
+    }
+
+    /// 读取下一行并去除行尾换行符，文件结束时返回 None
+    fn next_line(&mut self) -> Result<Option<String>, FastqError> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        Ok(Some(self.buffer.trim_end_matches(['\n', '\r']).to_string()))
+    }
+
+    /// 读取记录中必须存在的一行
+    fn expect_line(&mut self) -> Result<String, FastqError> {
+        let line = self.line;
+        self.next_line()?
+            .ok_or(FastqError::Truncated { line: line + 1 })
+    }
+
+    fn read_record(&mut self) -> Result<Option<FastqRecord>, FastqError> {
+        let header = loop {
+            match self.next_line()? {
+                Some(line) if line.trim().is_empty() => continue,
+                Some(line) => break line,
+                None => return Ok(None),
+            }
+        };
+        let header = header
+            .strip_prefix('@')
+            .ok_or(FastqError::InvalidHeader { line: self.line })?;
+        let (id, description) = header
+            .split_once(char::is_whitespace)
+            .unwrap_or((header, ""));
+        if id.is_empty() {
+            return Err(FastqError::InvalidHeader { line: self.line });
+        }
+        let (id, description) = (id.to_string(), description.trim().to_string());
+
+        let seq = self.expect_line()?.trim().to_string();
+
+        let separator = self.expect_line()?;
+        match separator.strip_prefix('+') {
+            Some(repeated)
+                if repeated.is_empty() || repeated.split_whitespace().next() == Some(&id) => {}
+            _ => return Err(FastqError::InvalidSeparator { line: self.line }),
+        }
+
+        let qual_line = self.expect_line()?;
+        let qual = decode_quality(qual_line.trim(), self.line)?;
+        if qual.len() != seq.len() {
+            return Err(FastqError::LengthMismatch {
+                id,
+                seq_len: seq.len(),
+                qual_len: qual.len(),
+            });
+        }
+
+        let biotype = BioType::infer(&seq);
+        Ok(Some(FastqRecord::new(
+            id,
+            description,
+            Sequence::new(biotype, seq),
+            qual,
+        )))
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<FastqRecord, FastqError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// 解码 Phred+33 质量字符串
+fn decode_quality(qual: &str, line: usize) -> Result<Vec<u8>, FastqError> {
+    qual.chars()
+        .map(|ch| match ch {
+            '!'..='~' => Ok(ch as u8 - PHRED_OFFSET),
+            _ => Err(FastqError::InvalidQuality { line, ch }),
+        })
+        .collect()
+}
+
+/// 读取 fastq 文件中的全部记录
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<FastqRecord>, FastqError> {
+    Reader::from_path(path)?.collect()
+}
+
+/// 按滑动窗口修剪低质量的两端 (类似 Trimmomatic 的 SLIDINGWINDOW 与 BWA 的 -q 修剪)
+/// 从 5' 端向后找到第一个平均质量值超过 `threshold` 的长度为 `window` 的窗口，以窗口起点为新的起点；
+/// 从 3' 端向前找到最后一个这样的窗口，以窗口终点为新的终点；记录短于 `window` 时整条记录作为一个窗口
+/// 序列与质量值按同样的区间截取；没有合格的窗口或修剪后短于 `min_length` 时返回 None
+/// `window` 为 0 时会 panic
+pub fn trim_quality(
+    record: &FastqRecord,
+    window: usize,
+    threshold: f64,
+    min_length: usize,
+) -> Option<FastqRecord> {
+    if window == 0 {
+        panic!("窗口长度不能为 0");
+    }
+    let window = window.min(record.qual.len());
+    let passes = |start: usize| {
+        let qual = &record.qual[start..start + window];
+        qual.iter().map(|&q| q as f64).sum::<f64>() / window as f64 > threshold
+    };
+    let last_window = record.qual.len().checked_sub(window.max(1))?;
+    let start = (0..=last_window).find(|&i| passes(i))?;
+    let end = (start..=last_window).rev().find(|&i| passes(i))? + window;
+    if end - start < min_length {
+        return None;
+    }
+    Some(slice_record(record, start, end))
+}
+
+/// 切除 3' 端的接头序列
+/// 对每个接头，找到 read 中最靠前的位置，使从该位置开始完整出现接头，或者 read 的末尾与接头的开头相同；
+/// 重叠部分至少为 `min_overlap` 个碱基时，从所有接头中最靠前的位置切除到末尾
+/// 不区分大小写，T 与 U 视为相同，不允许错配；没有找到接头时返回原记录的副本
+pub fn trim_adapters(record: &FastqRecord, adapters: &[&str], min_overlap: usize) -> FastqRecord {
+    let normalize = |seq: &str| -> Vec<char> {
+        seq.chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'U' => 'T',
+                c => c,
+            })
+            .collect()
+    };
+    let read = normalize(&record.seq.seq);
+    let cut = adapters
+        .iter()
+        .map(|adapter| normalize(adapter))
+        .filter(|adapter| !adapter.is_empty())
+        .filter_map(|adapter| {
+            (0..read.len()).find(|&i| {
+                let overlap = (read.len() - i).min(adapter.len());
+                overlap >= min_overlap && read[i..i + overlap] == adapter[..overlap]
+            })
+        })
+        .min()
+        .unwrap_or(read.len());
+    slice_record(record, 0, cut)
+}
+
+/// 按字符位置截取 `start..end`，序列与质量值保持对齐
+fn slice_record(record: &FastqRecord, start: usize, end: usize) -> FastqRecord {
+    FastqRecord {
+        id: record.id.clone(),
+        description: record.description.clone(),
+        seq: record.seq.subsequence(start..end),
+        qual: record.qual[start..end].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_records() {
+        let data = "@read1 lane1\nACGT\n+\nII#!\n@read2\r\nAC\r\n+read2\r\n5?\r\n";
+        let records: Vec<FastqRecord> = Reader::new(Cursor::new(data))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].id, "read1");
+        assert_eq!(records[0].description, "lane1");
+        assert_eq!(records[0].qual, vec![40, 40, 2, 0]);
+        assert_eq!(records[0].mean_quality(), 20.5);
+        assert_eq!(records[1].seq.seq, "AC");
+        assert_eq!(records[1].qual, vec![20, 30]);
+    }
+
+    #[test]
+    fn malformed_records() {
+        let mismatch = Reader::new(Cursor::new("@r\nACGT\n+\nII\n"))
+            .next()
+            .unwrap();
+        assert!(matches!(mismatch, Err(FastqError::LengthMismatch { .. })));
+        let separator = Reader::new(Cursor::new("@r\nAC\n+other\nII\n"))
+            .next()
+            .unwrap();
+        assert!(matches!(
+            separator,
+            Err(FastqError::InvalidSeparator { line: 3 })
+        ));
+        let truncated = Reader::new(Cursor::new("@r\nAC\n")).next().unwrap();
+        assert!(matches!(truncated, Err(FastqError::Truncated { line: 3 })));
+        assert!(matches!(
+            read("tests/data/missing.fastq"),
+            Err(FastqError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn quality_and_adapter_trimming() {
+        let record = FastqRecord::new(
+            String::from("read1"),
+            String::new(),
+            Sequence::new(BioType::Dna, String::from("NACGTACGTACN")),
+            vec![2, 10, 30, 30, 35, 35, 35, 35, 30, 12, 2, 2],
+        );
+        let trimmed = trim_quality(&record, 3, 20.0, 5).unwrap();
+        assert_eq!(trimmed.seq.seq, "ACGTACGTA");
+        assert_eq!(trimmed.qual, record.qual[1..10]);
+        assert_eq!(trimmed.id, "read1");
+        assert!(trim_quality(&record, 3, 20.0, 10).is_none());
+        assert!(trim_quality(&record, 3, 40.0, 0).is_none());
+        let short = trim_quality(&record, 20, 20.0, 0).unwrap();
+        assert_eq!(short.seq.len(), record.seq.len());
+
+        let read = FastqRecord::new(
+            String::from("read2"),
+            String::new(),
+            Sequence::new(BioType::Dna, String::from("GATTACAAGATCGGAAGag")),
+            (0..19).collect(),
+        );
+        let clipped = trim_adapters(&read, &["AGATCGGAAGAGC", "CTGTCTCTTATA"], 3);
+        assert_eq!(clipped.seq.seq, "GATTACA");
+        assert_eq!(clipped.qual, (0..7).collect::<Vec<u8>>());
+        // 只有末尾的部分接头
+        let partial = trim_adapters(&read, &["AGAGC"], 3);
+        assert_eq!(partial.seq.seq, "GATTACAAGATCGGA");
+        assert_eq!(partial.qual.len(), partial.seq.len());
+        assert_eq!(trim_adapters(&read, &["AGAGC"], 5).seq, read.seq);
+    }
+}
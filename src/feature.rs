@@ -0,0 +1,318 @@
+use super::coordinate::CoordinateSystem;
+use super::sequence::Strand;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+
+// 序列上的注释特征，以及按区间查询特征的索引
+// 坐标以 0 开始、左闭右开，与 `Sequence::subsequence` 一致
+// `coordinate_system` 只决定输出时使用的坐标系统
+
+/// 特征的类型
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeatureType {
+    Gene,
+    Exon,
+    Intron,
+    Cds,
+    Utr5,
+    Utr3,
+    CircRna,
+    /// 其他类型，保存原始名称
+    Other(String),
+}
+
+/// 按 GFF3 / GenBank 中常见的名称解析，不区分大小写，无法识别的名称为 `Other`
+impl From<&str> for FeatureType {
+    fn from(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "gene" => FeatureType::Gene,
+            "exon" => FeatureType::Exon,
+            "intron" => FeatureType::Intron,
+            "cds" => FeatureType::Cds,
+            "5'utr" | "utr5" | "five_prime_utr" => FeatureType::Utr5,
+            "3'utr" | "utr3" | "three_prime_utr" => FeatureType::Utr3,
+            "circrna" | "circ_rna" | "circular_rna" => FeatureType::CircRna,
+            _ => FeatureType::Other(name.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for FeatureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureType::Gene => write!(f, "gene"),
+            FeatureType::Exon => write!(f, "exon"),
+            FeatureType::Intron => write!(f, "intron"),
+            FeatureType::Cds => write!(f, "CDS"),
+            FeatureType::Utr5 => write!(f, "5'UTR"),
+            FeatureType::Utr3 => write!(f, "3'UTR"),
+            FeatureType::CircRna => write!(f, "circRNA"),
+            FeatureType::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// 序列上的一个注释特征
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feature {
+    pub start: usize,
+    pub end: usize,
+    pub strand: Strand,
+    pub feature_type: FeatureType,
+    pub attributes: HashMap<String, String>,
+    /// 输出时使用的坐标系统，`start` 与 `end` 本身总是以 0 开始、左闭右开
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub coordinate_system: CoordinateSystem,
+}
+
+impl Feature {
+    /// 新建没有属性的特征，输出时使用以 0 开始的坐标
+    pub fn new(start: usize, end: usize, strand: Strand, feature_type: FeatureType) -> Self {
+        Feature {
+            start,
+            end,
+            strand,
+            feature_type,
+            attributes: HashMap::new(),
+            coordinate_system: CoordinateSystem::default(),
+        }
+    }
+
+    /// 修改输出时使用的坐标系统
+    pub fn with_coordinate_system(mut self, coordinate_system: CoordinateSystem) -> Self {
+        self.coordinate_system = coordinate_system;
+        self
+    }
+
+    /// `coordinate_system` 中的 (起点, 终点)
+    pub fn coordinates(&self) -> (usize, usize) {
+        self.coordinate_system.from_zero_based(self.start, self.end)
+    }
+
+    /// 返回长度
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// 区间是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 与区间 `start..end` 是否有重叠
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
+/// 输出 `类型\t起点\t终点\t链`，坐标按 `coordinate_system` 转换，链为 `+`、`-` 或 `.`
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = self.coordinates();
+        let strand = match self.strand {
+            Strand::Forward => '+',
+            Strand::Reverse => '-',
+            Strand::Both => '.',
+        };
+        write!(f, "{}\t{}\t{}\t{}", self.feature_type, start, end, strand)
+    }
+}
+
+/// 按起点排序的特征集合，同时作为隐式的区间树
+/// 排好序的数组中，区间 `lo..hi` 的中点为子树的根，`max_end` 记录每棵子树中最大的终点
+/// 查询的时间复杂度为 O(log n + k)，插入新特征时会重建索引
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<Feature>", into = "Vec<Feature>")
+)]
+pub struct FeatureIndex {
+    features: Vec<Feature>,
+    max_end: Vec<usize>,
+}
+
+impl FeatureIndex {
+    /// 新建空的索引
+    pub fn new() -> Self {
+        FeatureIndex::default()
+    }
+
+    /// 加入一个特征
+    pub fn insert(&mut self, feature: Feature) {
+        let position = self
+            .features
+            .partition_point(|f| (f.start, f.end) <= (feature.start, feature.end));
+        self.features.insert(position, feature);
+        self.rebuild();
+    }
+
+    /// 返回覆盖位置 `pos` 的所有特征，按起点排序
+    pub fn features_at(&self, pos: usize) -> Vec<&Feature> {
+        self.features_overlapping(pos, pos + 1)
+    }
+
+    /// 返回与区间 `start..end` 重叠的所有特征，按起点排序
+    pub fn features_overlapping(&self, start: usize, end: usize) -> Vec<&Feature> {
+        let mut found = Vec::new();
+        self.query(0, self.features.len(), start, end, &mut found);
+        found
+    }
+
+    /// 取出全部特征
+    pub fn into_vec(self) -> Vec<Feature> {
+        self.features
+    }
+
+    fn query<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        start: usize,
+        end: usize,
+        found: &mut Vec<&'a Feature>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        // 子树中所有特征都在查询区间之前结束
+        if self.max_end[mid] <= start {
+            return;
+        }
+        self.query(lo, mid, start, end, found);
+        let feature = &self.features[mid];
+        if feature.start < end {
+            if feature.overlaps(start, end) {
+                found.push(feature);
+            }
+            self.query(mid + 1, hi, start, end, found);
+        }
+    }
+
+    /// 重新计算每棵子树的最大终点
+    fn rebuild(&mut self) {
+        self.max_end = vec![0; self.features.len()];
+        self.build(0, self.features.len());
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let left = self.build(lo, mid);
+        let right = self.build(mid + 1, hi);
+        self.max_end[mid] = self.features[mid].end.max(left).max(right);
+        self.max_end[mid]
+    }
+}
+
+impl From<Vec<Feature>> for FeatureIndex {
+    fn from(mut features: Vec<Feature>) -> Self {
+        features.sort_by_key(|f| (f.start, f.end));
+        let mut index = FeatureIndex {
+            features,
+            max_end: Vec::new(),
+        };
+        index.rebuild();
+        index
+    }
+}
+
+impl From<FeatureIndex> for Vec<Feature> {
+    fn from(index: FeatureIndex) -> Self {
+        index.features
+    }
+}
+
+impl FromIterator<Feature> for FeatureIndex {
+    fn from_iter<T: IntoIterator<Item = Feature>>(iter: T) -> Self {
+        FeatureIndex::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// 可以像切片一样遍历特征
+impl Deref for FeatureIndex {
+    type Target = [Feature];
+
+    fn deref(&self) -> &Self::Target {
+        &self.features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_type_names() {
+        assert_eq!(FeatureType::from("CDS"), FeatureType::Cds);
+        assert_eq!(FeatureType::from("five_prime_UTR"), FeatureType::Utr5);
+        assert_eq!(FeatureType::from("circRNA").to_string(), "circRNA");
+        assert_eq!(
+            FeatureType::from("misc_feature"),
+            FeatureType::Other(String::from("misc_feature"))
+        );
+
+        let exon = Feature::new(0, 10, Strand::Reverse, FeatureType::Exon);
+        assert_eq!(exon.to_string(), "exon\t0\t10\t-");
+        let exon = exon.with_coordinate_system(CoordinateSystem::OneBasedClosed);
+        assert_eq!(exon.coordinates(), (1, 10));
+        assert_eq!(exon.to_string(), "exon\t1\t10\t-");
+        assert_eq!(exon.len(), 10);
+    }
+
+    #[test]
+    fn interval_queries_match_linear_scan() {
+        // 用线性同余生成器产生的伪随机区间与逐个比较的结果对照
+        let mut state: u64 = 42;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as usize
+        };
+        let mut features = Vec::new();
+        for _ in 0..300 {
+            let start = next(1000);
+            let feature_type = if next(2) == 0 {
+                FeatureType::Exon
+            } else {
+                FeatureType::Intron
+            };
+            features.push(Feature::new(
+                start,
+                start + 1 + next(80),
+                Strand::Forward,
+                feature_type,
+            ));
+        }
+        let index: FeatureIndex = features.clone().into_iter().collect();
+        assert_eq!(index.len(), 300);
+        for _ in 0..200 {
+            let start = next(1100);
+            let end = start + next(50);
+            let mut expected: Vec<&Feature> =
+                features.iter().filter(|f| f.overlaps(start, end)).collect();
+            expected.sort_by_key(|f| (f.start, f.end));
+            let found = index.features_overlapping(start, end);
+            assert_eq!(found.len(), expected.len());
+            assert!(found.windows(2).all(|w| w[0].start <= w[1].start));
+            assert!(found.iter().all(|f| f.overlaps(start, end)));
+        }
+
+        let mut index = FeatureIndex::new();
+        index.insert(Feature::new(10, 20, Strand::Forward, FeatureType::Exon));
+        index.insert(Feature::new(0, 100, Strand::Forward, FeatureType::Gene));
+        index.insert(Feature::new(30, 40, Strand::Forward, FeatureType::Exon));
+        assert_eq!(index[0].feature_type, FeatureType::Gene);
+        assert_eq!(index.features_at(15).len(), 2);
+        assert_eq!(index.features_at(20).len(), 1);
+        assert!(index.features_at(100).is_empty());
+    }
+}
@@ -0,0 +1,662 @@
+use super::sequence::{BioType, Sequence, Strand, Topology};
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{error, fmt};
+
+// GenBank 平面文件的读取
+// 每条记录以 LOCUS 行开始、`//` 行结束，FEATURES 与 ORIGIN 之间为特征表，ORIGIN 之后为序列
+// 特征表中第 6 列起为特征名，第 22 列起为位置或以 `/` 开头的限定词
+
+/// 特征表中位置与限定词所在的列 (以 0 开始)
+const QUALIFIER_COLUMN: usize = 21;
+
+/// GenBank 读取过程中可能出现的错误
+#[derive(Debug)]
+pub enum GenBankError {
+    /// 文件不存在
+    NotFound(PathBuf),
+    /// 其他读写错误
+    Io(io::Error),
+    /// 在 LOCUS 行之前出现了其他内容
+    MissingLocus { line: usize },
+    /// 特征表中的行格式不正确
+    InvalidFeature { line: usize },
+    /// 无法解析的特征位置
+    InvalidLocation { line: usize, location: String },
+}
+
+impl fmt::Display for GenBankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenBankError::NotFound(path) => {
+                write!(f, "找不到 GenBank 文件: {}", path.display())
+            }
+            GenBankError::Io(err) => write!(f, "GenBank 读写错误: {}", err),
+            GenBankError::MissingLocus { line } => {
+                write!(f, "第 {} 行: 记录应以 LOCUS 行开始", line)
+            }
+            GenBankError::InvalidFeature { line } => {
+                write!(f, "第 {} 行: 特征表格式不正确", line)
+            }
+            GenBankError::InvalidLocation { line, location } => {
+                write!(f, "第 {} 行: 无法解析的特征位置 '{}'", line, location)
+            }
+        }
+    }
+}
+
+impl error::Error for GenBankError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            GenBankError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GenBankError {
+    fn from(err: io::Error) -> Self {
+        GenBankError::Io(err)
+    }
+}
+
+/// 特征在序列上的位置，坐标以 0 开始、左闭右开
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Location {
+    /// 一段连续的区间，`partial_start` / `partial_end` 对应 `<` 与 `>`，表示端点在区间之外
+    /// 两个碱基之间的位置 (`12^13`) 表示为长度为 0 的区间
+    Range {
+        start: usize,
+        end: usize,
+        partial_start: bool,
+        partial_end: bool,
+    },
+    /// 位于负链上的位置
+    Complement(Box<Location>),
+    /// 按顺序拼接的多个位置，例如 CDS 的多个外显子
+    Join(Vec<Location>),
+    /// 多个位置，顺序不代表拼接关系
+    Order(Vec<Location>),
+}
+
+impl Location {
+    /// 所覆盖区域最左端的位置
+    pub fn start(&self) -> usize {
+        match self {
+            Location::Range { start, .. } => *start,
+            Location::Complement(inner) => inner.start(),
+            Location::Join(parts) | Location::Order(parts) => {
+                parts.iter().map(Location::start).min().unwrap_or(0)
+            }
+        }
+    }
+
+    /// 所覆盖区域最右端的位置 (不含)
+    pub fn end(&self) -> usize {
+        match self {
+            Location::Range { end, .. } => *end,
+            Location::Complement(inner) => inner.end(),
+            Location::Join(parts) | Location::Order(parts) => {
+                parts.iter().map(Location::end).max().unwrap_or(0)
+            }
+        }
+    }
+
+    /// 位置所在的链，只要最外层或全部组成部分为 complement 即视为负链
+    pub fn strand(&self) -> Strand {
+        match self {
+            Location::Range { .. } => Strand::Forward,
+            Location::Complement(_) => Strand::Reverse,
+            Location::Join(parts) | Location::Order(parts) => {
+                if !parts.is_empty() && parts.iter().all(|p| p.strand() == Strand::Reverse) {
+                    Strand::Reverse
+                } else {
+                    Strand::Forward
+                }
+            }
+        }
+    }
+
+    /// 是否有端点位于已知区间之外 (`<` / `>`)
+    pub fn is_partial(&self) -> bool {
+        match self {
+            Location::Range {
+                partial_start,
+                partial_end,
+                ..
+            } => *partial_start || *partial_end,
+            Location::Complement(inner) => inner.is_partial(),
+            Location::Join(parts) | Location::Order(parts) => {
+                parts.iter().any(Location::is_partial)
+            }
+        }
+    }
+
+    /// 从序列中取出该位置对应的序列，complement 会取反向互补，join 与 order 按顺序拼接
+    pub fn extract(&self, seq: &Sequence) -> Result<Sequence, String> {
+        match self {
+            Location::Range { start, end, .. } => {
+                if *end > seq.len() {
+                    return Err(format!(
+                        "位置 {}..{} 超出序列长度 {}",
+                        start,
+                        end,
+                        seq.len()
+                    ));
+                }
+                Ok(seq.subsequence(*start..*end))
+            }
+            Location::Complement(inner) => inner.extract(seq)?.reverse_complementary(),
+            Location::Join(parts) | Location::Order(parts) => {
+                let mut joined = Sequence::new(seq.biotype.clone(), String::new());
+                for part in parts {
+                    joined.seq.push_str(&part.extract(seq)?.seq);
+                }
+                Ok(joined)
+            }
+        }
+    }
+}
+
+/// 解析位置字符串，例如 `<1..>200`、`complement(join(10..20,30..40))`
+/// 不支持指向其他记录的位置 (如 `J00194.1:100..202`)
+impl FromStr for Location {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let invalid = || format!("无法解析的特征位置: {}", s);
+        if let Some(inner) = strip_call(&s, "complement") {
+            return Ok(Location::Complement(Box::new(inner.parse()?)));
+        }
+        if let Some(inner) = strip_call(&s, "join") {
+            return Ok(Location::Join(split_parts(inner)?));
+        }
+        if let Some(inner) = strip_call(&s, "order") {
+            return Ok(Location::Order(split_parts(inner)?));
+        }
+        // 以 1 开始的端点，可带有 `<` 或 `>` 前缀
+        let point = |text: &str| -> Result<(usize, bool), String> {
+            let partial = text.starts_with('<') || text.starts_with('>');
+            let number = text.trim_start_matches(['<', '>']);
+            match number.parse::<usize>() {
+                Ok(position) if position > 0 => Ok((position, partial)),
+                _ => Err(invalid()),
+            }
+        };
+        if let Some((left, right)) = s.split_once("..") {
+            let (start, partial_start) = point(left)?;
+            let (end, partial_end) = point(right)?;
+            if start > end {
+                return Err(invalid());
+            }
+            return Ok(Location::Range {
+                start: start - 1,
+                end,
+                partial_start,
+                partial_end,
+            });
+        }
+        if let Some((left, right)) = s.split_once('^') {
+            let (left, _) = point(left)?;
+            let (right, _) = point(right)?;
+            if right != left + 1 {
+                return Err(invalid());
+            }
+            return Ok(Location::Range {
+                start: left,
+                end: left,
+                partial_start: false,
+                partial_end: false,
+            });
+        }
+        let (position, partial) = point(&s)?;
+        Ok(Location::Range {
+            start: position - 1,
+            end: position,
+            partial_start: partial && s.starts_with('<'),
+            partial_end: partial && s.starts_with('>'),
+        })
+    }
+}
+
+/// 以 GenBank 的语法输出位置
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, parts: &[Location]| {
+            write!(f, "{}(", name)?;
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", part)?;
+            }
+            write!(f, ")")
+        };
+        match self {
+            Location::Range {
+                start,
+                end,
+                partial_start,
+                partial_end,
+            } => {
+                if start == end {
+                    return write!(f, "{}^{}", start, start + 1);
+                }
+                let left = if *partial_start { "<" } else { "" };
+                let right = if *partial_end { ">" } else { "" };
+                if end - start == 1 && !partial_start && !partial_end {
+                    write!(f, "{}", end)
+                } else {
+                    write!(f, "{}{}..{}{}", left, start + 1, right, end)
+                }
+            }
+            Location::Complement(inner) => write!(f, "complement({})", inner),
+            Location::Join(parts) => list(f, "join", parts),
+            Location::Order(parts) => list(f, "order", parts),
+        }
+    }
+}
+
+/// 去掉 `name(...)` 的外层，括号不匹配时返回 None
+fn strip_call<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    s.strip_prefix(name)?.strip_prefix('(')?.strip_suffix(')')
+}
+
+/// 按最外层的逗号拆分 join / order 中的各个位置
+fn split_parts(s: &str) -> Result<Vec<Location>, String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| format!("括号不匹配: {}", s))?
+            }
+            ',' if depth == 0 => {
+                parts.push(s[start..i].parse()?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].parse()?);
+    Ok(parts)
+}
+
+/// 特征表中的一个特征
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenBankFeature {
+    /// 特征名，例如 gene、CDS、exon
+    pub key: String,
+    pub location: Location,
+    /// 按出现顺序保存的限定词，值已去掉两侧的引号，没有值的限定词 (如 `/pseudo`) 值为空字符串
+    pub qualifiers: Vec<(String, String)>,
+}
+
+impl GenBankFeature {
+    /// 返回第一个名为 `name` 的限定词的值
+    pub fn qualifier(&self, name: &str) -> Option<&str> {
+        self.qualifiers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 一条 GenBank 记录
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenBankRecord {
+    /// LOCUS 行中的序列名
+    pub name: String,
+    pub accession: String,
+    pub definition: String,
+    pub organism: String,
+    pub features: Vec<GenBankFeature>,
+    /// 序列类型根据 LOCUS 行的单位 (bp / aa) 与序列组成推断，LOCUS 中标明 circular 时为环状
+    pub sequence: Sequence,
+}
+
+/// 读取 GenBank 文件中的全部记录
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<GenBankRecord>, GenBankError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => GenBankError::NotFound(path.to_path_buf()),
+        _ => GenBankError::Io(err),
+    })?;
+    parse(BufReader::new(file))
+}
+
+/// 当前所在的段落
+#[derive(PartialEq)]
+enum Section {
+    Header,
+    Definition,
+    Features,
+    Origin,
+}
+
+/// 正在读取的特征，位置可能跨越多行，因此读取完毕后再解析
+struct PendingFeature {
+    key: String,
+    location: String,
+    line: usize,
+    qualifiers: Vec<(String, String)>,
+    /// 最后一个限定词的值是否还在引号中
+    open_quote: bool,
+}
+
+impl PendingFeature {
+    fn finish(self) -> Result<GenBankFeature, GenBankError> {
+        let location = self
+            .location
+            .parse()
+            .map_err(|_| GenBankError::InvalidLocation {
+                line: self.line,
+                location: self.location.clone(),
+            })?;
+        Ok(GenBankFeature {
+            key: self.key,
+            location,
+            qualifiers: self.qualifiers,
+        })
+    }
+
+    /// 读入以 `/` 开头的限定词
+    fn push_qualifier(&mut self, text: &str) {
+        let text = &text[1..];
+        let (name, value) = text.split_once('=').unwrap_or((text, ""));
+        self.open_quote = value.starts_with('"') && (value.len() == 1 || !value.ends_with('"'));
+        self.qualifiers
+            .push((name.to_string(), value.trim_matches('"').to_string()));
+    }
+
+    /// 读入限定词值的续行，translation 直接拼接，其余以空格分隔
+    fn continue_qualifier(&mut self, text: &str) {
+        if let Some((name, value)) = self.qualifiers.last_mut() {
+            if text.ends_with('"') {
+                self.open_quote = false;
+            }
+            if name != "translation" && !value.is_empty() {
+                value.push(' ');
+            }
+            value.push_str(text.trim_matches('"'));
+        }
+    }
+}
+
+/// 从任意 BufRead 中解析 GenBank 记录
+/// 会去除行尾的 `\r`，文件末尾缺少 `//` 的记录同样会被保留
+pub fn parse(reader: impl BufRead) -> Result<Vec<GenBankRecord>, GenBankError> {
+    let mut records = Vec::new();
+    let mut current: Option<GenBankRecord> = None;
+    let mut section = Section::Header;
+    let mut feature: Option<PendingFeature> = None;
+    let mut protein = false;
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        let line_number = number + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("LOCUS") {
+            if let Some(record) = current.take() {
+                records.push(finish(record, feature.take(), protein)?);
+            }
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            protein = fields.get(2) == Some(&"aa");
+            let mut sequence = Sequence::new(BioType::Dna, String::new());
+            if fields.contains(&"circular") {
+                sequence.topology = Topology::Circular;
+            }
+            current = Some(GenBankRecord {
+                name: fields.first().unwrap_or(&"").to_string(),
+                accession: String::new(),
+                definition: String::new(),
+                organism: String::new(),
+                features: Vec::new(),
+                sequence,
+            });
+            section = Section::Header;
+            continue;
+        }
+        let record = match current.as_mut() {
+            Some(record) => record,
+            None => return Err(GenBankError::MissingLocus { line: line_number }),
+        };
+        if line.starts_with("//") {
+            records.push(finish(current.take().unwrap(), feature.take(), protein)?);
+            section = Section::Header;
+            continue;
+        }
+
+        // 第 0 列不是空格的行为新的关键字
+        if !line.starts_with(' ') {
+            let (keyword, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let value = value.trim();
+            section = match keyword {
+                "DEFINITION" => {
+                    record.definition = value.to_string();
+                    Section::Definition
+                }
+                "ACCESSION" => {
+                    record.accession = value.split_whitespace().next().unwrap_or("").to_string();
+                    Section::Header
+                }
+                "FEATURES" => Section::Features,
+                "ORIGIN" => {
+                    if let Some(pending) = feature.take() {
+                        record.features.push(pending.finish()?);
+                    }
+                    Section::Origin
+                }
+                _ => Section::Header,
+            };
+            continue;
+        }
+
+        match section {
+            Section::Definition => {
+                record.definition.push(' ');
+                record.definition.push_str(line.trim());
+            }
+            Section::Header => {
+                if let Some(organism) = line.trim_start().strip_prefix("ORGANISM") {
+                    if line.starts_with("  ORGANISM") {
+                        record.organism = organism.trim().to_string();
+                    }
+                }
+            }
+            Section::Features => {
+                let key = line.get(5..QUALIFIER_COLUMN).unwrap_or("").trim();
+                let text = line.get(QUALIFIER_COLUMN..).unwrap_or("").trim();
+                if !key.is_empty() {
+                    if let Some(pending) = feature.take() {
+                        record.features.push(pending.finish()?);
+                    }
+                    feature = Some(PendingFeature {
+                        key: key.to_string(),
+                        location: text.to_string(),
+                        line: line_number,
+                        qualifiers: Vec::new(),
+                        open_quote: false,
+                    });
+                    continue;
+                }
+                let pending = match feature.as_mut() {
+                    Some(pending) if line.len() > QUALIFIER_COLUMN => pending,
+                    _ => return Err(GenBankError::InvalidFeature { line: line_number }),
+                };
+                if pending.open_quote {
+                    pending.continue_qualifier(text);
+                } else if text.starts_with('/') {
+                    pending.push_qualifier(text);
+                } else if pending.qualifiers.is_empty() {
+                    pending.location.push_str(text);
+                } else {
+                    pending.continue_qualifier(text);
+                }
+            }
+            Section::Origin => {
+                record.sequence.seq.extend(
+                    line.chars()
+                        .filter(|c| !c.is_ascii_digit() && !c.is_whitespace()),
+                );
+            }
+        }
+    }
+
+    if let Some(record) = current {
+        records.push(finish(record, feature, protein)?);
+    }
+    Ok(records)
+}
+
+/// 记录读取完毕后加入尚未结束的特征，并推断序列类型
+fn finish(
+    mut record: GenBankRecord,
+    feature: Option<PendingFeature>,
+    protein: bool,
+) -> Result<GenBankRecord, GenBankError> {
+    if let Some(pending) = feature {
+        record.features.push(pending.finish()?);
+    }
+    record.sequence.biotype = if protein {
+        BioType::Protein
+    } else {
+        match BioType::infer(&record.sequence.seq) {
+            BioType::Rna => BioType::Rna,
+            _ => BioType::Dna,
+        }
+    };
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const RECORD: &str = "\
+LOCUS       TEST0001                  60 bp    DNA     circular SYN 01-JAN-2024
+DEFINITION  Synthetic test construct with a split
+            coding sequence.
+ACCESSION   TS000001 TS000002
+VERSION     TS000001.1
+SOURCE      synthetic construct
+  ORGANISM  synthetic construct
+            other sequences; artificial sequences.
+FEATURES             Location/Qualifiers
+     source          1..60
+                     /organism=\"synthetic construct\"
+     gene            <1..>60
+                     /gene=\"tst\"
+     CDS             join(1..9,
+                     31..39)
+                     /gene=\"tst\"
+                     /codon_start=1
+                     /product=\"test protein with a
+                     long name\"
+                     /translation=\"MKKRS
+                     S\"
+     misc_feature    complement(41..50)
+                     /pseudo
+ORIGIN
+        1 atgaaaaaac cccccccccc cccccccccc cgttcatcag gggggggggg ttgcggccgc
+//
+LOCUS       PROT1                      5 aa            linear   SYN 01-JAN-2024
+ACCESSION   PR000001
+ORIGIN
+        1 mkacg
+//
+";
+
+    #[test]
+    fn parse_genbank_records() {
+        let records = parse(Cursor::new(RECORD)).unwrap();
+        assert_eq!(records.len(), 2);
+        let record = &records[0];
+        assert_eq!(record.name, "TEST0001");
+        assert_eq!(record.accession, "TS000001");
+        assert_eq!(
+            record.definition,
+            "Synthetic test construct with a split coding sequence."
+        );
+        assert_eq!(record.organism, "synthetic construct");
+        assert_eq!(record.sequence.len(), 60);
+        assert_eq!(record.sequence.biotype, BioType::Dna);
+        assert!(record.sequence.is_circular());
+
+        assert_eq!(record.features.len(), 4);
+        let gene = &record.features[1];
+        assert!(gene.location.is_partial());
+        let cds = &record.features[2];
+        assert_eq!(cds.key, "CDS");
+        assert_eq!(cds.location.to_string(), "join(1..9,31..39)");
+        assert_eq!(cds.qualifier("codon_start"), Some("1"));
+        assert_eq!(
+            cds.qualifier("product"),
+            Some("test protein with a long name")
+        );
+        assert_eq!(cds.qualifier("translation"), Some("MKKRSS"));
+        let spliced = cds.location.extract(&record.sequence).unwrap();
+        assert_eq!(spliced.seq, "atgaaaaaacgttcatca");
+        let misc = &record.features[3];
+        assert_eq!(misc.location.strand(), Strand::Reverse);
+        assert_eq!(misc.qualifier("pseudo"), Some(""));
+        assert_eq!(
+            misc.location.extract(&record.sequence).unwrap().seq,
+            "cccccccccc"
+        );
+
+        assert_eq!(records[1].sequence.biotype, BioType::Protein);
+        assert_eq!(records[1].sequence.seq, "mkacg");
+    }
+
+    #[test]
+    fn parse_locations() {
+        let location: Location = "complement(join(<10..20, 30^31, 45))".parse().unwrap();
+        assert_eq!(location.start(), 9);
+        assert_eq!(location.end(), 45);
+        assert_eq!(location.strand(), Strand::Reverse);
+        assert!(location.is_partial());
+        assert_eq!(location.to_string(), "complement(join(<10..20,30^31,45))");
+        assert_eq!(
+            "2..>5".parse::<Location>(),
+            Ok(Location::Range {
+                start: 1,
+                end: 5,
+                partial_start: false,
+                partial_end: true,
+            })
+        );
+        assert!("join(1..5".parse::<Location>().is_err());
+        assert!("J00194.1:100..202".parse::<Location>().is_err());
+        assert!("5..2".parse::<Location>().is_err());
+        assert!("0..2".parse::<Location>().is_err());
+    }
+
+    #[test]
+    fn malformed_genbank() {
+        assert!(matches!(
+            parse(Cursor::new("DEFINITION  nothing\n")),
+            Err(GenBankError::MissingLocus { line: 1 })
+        ));
+        let bad = "LOCUS       X 4 bp DNA linear\nFEATURES             Location/Qualifiers\n     gene            1..x\nORIGIN\n        1 acgt\n//\n";
+        assert!(matches!(
+            parse(Cursor::new(bad)),
+            Err(GenBankError::InvalidLocation { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+use super::record::NamedSequence;
+use super::sequence::{Sequence, Strand};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+// GFF3 注释文件的读取
+// 每行为 9 列以制表符分隔的字段：seqid、source、type、start、end、score、strand、phase、attributes
+// `#` 开头的行为注释或指令，遇到 `##FASTA` 后其余内容为序列，不再解析
+
+/// GFF3 读取过程中可能出现的错误
+#[derive(Debug)]
+pub enum Gff3Error {
+    /// 文件不存在
+    NotFound(PathBuf),
+    /// 其他读写错误
+    Io(io::Error),
+    /// 列数不是 9
+    InvalidColumns { line: usize, found: usize },
+    /// 起点或终点不是正整数，或起点大于终点
+    InvalidPosition { line: usize },
+    /// score 列既不是 `.` 也不是数字
+    InvalidScore { line: usize },
+    /// strand 列不是 `+`、`-`、`.` 或 `?`
+    InvalidStrand { line: usize },
+    /// phase 列不是 `.`、0、1 或 2
+    InvalidPhase { line: usize },
+    /// 属性不是 `tag=value` 的形式
+    InvalidAttribute { line: usize },
+}
+
+impl fmt::Display for Gff3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gff3Error::NotFound(path) => write!(f, "找不到 GFF3 文件: {}", path.display()),
+            Gff3Error::Io(err) => write!(f, "GFF3 读写错误: {}", err),
+            Gff3Error::InvalidColumns { line, found } => {
+                write!(f, "第 {} 行: 应有 9 列，实际为 {} 列", line, found)
+            }
+            Gff3Error::InvalidPosition { line } => write!(f, "第 {} 行: 起止位置无效", line),
+            Gff3Error::InvalidScore { line } => write!(f, "第 {} 行: score 无效", line),
+            Gff3Error::InvalidStrand { line } => write!(f, "第 {} 行: strand 无效", line),
+            Gff3Error::InvalidPhase { line } => write!(f, "第 {} 行: phase 无效", line),
+            Gff3Error::InvalidAttribute { line } => {
+                write!(f, "第 {} 行: 属性应为 tag=value 的形式", line)
+            }
+        }
+    }
+}
+
+impl error::Error for Gff3Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Gff3Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Gff3Error {
+    fn from(err: io::Error) -> Self {
+        Gff3Error::Io(err)
+    }
+}
+
+/// GFF3 中的一行特征
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gff3Record {
+    pub seqname: String,
+    pub source: String,
+    pub feature_type: String,
+    /// 起点，以 1 开始
+    pub start: usize,
+    /// 终点，包含在区间内
+    pub end: usize,
+    pub score: Option<f64>,
+    /// `.` 与 `?` (链未知) 均为 None
+    pub strand: Option<Strand>,
+    /// CDS 的读码框相位，其他特征通常为 None
+    pub phase: Option<u8>,
+    /// 属性的值已经过百分号解码，多个值 (如 `Parent=a,b`) 保持原样以逗号分隔
+    pub attributes: HashMap<String, String>,
+}
+
+impl Gff3Record {
+    /// 返回名为 `tag` 的属性
+    pub fn attribute(&self, tag: &str) -> Option<&str> {
+        self.attributes.get(tag).map(String::as_str)
+    }
+
+    /// 与闭区间 `start..=end` (以 1 开始) 是否有重叠
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.start <= end && start <= self.end
+    }
+}
+
+/// 读取 GFF3 文件中的全部特征
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<Gff3Record>, Gff3Error> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => Gff3Error::NotFound(path.to_path_buf()),
+        _ => Gff3Error::Io(err),
+    })?;
+    parse(BufReader::new(file))
+}
+
+/// 从任意 BufRead 中解析 GFF3 特征，空行与 `#` 开头的行会被忽略
+pub fn parse(reader: impl BufRead) -> Result<Vec<Gff3Record>, Gff3Error> {
+    let mut records = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("##FASTA") {
+            break;
+        }
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        records.push(parse_line(line, number + 1)?);
+    }
+    Ok(records)
+}
+
+fn parse_line(line: &str, number: usize) -> Result<Gff3Record, Gff3Error> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    if columns.len() != 9 {
+        return Err(Gff3Error::InvalidColumns {
+            line: number,
+            found: columns.len(),
+        });
+    }
+    let position = |text: &str| match text.parse::<usize>() {
+        Ok(position) if position > 0 => Ok(position),
+        _ => Err(Gff3Error::InvalidPosition { line: number }),
+    };
+    let start = position(columns[3])?;
+    let end = position(columns[4])?;
+    if start > end {
+        return Err(Gff3Error::InvalidPosition { line: number });
+    }
+    let score = match columns[5] {
+        "." => None,
+        text => Some(
+            text.parse()
+                .map_err(|_| Gff3Error::InvalidScore { line: number })?,
+        ),
+    };
+    let strand = match columns[6] {
+        "+" => Some(Strand::Forward),
+        "-" => Some(Strand::Reverse),
+        "." | "?" => None,
+        _ => return Err(Gff3Error::InvalidStrand { line: number }),
+    };
+    let phase = match columns[7] {
+        "." => None,
+        "0" => Some(0),
+        "1" => Some(1),
+        "2" => Some(2),
+        _ => return Err(Gff3Error::InvalidPhase { line: number }),
+    };
+    let mut attributes = HashMap::new();
+    for attribute in columns[8].split(';').map(str::trim) {
+        if attribute.is_empty() || attribute == "." {
+            continue;
+        }
+        match attribute.split_once('=') {
+            Some((tag, value)) if !tag.is_empty() => {
+                attributes.insert(percent_decode(tag), percent_decode(value));
+            }
+            _ => return Err(Gff3Error::InvalidAttribute { line: number }),
+        }
+    }
+    Ok(Gff3Record {
+        seqname: percent_decode(columns[0]),
+        source: columns[1].to_string(),
+        feature_type: columns[2].to_string(),
+        start,
+        end,
+        score,
+        strand,
+        phase,
+        attributes,
+    })
+}
+
+/// 解码 `%XX` 形式的转义字符，无效的转义保持原样
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit();
+        if escaped {
+            let hex = |b: u8| (b as char).to_digit(16).unwrap_or(0) as u8;
+            decoded.push(hex(bytes[i + 1]) * 16 + hex(bytes[i + 2]));
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 返回位于 `seqname` 上、与闭区间 `start..=end` (以 1 开始) 重叠的所有特征
+pub fn features_overlapping<'a>(
+    records: &'a [Gff3Record],
+    seqname: &str,
+    start: usize,
+    end: usize,
+) -> Vec<&'a Gff3Record> {
+    records
+        .iter()
+        .filter(|record| record.seqname == seqname && record.overlaps(start, end))
+        .collect()
+}
+
+/// 从基因组序列中取出每个特征对应的序列，负链特征取反向互补
+/// id 优先使用 ID 属性，否则为 `seqname:start-end`；描述为特征类型
+/// 找不到对应序列或位置超出序列长度的特征会被跳过
+pub fn extract_sequences(
+    records: &[Gff3Record],
+    genome: &HashMap<String, Sequence>,
+) -> Vec<NamedSequence> {
+    let mut sequences = Vec::new();
+    for record in records {
+        let chromosome = match genome.get(&record.seqname) {
+            Some(chromosome) if record.end <= chromosome.len() => chromosome,
+            _ => continue,
+        };
+        let mut sequence = chromosome.subsequence(record.start - 1..record.end);
+        if record.strand == Some(Strand::Reverse) {
+            sequence = match sequence.reverse_complementary() {
+                Ok(reverse) => reverse,
+                Err(_) => continue,
+            };
+        }
+        let id = match record.attribute("ID") {
+            Some(id) => id.to_string(),
+            None => format!("{}:{}-{}", record.seqname, record.start, record.end),
+        };
+        sequences.push(NamedSequence::new(
+            id,
+            record.feature_type.clone(),
+            sequence,
+        ));
+    }
+    sequences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+    use std::io::Cursor;
+
+    const GFF: &str = "##gff-version 3
+##sequence-region chr1 1 40
+chr1\tdemo\tgene\t5\t20\t.\t+\t.\tID=gene1;Name=demo%3Bgene
+chr1\tdemo\tCDS\t5\t13\t0.9\t+\t0\tID=cds1;Parent=gene1
+chr1\tdemo\tcircRNA\t21\t30\t.\t-\t.\tID=circ1;Note=back%2Dsplice
+chr2\tdemo\tgene\t1\t4\t.\t.\t.\tID=gene2
+##FASTA
+>chr1
+ACGT
+";
+
+    #[test]
+    fn parse_gff3_records() {
+        let records = parse(Cursor::new(GFF)).unwrap();
+        assert_eq!(records.len(), 4);
+        let gene = &records[0];
+        assert_eq!(gene.seqname, "chr1");
+        assert_eq!(gene.feature_type, "gene");
+        assert_eq!((gene.start, gene.end), (5, 20));
+        assert_eq!(gene.strand, Some(Strand::Forward));
+        assert_eq!(gene.attribute("Name"), Some("demo;gene"));
+        assert_eq!(records[1].score, Some(0.9));
+        assert_eq!(records[1].phase, Some(0));
+        assert_eq!(records[2].attribute("Note"), Some("back-splice"));
+        assert_eq!(records[3].strand, None);
+
+        let overlapping = features_overlapping(&records, "chr1", 14, 21);
+        let ids: Vec<&str> = overlapping
+            .iter()
+            .filter_map(|r| r.attribute("ID"))
+            .collect();
+        assert_eq!(ids, vec!["gene1", "circ1"]);
+        assert!(features_overlapping(&records, "chr3", 1, 100).is_empty());
+    }
+
+    #[test]
+    fn extract_feature_sequences() {
+        let records = parse(Cursor::new(GFF)).unwrap();
+        let mut genome = HashMap::new();
+        genome.insert(
+            String::from("chr1"),
+            Sequence::new(
+                BioType::Dna,
+                String::from("NNNNATGGCCAAATAAGGGGAAAACCCCGTNNNNNNNNNN"),
+            ),
+        );
+        let sequences = extract_sequences(&records, &genome);
+        assert_eq!(sequences.len(), 3);
+        assert_eq!(sequences[1].id, "cds1");
+        assert_eq!(sequences[1].description, "CDS");
+        assert_eq!(sequences[1].sequence.seq, "ATGGCCAAA");
+        assert_eq!(sequences[2].sequence.seq, "ACGGGGTTTT");
+    }
+
+    #[test]
+    fn malformed_gff3() {
+        let line = |text: &str| parse(Cursor::new(text.replace(' ', "\t")));
+        assert!(matches!(
+            line("chr1 src gene 5 20 . +"),
+            Err(Gff3Error::InvalidColumns { line: 1, found: 7 })
+        ));
+        assert!(matches!(
+            line("chr1 src gene 20 5 . + . ID=a"),
+            Err(Gff3Error::InvalidPosition { line: 1 })
+        ));
+        assert!(matches!(
+            line("chr1 src gene 5 20 high + . ID=a"),
+            Err(Gff3Error::InvalidScore { .. })
+        ));
+        assert!(matches!(
+            line("chr1 src gene 5 20 . * . ID=a"),
+            Err(Gff3Error::InvalidStrand { .. })
+        ));
+        assert!(matches!(
+            line("chr1 src CDS 5 20 . + 3 ID=a"),
+            Err(Gff3Error::InvalidPhase { .. })
+        ));
+        assert!(matches!(
+            line("chr1 src gene 5 20 . + . ID"),
+            Err(Gff3Error::InvalidAttribute { .. })
+        ));
+    }
+}
@@ -1,35 +1,68 @@
+// usize::is_multiple_of 需要 Rust 1.87，为了不提高最低支持版本仍使用 % 判断整除
+#![allow(clippy::manual_is_multiple_of)]
+
+pub mod aho_corasick;
+pub mod alignment;
+pub mod aminoacid;
+pub mod bed;
+pub mod bisulfite;
+pub mod builder;
+pub mod bwt;
+pub mod cigar;
+pub mod circrna;
 pub mod codon;
+pub mod complexity;
+pub mod composition;
+pub mod coordinate;
+pub mod crispr;
+pub mod display;
+pub mod distance;
+pub mod evolution;
+pub mod fasta;
+pub mod fastq;
+pub mod feature;
+pub mod genbank;
+pub mod gff3;
+pub mod mask;
+pub mod minhash;
+pub mod mirna;
+pub mod motif;
+pub mod orf;
+pub mod packed;
+pub mod pcr;
+pub mod polya;
+pub mod primer;
+pub mod protein;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod record;
+pub mod repeat;
+pub mod restriction;
 pub mod sequence;
-
-use sequence::{BioType, Sequence};
+pub mod set;
+pub mod signal;
+pub mod splice;
+pub mod structure;
+pub mod suffix;
+
+use fasta::FastaError;
+use sequence::BioType;
 use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::io::{self, BufReader};
 
-/// 接下来是对于生物序列对象的封装
-/// SeqRecode
-/// biopython 应该没有版权法吧
-/// 包括： 序列名称、类型、描述、序列
+pub use record::{NamedSequence, SequenceMetadata};
 
-/// 用于封装 fasta 对象数据
-/// 结构包括：id、name、description、sequence
-pub struct FastaRecode {
-    pub id: String,
-    pub description: String,
-    pub sequence: Sequence,
-}
+// 接下来是对于生物序列对象的封装
+// SeqRecode
+// biopython 应该没有版权法吧
+// 包括： 序列名称、类型、描述、序列
 
-impl FastaRecode {
-    /// 新建 FastaRecode 对象
-    pub fn new(id: String, description: String, sequence: Sequence) -> Self {
-        FastaRecode {
-            id,
-            description,
-            sequence,
-        }
-    }
-}
+/// 用于封装 fasta 对象数据
+/// 结构包括：id、description、sequence
+pub type FastaRecode = NamedSequence;
 
+/// 按指定的序列类型读取 fasta 文件
+/// 如果需要自动推断序列类型，请使用 `fasta::read`
 pub struct FastaReader {
     pub file: String,
     pub biotype: BioType,
@@ -37,54 +70,31 @@ pub struct FastaReader {
 
 impl FastaReader {
     pub fn read(&self) -> io::Result<Vec<FastaRecode>> {
-        let path = self.file.clone();
-        let file = File::open(&path)?;
-        let reader = io::BufReader::new(file);
-        let mut sequence_vec: Vec<FastaRecode> = Vec::new();
-        let mut fasta_recode: Option<FastaRecode> = None;
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.starts_with('>') {
-                if let Some(rec) = fasta_recode.take() {
-                    sequence_vec.push(rec);
-                }
-                let (id, description) = line[1..].split_once(' ').unwrap_or((&line[1..], ""));
-                fasta_recode = Some(FastaRecode::new(
-                    id.to_string(),
-                    description.to_string(),
-                    Sequence::new(self.biotype.clone(), String::new()),
-                ));
-            } else {
-                if let Some(ref mut rec) = fasta_recode {
-                    rec.sequence.seq.push_str(&line);
-                }
-            }
+        let file = File::open(&self.file)?;
+        let mut records = fasta::parse(BufReader::new(file)).map_err(|err| match err {
+            FastaError::Io(err) => err,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        })?;
+        for record in records.iter_mut() {
+            record.sequence.biotype = self.biotype.clone();
         }
-
-        // Push the last record if exists
-        if let Some(rec) = fasta_recode {
-            sequence_vec.push(rec);
-        }
-
-        Ok(sequence_vec)
+        Ok(records)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sequence::Sequence;
 
-    #[cfg(test)]
-
+    #[test]
     fn base_function() {
         let result: Sequence = Sequence::new(BioType::Dna, String::from("ACGTTCGA"));
         assert_eq!(result.len(), 8);
         assert_eq!(result.index(2), 'G');
     }
 
-    #[cfg(test)]
-
+    #[test]
     fn string() {
         let mut sequence1: Sequence = Sequence::new(BioType::Dna, String::from("AGCT"));
         println!("Sequence: {}", sequence1);
@@ -103,8 +113,7 @@ mod tests {
         );
     }
 
-    #[cfg(test)]
-
+    #[test]
     fn trans() {
         let sequence1: Sequence = Sequence::new(BioType::Dna, String::from("AGCTTCGAA"));
 
@@ -114,13 +123,14 @@ mod tests {
         println!("{:?}", sequence1.complementary());
     }
 
-    #[cfg(test)]
-
+    #[test]
     fn seqrecode() {
         let mut recode1 = FastaRecode {
             id: String::from("recode1"),
             description: String::new(),
             sequence: Sequence::new(BioType::Rna, String::from("AGCUTUCG")),
+            metadata: SequenceMetadata::default(),
+            features: feature::FeatureIndex::new(),
         };
 
         let recorde2 = FastaRecode::new(
@@ -0,0 +1,85 @@
+use super::sequence::{BioType, Sequence};
+
+// 序列屏蔽，常用于重复序列分析或比对之前的预处理
+// 硬屏蔽把区域替换为 N (蛋白质为 X)，软屏蔽把区域改为小写
+// 区域为以 0 开始的左闭右开区间 (start, end)，与 `Sequence::subsequence` 一致
+
+impl Sequence {
+    /// 硬屏蔽用于替换的字符，核酸为 N，蛋白质为 X (N 是天冬酰胺)
+    fn mask_char(&self) -> char {
+        match self.biotype {
+            BioType::Protein => 'X',
+            BioType::Dna | BioType::Rna => 'N',
+        }
+    }
+
+    /// 对每个区域中的字符应用 `mask`，区域可以重叠
+    /// 区域越界或起点大于终点时会 panic
+    fn mask_with(&self, regions: &[(usize, usize)], mask: impl Fn(char) -> char) -> Sequence {
+        let mut chars: Vec<char> = self.seq.chars().collect();
+        for &(start, end) in regions {
+            if start > end {
+                panic!("区间起点 {} 大于终点 {}", start, end);
+            }
+            if end > chars.len() {
+                panic!("区间终点 {} 超出序列长度 {}", end, chars.len());
+            }
+            for c in chars[start..end].iter_mut() {
+                *c = mask(*c);
+            }
+        }
+        Sequence {
+            biotype: self.biotype.clone(),
+            seq: chars.into_iter().collect(),
+            topology: self.topology,
+        }
+    }
+
+    /// 将区域中的字符替换为 N (蛋白质为 X)
+    /// 区域越界或起点大于终点时会 panic
+    pub fn hard_mask(&self, regions: &[(usize, usize)]) -> Sequence {
+        let mask = self.mask_char();
+        self.mask_with(regions, |_| mask)
+    }
+
+    /// 将区域中的字符改为小写
+    /// 区域越界或起点大于终点时会 panic
+    pub fn soft_mask(&self, regions: &[(usize, usize)]) -> Sequence {
+        self.mask_with(regions, |c| c.to_ascii_lowercase())
+    }
+
+    /// 序列中是否含有小写字符或 N (蛋白质为 X)，用于检测已经屏蔽过的输入
+    pub fn is_masked(&self) -> bool {
+        let mask = self.mask_char();
+        self.seq
+            .chars()
+            .any(|c| c.is_ascii_lowercase() || c.to_ascii_uppercase() == mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hard_and_soft_mask() {
+        let dna = Sequence::new_circular(BioType::Dna, String::from("ACGTACGTAC"));
+        assert!(!dna.is_masked());
+        let hard = dna.hard_mask(&[(1, 3), (2, 5)]);
+        assert_eq!(hard.seq, "ANNNNCGTAC");
+        assert!(hard.is_masked());
+        assert!(hard.is_circular());
+        let soft = dna.soft_mask(&[(6, 10)]);
+        assert_eq!(soft.seq, "ACGTACgtac");
+        assert!(soft.is_masked());
+
+        // 互补之后仍保持屏蔽状态
+        let masked = dna.hard_mask(&[(0, 2)]).soft_mask(&[(5, 8)]);
+        assert_eq!(masked.seq, "NNGTAcgtAC");
+        assert_eq!(masked.reverse_complementary().unwrap().seq, "GTacgTACNN");
+
+        let protein = Sequence::new(BioType::Protein, String::from("MNKL"));
+        assert!(!protein.is_masked());
+        assert_eq!(protein.hard_mask(&[(0, 1)]).seq, "XNKL");
+    }
+}
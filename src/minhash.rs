@@ -0,0 +1,204 @@
+use super::sequence::{BioType, Sequence};
+
+use std::collections::BTreeSet;
+
+// MinHash 草图，用于快速估计大量序列之间的相似度 (Ondov et al. 2016, Mash)
+// 每条序列只保留 k-mer 哈希值中最小的若干个，比较两个草图的时间只与草图大小有关
+
+/// 一条序列的 MinHash 草图
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHashSketch {
+    /// 从小到大排列、互不相同的 k-mer 哈希值
+    hashes: Vec<u64>,
+    k: usize,
+    num_hashes: usize,
+}
+
+impl MinHashSketch {
+    /// 草图中的哈希值，从小到大排列
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// k-mer 的长度
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// 草图最多保留的哈希值个数
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+}
+
+/// 为序列建立草图，保留最小的 `num_hashes` 个 k-mer 哈希值
+/// DNA 与 RNA 使用正反链中较小的 k-mer (canonical k-mer)，不区分大小写，T 与 U 视为相同，
+/// 含有 ACGTU 以外字符的 k-mer 会被跳过；环状序列包括跨过接头的 k-mer
+pub fn sketch(seq: &Sequence, k: usize, num_hashes: usize) -> MinHashSketch {
+    let mut chars: Vec<char> = seq
+        .seq
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'U' if seq.biotype != BioType::Protein => 'T',
+            other => other,
+        })
+        .collect();
+    if seq.is_circular() && k > 1 && k <= chars.len() {
+        chars.extend_from_within(..k - 1);
+    }
+    let nucleotide = seq.biotype != BioType::Protein;
+
+    let mut smallest: BTreeSet<u64> = BTreeSet::new();
+    if k > 0 && num_hashes > 0 {
+        for kmer in chars.windows(k) {
+            let kmer: String = if nucleotide {
+                match canonical(kmer) {
+                    Some(kmer) => kmer,
+                    None => continue,
+                }
+            } else {
+                kmer.iter().collect()
+            };
+            let hash = hash_kmer(kmer.as_bytes());
+            if smallest.len() < num_hashes {
+                smallest.insert(hash);
+            } else if hash < *smallest.last().unwrap() && smallest.insert(hash) {
+                smallest.pop_last();
+            }
+        }
+    }
+    MinHashSketch {
+        hashes: smallest.into_iter().collect(),
+        k,
+        num_hashes,
+    }
+}
+
+/// 估计两条序列 k-mer 集合的 Jaccard 相似度
+/// 取两个草图并集中最小的 s 个哈希值 (s 为两者中较小的草图大小)，返回其中同时出现在两个草图中的比例
+/// 两个草图都为空时返回 0.0；k 不同时会 panic
+pub fn jaccard_estimate(a: &MinHashSketch, b: &MinHashSketch) -> f64 {
+    if a.k != b.k {
+        panic!("k 不同的草图不能比较: {} 与 {}", a.k, b.k);
+    }
+    let size = a.num_hashes.min(b.num_hashes);
+    let (mut i, mut j) = (0, 0);
+    let (mut shared, mut total) = (0, 0);
+    while total < size && (i < a.hashes.len() || j < b.hashes.len()) {
+        match (a.hashes.get(i), b.hashes.get(j)) {
+            (Some(x), Some(y)) if x == y => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+            (Some(x), Some(y)) if x < y => i += 1,
+            (Some(_), None) => i += 1,
+            _ => j += 1,
+        }
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    shared as f64 / total as f64
+}
+
+/// Mash 距离 D = -1/k · ln(2J / (1 + J))，近似于两条序列之间每个碱基的突变率
+/// J 为 `jaccard_estimate` 的结果，没有共同的哈希值时返回 1.0；D < 0.05 的序列通常可以视为几乎相同
+pub fn mash_distance(a: &MinHashSketch, b: &MinHashSketch) -> f64 {
+    let jaccard = jaccard_estimate(a, b);
+    if jaccard == 0.0 {
+        return 1.0;
+    }
+    (-1.0 / a.k as f64 * (2.0 * jaccard / (1.0 + jaccard)).ln()).max(0.0)
+}
+
+/// k-mer 与其反向互补中字典序较小的一个，含有 ACGT 以外的字符时返回 None
+fn canonical(kmer: &[char]) -> Option<String> {
+    let mut reverse = String::with_capacity(kmer.len());
+    for &base in kmer.iter().rev() {
+        reverse.push(match base {
+            'A' => 'T',
+            'C' => 'G',
+            'G' => 'C',
+            'T' => 'A',
+            _ => return None,
+        });
+    }
+    let forward: String = kmer.iter().collect();
+    Some(forward.min(reverse))
+}
+
+/// 64 位 FNV-1a 哈希，再用 SplitMix64 的混合函数打散低位
+fn hash_kmer(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 线性同余生成器产生的伪随机 DNA
+    fn pseudo_random_dna(length: usize, mut state: u64) -> String {
+        (0..length)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ['A', 'C', 'G', 'T'][(state >> 33) as usize % 4]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sketch_similarity() {
+        let seq = pseudo_random_dna(5000, 1);
+        let dna = Sequence::new(BioType::Dna, seq.clone());
+        let a = sketch(&dna, 21, 500);
+        assert_eq!(a.hashes().len(), 500);
+        assert!(a.hashes().windows(2).all(|w| w[0] < w[1]));
+
+        // 反向互补序列与 RNA 形式的 k-mer 集合相同
+        let reverse = sketch(&dna.reverse_complementary().unwrap(), 21, 500);
+        assert_eq!(jaccard_estimate(&a, &reverse), 1.0);
+        assert_eq!(mash_distance(&a, &reverse), 0.0);
+        let rna = Sequence::new(BioType::Rna, seq.replace('T', "u"));
+        assert_eq!(sketch(&rna, 21, 500), a);
+
+        // 与前一半序列相同：k-mer 集合的 Jaccard 相似度约为 0.5
+        let half = sketch(&dna.subsequence(..2500), 21, 500);
+        let jaccard = jaccard_estimate(&a, &half);
+        assert!((jaccard - 0.5).abs() < 0.08);
+
+        // 无关的随机序列几乎没有共同的 k-mer
+        let other = sketch(
+            &Sequence::new(BioType::Dna, pseudo_random_dna(5000, 2)),
+            21,
+            500,
+        );
+        assert_eq!(jaccard_estimate(&a, &other), 0.0);
+        assert_eq!(mash_distance(&a, &other), 1.0);
+
+        // 每 50 个碱基一个突变，Mash 距离与突变率 0.02 接近，低于 0.05
+        let mut mutated: Vec<char> = seq.chars().collect();
+        for base in mutated.iter_mut().step_by(50) {
+            *base = if *base == 'A' { 'C' } else { 'A' };
+        }
+        let mutated = sketch(
+            &Sequence::new(BioType::Dna, mutated.into_iter().collect()),
+            21,
+            500,
+        );
+        let distance = mash_distance(&a, &mutated);
+        assert!(distance > 0.01 && distance < 0.05);
+    }
+}
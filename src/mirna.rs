@@ -0,0 +1,159 @@
+use super::sequence::{BioType, Sequence};
+
+// miRNA 结合位点的预测，用于 circRNA 海绵作用的分析
+// miRNA 5' 端的种子区 (通常为第 2 ~ 8 位) 与靶序列反向互补配对，靶序列按 5' -> 3' 给出
+
+/// 靶序列上的一个 miRNA 结合位点
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirnaSite {
+    /// 与种子区配对的靶序列片段的起点 (以 0 开始)，环状序列中片段可以跨过接头
+    pub position: usize,
+    /// 配对得分：Watson-Crick 配对记 1 分，G:U 摆动配对记 0.5 分，再除以种子区长度
+    pub seed_score: f64,
+    /// 与种子区配对的靶序列片段
+    pub site: Sequence,
+}
+
+/// 统一为大写的 RNA 碱基
+fn rna_base(base: char) -> char {
+    match base.to_ascii_uppercase() {
+        'T' => 'U',
+        other => other,
+    }
+}
+
+/// miRNA 碱基与靶序列碱基的配对得分，不能配对时返回 None
+fn pair_score(mirna: char, target: char, allow_gu: bool) -> Option<f64> {
+    match (rna_base(mirna), rna_base(target)) {
+        ('A', 'U') | ('U', 'A') | ('G', 'C') | ('C', 'G') => Some(1.0),
+        ('G', 'U') | ('U', 'G') if allow_gu => Some(0.5),
+        _ => None,
+    }
+}
+
+/// 在靶序列中寻找与 miRNA 种子区完全互补的位点，`allow_gu` 为 true 时允许 G:U 摆动配对
+/// `seed_start` 与 `seed_end` 为 miRNA 上以 1 开始的闭区间，常用的种子区为 2 ~ 8
+/// 靶序列为环状时会检查跨过接头的位点；种子区无效或序列为蛋白质时返回空数组
+pub fn find_mirna_binding_sites(
+    target: &Sequence,
+    mirna: &Sequence,
+    seed_start: usize,
+    seed_end: usize,
+    allow_gu: bool,
+) -> Vec<MirnaSite> {
+    if target.biotype == BioType::Protein
+        || mirna.biotype == BioType::Protein
+        || seed_start == 0
+        || seed_start > seed_end
+        || seed_end > mirna.len()
+    {
+        return Vec::new();
+    }
+    // 种子区反向后与靶序列片段逐个配对
+    let mut seed: Vec<char> = mirna
+        .seq
+        .chars()
+        .skip(seed_start - 1)
+        .take(seed_end - seed_start + 1)
+        .collect();
+    seed.reverse();
+    let n = target.len();
+    let mut bases: Vec<char> = target.seq.chars().collect();
+    let starts = if target.is_circular() {
+        bases.extend(target.seq.chars().take(seed.len() - 1));
+        n
+    } else {
+        (n + 1).saturating_sub(seed.len())
+    };
+    let mut sites = Vec::new();
+    for position in 0..starts.min(bases.len().saturating_sub(seed.len() - 1)) {
+        let window = &bases[position..position + seed.len()];
+        let score: Option<f64> = seed
+            .iter()
+            .zip(window)
+            .map(|(&m, &t)| pair_score(m, t, allow_gu))
+            .sum();
+        if let Some(score) = score {
+            sites.push(MirnaSite {
+                position,
+                seed_score: score / seed.len() as f64,
+                site: Sequence::new(target.biotype.clone(), window.iter().collect()),
+            });
+        }
+    }
+    sites
+}
+
+/// 按 TargetScan 的位点类型给出的得分，越小表示抑制作用越强
+/// `target_site` 为与 miRNA 第 1 ~ 8 位相对的 8 nt 靶序列 (5' -> 3')，最后一个碱基与 miRNA 第 1 位相对
+/// 只计算 context 得分模型 (Grimson et al. 2007) 中位点类型一项，种子区只接受 Watson-Crick 配对：
+/// - 8mer (第 2 ~ 8 位配对且第 1 位相对为 A)：-0.310
+/// - 7mer-m8 (第 2 ~ 8 位配对)：-0.161
+/// - 7mer-A1 (第 2 ~ 7 位配对且第 1 位相对为 A)：-0.099
+/// - 其他 (包括 6mer)：0.0
+pub fn seed_match_score(mirna: &Sequence, target_site: &Sequence) -> f64 {
+    let mirna: Vec<char> = mirna.seq.chars().collect();
+    let site: Vec<char> = target_site.seq.chars().collect();
+    if mirna.len() < 8 || site.len() != 8 {
+        return 0.0;
+    }
+    // site[7 - i] 与 miRNA 的第 i + 1 位相对
+    let paired = |i: usize| pair_score(mirna[i], site[7 - i], false).is_some();
+    let seed_2_7 = (1..7).all(paired);
+    let m8 = paired(7);
+    let a1 = rna_base(site[7]) == 'A';
+    match (seed_2_7, m8, a1) {
+        (true, true, true) => -0.310,
+        (true, true, false) => -0.161,
+        (true, false, true) => -0.099,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// miR-7-5p，ciRS-7 (CDR1as) 是它最著名的海绵
+    const MIR7: &str = "UGGAAGACUAGUGAUUUUGUUGU";
+
+    #[test]
+    fn seed_sites() {
+        let mirna = Sequence::new(BioType::Rna, String::from(MIR7));
+        // 种子区 GGAAGAC (第 2 ~ 8 位) 的反向互补为 GUCUUCC
+        let target = Sequence::new(BioType::Rna, String::from("AAGUCUUCCAAAGUCUUUCAA"));
+        let sites = find_mirna_binding_sites(&target, &mirna, 2, 8, false);
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].position, 2);
+        assert_eq!(sites[0].site.seq, "GUCUUCC");
+        assert_eq!(sites[0].seed_score, 1.0);
+
+        // 第二个位点在 miRNA 第 3 位为 G:U 摆动配对
+        let wobble = find_mirna_binding_sites(&target, &mirna, 2, 8, true);
+        assert_eq!(wobble.len(), 2);
+        assert_eq!(wobble[1].position, 12);
+        assert!((wobble[1].seed_score - 6.5 / 7.0).abs() < 1e-9);
+
+        // 环状序列中跨过接头的位点，DNA 靶序列同样可以使用
+        let mut circ = Sequence::new_circular(BioType::Dna, String::from("TCCAAAAAGTCT"));
+        let sites = find_mirna_binding_sites(&circ, &mirna, 2, 8, false);
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].position, 8);
+        assert_eq!(sites[0].site.seq, "GTCTTCC");
+        circ.topology = Default::default();
+        assert!(find_mirna_binding_sites(&circ, &mirna, 2, 8, false).is_empty());
+        assert!(find_mirna_binding_sites(&target, &mirna, 0, 8, false).is_empty());
+        assert!(find_mirna_binding_sites(&target, &mirna, 2, 30, false).is_empty());
+    }
+
+    #[test]
+    fn targetscan_site_types() {
+        let mirna = Sequence::new(BioType::Rna, String::from(MIR7));
+        let site = |s: &str| seed_match_score(&mirna, &Sequence::new(BioType::Rna, s.to_string()));
+        assert_eq!(site("GUCUUCCA"), -0.310);
+        assert_eq!(site("GUCUUCCG"), -0.161);
+        assert_eq!(site("AUCUUCCA"), -0.099);
+        assert_eq!(site("AUCUUCCG"), 0.0);
+        assert_eq!(site("GUCUUC"), 0.0);
+    }
+}
@@ -0,0 +1,353 @@
+use super::sequence::{BioType, Sequence, Strand, AMINO_ACIDS};
+use super::set::SequenceSet;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+// 位置特异性打分矩阵 (PSSM)，用于转录因子结合位点、RNA 结合蛋白基序等的预测
+// 以及前景序列集合相对于背景集合的 k-mer 富集分析
+
+/// `Pssm::from_fasta` 使用的伪计数
+pub const DEFAULT_PSEUDOCOUNT: f64 = 0.5;
+
+/// 位置特异性打分矩阵
+/// 每一列记录各字符的 log2 优势比 (log-odds)，背景频率取字母表上的均匀分布
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pssm {
+    pub columns: Vec<HashMap<char, f64>>,
+}
+
+/// PSSM 在序列上的一次命中
+#[derive(Debug, Clone, PartialEq)]
+pub struct PssmMatch {
+    /// 命中区间在正链上的起点 (以 0 开始)，负链命中同样以正链坐标表示
+    pub start: usize,
+    /// 命中区间的终点 (不含)
+    pub end: usize,
+    pub score: f64,
+    pub strand: Strand,
+}
+
+impl Pssm {
+    /// 由每一列各字符的计数构建 PSSM，字符不区分大小写
+    /// 字母表为所有列中出现过的字符，某一列中缺失的字符计数视为 0
+    pub fn from_counts(counts: &[HashMap<char, usize>], pseudocount: f64) -> Pssm {
+        let alphabet: BTreeSet<char> = counts
+            .iter()
+            .flat_map(|column| column.keys())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let background = 1.0 / alphabet.len() as f64;
+        let columns = counts
+            .iter()
+            .map(|column| {
+                let count = |c: char| {
+                    column
+                        .iter()
+                        .filter(|(k, _)| k.to_ascii_uppercase() == c)
+                        .map(|(_, &n)| n)
+                        .sum::<usize>() as f64
+                };
+                let total = alphabet.iter().map(|&c| count(c)).sum::<f64>()
+                    + pseudocount * alphabet.len() as f64;
+                alphabet
+                    .iter()
+                    .map(|&c| {
+                        let frequency = (count(c) + pseudocount) / total;
+                        (c, (frequency / background).log2())
+                    })
+                    .collect()
+            })
+            .collect();
+        Pssm { columns }
+    }
+
+    /// 由等长的比对序列 (例如一组已知的结合位点) 统计计数并构建 PSSM
+    /// 字母表取第一条序列类型对应的标准字符，使用 `DEFAULT_PSEUDOCOUNT` 作为伪计数
+    /// 序列长度不一致或为空时返回 Err
+    pub fn from_fasta(records: &[Sequence]) -> Result<Pssm, String> {
+        let len = match records.first() {
+            Some(first) => first.len(),
+            None => return Err(String::from("不能由空的序列集合构建 PSSM")),
+        };
+        if let Some(record) = records.iter().find(|record| record.len() != len) {
+            return Err(format!("比对序列的长度不一致: {} 与 {}", len, record.len()));
+        }
+        let alphabet = match records[0].biotype {
+            BioType::Dna => "ACGT",
+            BioType::Rna => "ACGU",
+            BioType::Protein => AMINO_ACIDS,
+        };
+        let empty: HashMap<char, usize> = alphabet.chars().map(|c| (c, 0)).collect();
+        let mut counts = vec![empty; len];
+        for record in records {
+            for (column, c) in counts.iter_mut().zip(record.seq.chars()) {
+                *column.entry(c.to_ascii_uppercase()).or_insert(0) += 1;
+            }
+        }
+        Ok(Pssm::from_counts(&counts, DEFAULT_PSEUDOCOUNT))
+    }
+
+    /// 基序长度 (列数)
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// 是否没有任何列
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// 可能得到的最高分
+    pub fn max_score(&self) -> f64 {
+        self.columns
+            .iter()
+            .map(|column| column.values().cloned().fold(f64::NEG_INFINITY, f64::max))
+            .sum()
+    }
+
+    /// 对一段与基序等长的字符计算得分，含有字母表之外的字符时返回 None
+    fn score(&self, window: &[char]) -> Option<f64> {
+        self.columns
+            .iter()
+            .zip(window)
+            .map(|(column, c)| column.get(c).cloned())
+            .sum()
+    }
+
+    /// 扫描序列，返回得分不低于 `threshold` 的所有命中，按起点排序
+    /// DNA 序列同时扫描两条链，其他序列只扫描给定的方向
+    /// 含有 PSSM 字母表之外字符的窗口会被跳过
+    pub fn scan(&self, seq: &Sequence, threshold: f64) -> Vec<PssmMatch> {
+        let strand = if seq.biotype == BioType::Dna {
+            Strand::Both
+        } else {
+            Strand::Forward
+        };
+        self.scan_strand(seq, threshold, strand)
+    }
+
+    /// 与 `scan` 相同，但只扫描 `strand` 指定的链，同一起点正链的命中在前
+    /// 负链在反向互补序列上扫描，蛋白质序列没有负链
+    pub fn scan_strand(&self, seq: &Sequence, threshold: f64, strand: Strand) -> Vec<PssmMatch> {
+        let width = self.len();
+        let n = seq.len();
+        if width == 0 || width > n {
+            return Vec::new();
+        }
+        let mut strands = Vec::new();
+        if strand.includes(Strand::Forward) {
+            strands.push((Strand::Forward, seq.clone()));
+        }
+        if strand.includes(Strand::Reverse) {
+            if let Ok(reverse) = seq.reverse_complementary() {
+                strands.push((Strand::Reverse, reverse));
+            }
+        }
+        let mut matches = Vec::new();
+        for (strand, strand_seq) in strands {
+            let chars: Vec<char> = strand_seq
+                .seq
+                .chars()
+                .map(|c| c.to_ascii_uppercase())
+                .collect();
+            for (offset, window) in chars.windows(width).enumerate() {
+                let score = match self.score(window) {
+                    Some(score) if score >= threshold => score,
+                    _ => continue,
+                };
+                let start = if strand == Strand::Forward {
+                    offset
+                } else {
+                    n - offset - width
+                };
+                matches.push(PssmMatch {
+                    start,
+                    end: start + width,
+                    score,
+                    strand,
+                });
+            }
+        }
+        matches.sort_by_key(|m| (m.start, m.strand == Strand::Reverse));
+        matches
+    }
+}
+
+/// 一个 k-mer 在前景序列中的富集情况
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotifEnrichment {
+    pub kmer: String,
+    /// 含有该 k-mer 的前景序列所占的比例
+    pub fg_frequency: f64,
+    /// 含有该 k-mer 的背景序列所占的比例
+    pub bg_frequency: f64,
+    /// 2x2 列联表的优势比，每个格子加 0.5 (Haldane 校正) 以避免除以 0
+    pub odds_ratio: f64,
+    /// 单侧 Fisher 精确检验 (前景中富集) 的 p 值
+    pub p_value: f64,
+}
+
+/// 比较前景与背景序列集合中每个 k-mer 出现的序列数，找出在前景中富集的 k-mer
+/// k-mer 不区分大小写，一条序列中出现多次只计一次；只报告至少在一条前景序列中出现的 k-mer
+/// 结果按 p 值从小到大排序，p 值相同时按优势比从大到小排序
+pub fn motif_enrichment(
+    foreground: &SequenceSet,
+    background: &SequenceSet,
+    k: usize,
+) -> Vec<MotifEnrichment> {
+    let count = |set: &SequenceSet| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for record in set.iter() {
+            let kmers: HashSet<String> = record.sequence.kmer_count(k).into_keys().collect();
+            for kmer in kmers {
+                *counts.entry(kmer).or_insert(0) += 1;
+            }
+        }
+        counts
+    };
+    let (fg_counts, bg_counts) = (count(foreground), count(background));
+    let (fg_total, bg_total) = (foreground.len(), background.len());
+
+    // ln(n!)，n 不超过两组序列总数
+    let mut ln_factorial = vec![0.0; fg_total + bg_total + 1];
+    for n in 1..ln_factorial.len() {
+        ln_factorial[n] = ln_factorial[n - 1] + (n as f64).ln();
+    }
+
+    let mut results: Vec<MotifEnrichment> = fg_counts
+        .into_iter()
+        .map(|(kmer, fg)| {
+            let bg = bg_counts.get(&kmer).copied().unwrap_or(0);
+            let (a, b) = (fg as f64 + 0.5, (fg_total - fg) as f64 + 0.5);
+            let (c, d) = (bg as f64 + 0.5, (bg_total - bg) as f64 + 0.5);
+            MotifEnrichment {
+                fg_frequency: fg as f64 / fg_total as f64,
+                bg_frequency: if bg_total == 0 {
+                    0.0
+                } else {
+                    bg as f64 / bg_total as f64
+                },
+                odds_ratio: (a * d) / (b * c),
+                p_value: fisher_greater(fg, fg_total, bg, bg_total, &ln_factorial),
+                kmer,
+            }
+        })
+        .collect();
+    results.sort_by(|x, y| {
+        x.p_value
+            .total_cmp(&y.p_value)
+            .then(y.odds_ratio.total_cmp(&x.odds_ratio))
+            .then_with(|| x.kmer.cmp(&y.kmer))
+    });
+    results
+}
+
+/// 单侧 Fisher 精确检验：在边际总数固定时，前景中含有 k-mer 的序列数不少于 `fg` 的概率
+/// 超几何分布的每一项都用 ln(n!) 表精确计算，不需要对大计数做近似
+fn fisher_greater(
+    fg: usize,
+    fg_total: usize,
+    bg: usize,
+    bg_total: usize,
+    ln_factorial: &[f64],
+) -> f64 {
+    let with = fg + bg;
+    let total = fg_total + bg_total;
+    let ln_choose = |n: usize, r: usize| ln_factorial[n] - ln_factorial[r] - ln_factorial[n - r];
+    let ln_denominator = ln_choose(total, fg_total);
+    let p: f64 = (fg..=with.min(fg_total))
+        .filter(|&x| with - x <= bg_total)
+        .map(|x| {
+            (ln_choose(with, x) + ln_choose(total - with, fg_total - x) - ln_denominator).exp()
+        })
+        .sum();
+    p.min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pssm_from_alignment() {
+        let sites: Vec<Sequence> = ["TATAAT", "TATAAT", "TATACT", "TAAAAT"]
+            .iter()
+            .map(|s| Sequence::new(BioType::Dna, s.to_string()))
+            .collect();
+        let pssm = Pssm::from_fasta(&sites).unwrap();
+        assert_eq!(pssm.len(), 6);
+        assert_eq!(pssm.columns[0].len(), 4);
+        // 第一列只出现 T，T 的优势比最高
+        assert!(pssm.columns[0][&'T'] > 0.0);
+        assert!(pssm.columns[0][&'A'] < 0.0);
+        assert!(pssm.columns[4][&'A'] > pssm.columns[4][&'C']);
+
+        let short = Sequence::new(BioType::Dna, String::from("TATA"));
+        assert!(Pssm::from_fasta(&[sites[0].clone(), short]).is_err());
+        assert!(Pssm::from_fasta(&[]).is_err());
+    }
+
+    #[test]
+    fn pssm_scan_both_strands() {
+        let sites: Vec<Sequence> = ["TATAAT", "TATAAT", "TATACT"]
+            .iter()
+            .map(|s| Sequence::new(BioType::Dna, s.to_string()))
+            .collect();
+        let pssm = Pssm::from_fasta(&sites).unwrap();
+        // 位置 2 为正链上的 TATAAT，位置 12 为负链上的 TATAAT (正链 ATTATA)
+        let dna = Sequence::new(BioType::Dna, String::from("GCtataatGCGCATTATAGC"));
+        let hits = pssm.scan(&dna, pssm.max_score() - 0.01);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].start, hits[0].end), (2, 8));
+        assert_eq!(hits[0].strand, Strand::Forward);
+        assert_eq!((hits[1].start, hits[1].strand), (12, Strand::Reverse));
+        assert!((hits[1].score - pssm.max_score()).abs() < 1e-9);
+
+        let rna = Sequence::new(BioType::Rna, String::from("AUUAUA"));
+        assert!(pssm.scan(&rna, f64::NEG_INFINITY).is_empty());
+
+        let reverse = pssm.scan_strand(&dna, pssm.max_score() - 0.01, Strand::Reverse);
+        assert_eq!(reverse.len(), 1);
+        assert_eq!(reverse[0].start, 12);
+        assert_eq!(
+            pssm.scan_strand(&dna, pssm.max_score() - 0.01, Strand::Both),
+            hits
+        );
+    }
+
+    #[test]
+    fn kmer_enrichment() {
+        use crate::record::NamedSequence;
+        let set = |seqs: &[&str]| -> SequenceSet {
+            seqs.iter()
+                .enumerate()
+                .map(|(i, seq)| {
+                    NamedSequence::new(
+                        format!("seq{}", i),
+                        String::new(),
+                        Sequence::new(BioType::Rna, seq.to_string()),
+                    )
+                })
+                .collect()
+        };
+        // 前景中每条序列都含有 UGCAUG (RBFOX 结合基序)
+        let foreground = set(&["AAUGCAUGAA", "CCUGCAUGCC", "GGugcaugGG", "UUUGCAUGUU"]);
+        let background = set(&["AAAAAAAAAA", "CCCCCCCCCC", "GGGGGGGGGG", "UUUUUUUUUU"]);
+        let results = motif_enrichment(&foreground, &background, 6);
+        let top = &results[0];
+        assert_eq!(top.kmer, "UGCAUG");
+        assert_eq!(top.fg_frequency, 1.0);
+        assert_eq!(top.bg_frequency, 0.0);
+        // 4 条前景序列全部含有、4 条背景都不含：p = 1 / C(8, 4)
+        assert!((top.p_value - 1.0 / 70.0).abs() < 1e-12);
+        assert!((top.odds_ratio - 81.0).abs() < 1e-9);
+        assert!(results.windows(2).all(|w| w[0].p_value <= w[1].p_value));
+        // 只在背景中出现的 k-mer 不会报告
+        assert!(results.iter().all(|r| r.kmer != "AAAAAA"));
+        // 前景与背景中各有一条序列含有 AA：p = 1 - C(6, 4) / C(8, 4)
+        let results = motif_enrichment(&foreground, &background, 2);
+        let aa = results.iter().find(|r| r.kmer == "AA").unwrap();
+        assert!((aa.p_value - 55.0 / 70.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,150 @@
+use super::sequence::{BioType, Sequence, StopCodon, Strand};
+
+// 开放阅读框 (ORF) 的查找
+// 在每个读码框中从起始密码子 ATG (RNA 中为 AUG) 开始，直到同一读码框内的第一个终止密码子
+
+/// 六个读码框，正数为正链 (偏移 0、1、2)，负数为反向互补链
+pub const ALL_FRAMES: [i8; 6] = [1, 2, 3, -1, -2, -3];
+
+/// 一个开放阅读框
+/// start 与 end 为输入序列上从 0 开始的左闭右开区间，包括终止密码子
+#[derive(Debug, Clone)]
+pub struct Orf {
+    pub start: usize,
+    pub end: usize,
+    /// 1 ~ 3 为正链读码框，-1 ~ -3 为反向互补链读码框
+    pub frame: i8,
+    /// 编码链上的核苷酸序列，反向读码框中为反向互补后的序列
+    pub dna: Sequence,
+    /// 翻译产物，不包括终止密码子
+    pub protein: Sequence,
+}
+
+impl Orf {
+    /// 读码框所在的链
+    pub fn strand(&self) -> Strand {
+        if self.frame > 0 {
+            Strand::Forward
+        } else {
+            Strand::Reverse
+        }
+    }
+}
+
+impl Sequence {
+    /// 在六个读码框中查找蛋白质长度不小于 `min_length` 的 ORF
+    /// 每个起始密码子都会产生一个 ORF，因此嵌套或跨读码框重叠的 ORF 都会返回
+    pub fn find_orfs(&self, min_length: usize) -> Vec<Orf> {
+        self.find_orfs_in_frames(min_length, &ALL_FRAMES)
+    }
+
+    /// 只在 `strand` 指定的链的三个读码框中查找 ORF，`Strand::Both` 与 `find_orfs` 相同
+    pub fn find_orfs_on_strand(&self, min_length: usize, strand: Strand) -> Vec<Orf> {
+        let frames: Vec<i8> = ALL_FRAMES
+            .iter()
+            .copied()
+            .filter(|&frame| {
+                strand.includes(if frame > 0 {
+                    Strand::Forward
+                } else {
+                    Strand::Reverse
+                })
+            })
+            .collect();
+        self.find_orfs_in_frames(min_length, &frames)
+    }
+
+    /// 只在指定的读码框中查找 ORF，读码框取值见 `ALL_FRAMES`
+    /// 蛋白质序列以及非法的读码框不会产生结果
+    pub fn find_orfs_in_frames(&self, min_length: usize, frames: &[i8]) -> Vec<Orf> {
+        if self.biotype == BioType::Protein {
+            return Vec::new();
+        }
+        let reverse = match self.reverse_complementary() {
+            Ok(reverse) => reverse,
+            Err(_) => return Vec::new(),
+        };
+        let len = self.seq.len();
+        let mut orfs: Vec<Orf> = Vec::new();
+
+        for &frame in frames {
+            let (strand, offset) = match frame {
+                1..=3 => (self, (frame - 1) as usize),
+                -3..=-1 => (&reverse, (-frame - 1) as usize),
+                _ => continue,
+            };
+            let protein = match strand.translate_frame(offset, StopCodon::ReadThrough) {
+                Ok(protein) => protein.seq,
+                Err(_) => continue,
+            };
+            let residues = protein.as_bytes();
+
+            for (i, _) in residues.iter().enumerate().filter(|(_, &aa)| aa == b'M') {
+                let stop = match residues[i..].iter().position(|&aa| aa == b'*') {
+                    Some(stop) => i + stop,
+                    None => break,
+                };
+                if stop - i < min_length {
+                    continue;
+                }
+                let strand_start = offset + i * 3;
+                let strand_end = offset + (stop + 1) * 3;
+                let (start, end) = if frame > 0 {
+                    (strand_start, strand_end)
+                } else {
+                    (len - strand_end, len - strand_start)
+                };
+                orfs.push(Orf {
+                    start,
+                    end,
+                    frame,
+                    dna: strand.subsequence(strand_start..strand_end),
+                    protein: Sequence::new(BioType::Protein, protein[i..stop].to_string()),
+                });
+            }
+        }
+        orfs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_orfs() {
+        // 正链: ATG AAA TGG TAA，反向互补链中含有 ATG CCA TGA
+        let dna = Sequence::new(BioType::Dna, String::from("CCATGAAATGGTAAGTCATGGCATC"));
+        let orfs = dna.find_orfs(1);
+        let forward: Vec<&Orf> = orfs.iter().filter(|orf| orf.frame == 3).collect();
+        assert_eq!(forward.len(), 1);
+        assert_eq!((forward[0].start, forward[0].end), (2, 14));
+        assert_eq!(forward[0].dna.seq, "ATGAAATGGTAA");
+        assert_eq!(forward[0].protein.seq, "MKW");
+
+        let reverse: Vec<&Orf> = orfs.iter().filter(|orf| orf.frame < 0).collect();
+        assert_eq!(reverse.len(), 1);
+        assert_eq!(reverse[0].dna.seq, "ATGCCATGA");
+        assert_eq!(reverse[0].protein.seq, "MP");
+        assert_eq!(
+            dna.subsequence(reverse[0].start..reverse[0].end)
+                .reverse_complementary()
+                .unwrap()
+                .seq,
+            "ATGCCATGA"
+        );
+
+        assert_eq!(dna.find_orfs(3).len(), 1);
+        let minus = dna.find_orfs_on_strand(1, Strand::Reverse);
+        assert_eq!(minus.len(), 1);
+        assert_eq!(minus[0].strand(), Strand::Reverse);
+        assert_eq!(
+            dna.find_orfs_on_strand(1, Strand::Forward)[0].strand(),
+            Strand::Forward
+        );
+        assert_eq!(dna.find_orfs_on_strand(1, Strand::Both).len(), orfs.len());
+        assert!(dna.find_orfs_in_frames(1, &[1, 2]).is_empty());
+        let rna = Sequence::new(BioType::Rna, String::from("AUGUUUUGA"));
+        assert_eq!(rna.find_orfs_in_frames(1, &[1])[0].protein.seq, "MF");
+    }
+}
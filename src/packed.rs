@@ -0,0 +1,218 @@
+use super::sequence::{BioType, Sequence};
+
+use std::ops::{Bound, RangeBounds};
+
+// 2 bit 压缩存储的核酸序列，适合在内存中保存大量序列
+// 编码：A = 00，C = 01，G = 10，T/U = 11，互补碱基恰好为按位取反
+
+/// 每个 u64 保存的碱基数
+const BASES_PER_WORD: usize = 32;
+
+/// 以 2 bit 存储每个碱基的 DNA/RNA 序列
+/// 只能保存 A、C、G、T/U 四种碱基，大小写信息不会保留
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedSequence {
+    biotype: BioType,
+    len: usize,
+    /// 第 i 个碱基位于 `data[i / 32]` 的第 `2 * (i % 32)` 位起，未使用的位始终为 0
+    data: Vec<u64>,
+}
+
+impl PackedSequence {
+    /// 返回长度
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 序列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 序列类型
+    pub fn biotype(&self) -> &BioType {
+        &self.biotype
+    }
+
+    /// 实际占用的字节数 (不含结构体本身)
+    pub fn memory_usage(&self) -> usize {
+        self.data.len() * std::mem::size_of::<u64>()
+    }
+
+    /// 获取对应索引的碱基，索引越界时会 panic
+    pub fn index(&self, index: usize) -> char {
+        if index >= self.len {
+            panic!("索引 {} 超出序列长度 {}", index, self.len);
+        }
+        self.decode(self.code(index))
+    }
+
+    /// 截取一段区间作为新的压缩序列，区间越界或起点大于终点时会 panic
+    pub fn subsequence(&self, range: impl RangeBounds<usize>) -> PackedSequence {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        if start > end {
+            panic!("区间起点 {} 大于终点 {}", start, end);
+        }
+        if end > self.len {
+            panic!("区间终点 {} 超出序列长度 {}", end, self.len);
+        }
+        self.collect_codes((start..end).map(|i| self.code(i)))
+    }
+
+    /// 互补序列
+    pub fn complement(&self) -> PackedSequence {
+        self.collect_codes((0..self.len).map(|i| self.code(i) ^ 0b11))
+    }
+
+    /// 反向互补序列
+    pub fn reverse_complement(&self) -> PackedSequence {
+        self.collect_codes((0..self.len).rev().map(|i| self.code(i) ^ 0b11))
+    }
+
+    /// 第 `index` 个碱基的 2 bit 编码
+    fn code(&self, index: usize) -> u64 {
+        let shift = 2 * (index % BASES_PER_WORD);
+        (self.data[index / BASES_PER_WORD] >> shift) & 0b11
+    }
+
+    fn decode(&self, code: u64) -> char {
+        match (code, &self.biotype) {
+            (0, _) => 'A',
+            (1, _) => 'C',
+            (2, _) => 'G',
+            (_, BioType::Rna) => 'U',
+            _ => 'T',
+        }
+    }
+
+    /// 由 2 bit 编码构建同类型的新序列
+    fn collect_codes(&self, codes: impl Iterator<Item = u64>) -> PackedSequence {
+        let mut packed = PackedSequence {
+            biotype: self.biotype.clone(),
+            len: 0,
+            data: Vec::new(),
+        };
+        for code in codes {
+            packed.push_code(code);
+        }
+        packed
+    }
+
+    fn push_code(&mut self, code: u64) {
+        let offset = self.len % BASES_PER_WORD;
+        if offset == 0 {
+            self.data.push(0);
+        }
+        if let Some(word) = self.data.last_mut() {
+            *word |= code << (2 * offset);
+        }
+        self.len += 1;
+    }
+}
+
+/// 压缩 DNA/RNA 序列，蛋白质序列或含有 ACGT/U 以外字符 (包括简并碱基) 的序列返回 Err
+impl TryFrom<&Sequence> for PackedSequence {
+    type Error = String;
+
+    fn try_from(sequence: &Sequence) -> Result<Self, Self::Error> {
+        if sequence.biotype == BioType::Protein {
+            return Err(format!("不能压缩 {} 序列", BioType::Protein));
+        }
+        let mut packed = PackedSequence {
+            biotype: sequence.biotype.clone(),
+            len: 0,
+            data: Vec::with_capacity(sequence.len().div_ceil(BASES_PER_WORD)),
+        };
+        for base in sequence.seq.chars() {
+            let code = match base.to_ascii_uppercase() {
+                'A' => 0,
+                'C' => 1,
+                'G' => 2,
+                'T' | 'U' => 3,
+                other => return Err(format!("只能压缩 ACGT/U 四种碱基，遇到: {}", other)),
+            };
+            packed.push_code(code);
+        }
+        Ok(packed)
+    }
+}
+
+impl TryFrom<Sequence> for PackedSequence {
+    type Error = String;
+
+    fn try_from(sequence: Sequence) -> Result<Self, Self::Error> {
+        PackedSequence::try_from(&sequence)
+    }
+}
+
+/// 解压为大写的线性序列
+impl From<&PackedSequence> for Sequence {
+    fn from(packed: &PackedSequence) -> Self {
+        let seq = (0..packed.len)
+            .map(|i| packed.decode(packed.code(i)))
+            .collect();
+        Sequence::new(packed.biotype.clone(), seq)
+    }
+}
+
+impl From<PackedSequence> for Sequence {
+    fn from(packed: PackedSequence) -> Self {
+        Sequence::from(&packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_round_trip() {
+        let dna = Sequence::new(BioType::Dna, "acgtTGCA".repeat(10));
+        let packed = PackedSequence::try_from(&dna).unwrap();
+        assert_eq!(packed.len(), 80);
+        assert_eq!(packed.memory_usage(), 24);
+        assert_eq!(packed.index(1), 'C');
+        assert_eq!(packed.index(79), 'A');
+        assert_eq!(Sequence::from(&packed).seq, dna.seq.to_ascii_uppercase());
+        assert_eq!(Sequence::from(packed.subsequence(30..36)).seq, "CAACGT");
+        assert_eq!(packed.subsequence(..), packed);
+
+        let rna = Sequence::new(BioType::Rna, String::from("AUGGC"));
+        let packed = PackedSequence::try_from(rna.clone()).unwrap();
+        assert_eq!(Sequence::from(packed.complement()).seq, "UACCG");
+        assert_eq!(
+            Sequence::from(packed.reverse_complement()),
+            rna.reverse_complementary().unwrap()
+        );
+
+        let ambiguous = Sequence::new(BioType::Dna, String::from("ACGN"));
+        assert!(PackedSequence::try_from(&ambiguous).is_err());
+        let protein = Sequence::new(BioType::Protein, String::from("ACG"));
+        assert!(PackedSequence::try_from(&protein).is_err());
+    }
+
+    #[test]
+    fn packed_memory() {
+        let dna = Sequence::new(BioType::Dna, "ACGT".repeat(250_000));
+        let packed = PackedSequence::try_from(&dna).unwrap();
+        assert_eq!(packed.len(), 1_000_000);
+        assert!(packed.memory_usage() <= 250_000);
+        assert_eq!(packed.reverse_complement(), packed);
+    }
+
+    #[test]
+    #[should_panic]
+    fn packed_index_out_of_range() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGT"));
+        PackedSequence::try_from(&dna).unwrap().index(4);
+    }
+}
@@ -0,0 +1,241 @@
+use super::sequence::{BioType, Sequence};
+
+use std::{error, fmt};
+
+// 虚拟 PCR：在模板上寻找引物结合位点并给出扩增产物
+
+/// 虚拟 PCR 失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PcrError {
+    /// 模板或引物不是 DNA
+    NotDna(BioType),
+    /// 引物为空
+    EmptyPrimer,
+    /// 没有扩增产物
+    NoProduct,
+    /// 产生不止一个产物
+    MultipleProducts(usize),
+    /// 两条引物都能结合，但方向不是相对的 (反向引物位于正向引物上游)
+    WrongOrientation,
+}
+
+impl fmt::Display for PcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PcrError::NotDna(biotype) => {
+                write!(
+                    f,
+                    "虚拟 PCR 只支持 {} 序列，而不是 {}",
+                    BioType::Dna,
+                    biotype
+                )
+            }
+            PcrError::EmptyPrimer => write!(f, "引物不能为空"),
+            PcrError::NoProduct => write!(f, "没有扩增产物"),
+            PcrError::MultipleProducts(count) => write!(f, "产生了 {} 个扩增产物", count),
+            PcrError::WrongOrientation => write!(f, "引物的方向不相对，无法扩增"),
+        }
+    }
+}
+
+impl error::Error for PcrError {}
+
+/// 在模板上模拟 PCR，引物必须与模板完全匹配 (不区分大小写)，见 `simulate_pcr_with_mismatches`
+pub fn simulate_pcr(
+    template: &Sequence,
+    forward: &Sequence,
+    reverse: &Sequence,
+    max_product_size: usize,
+) -> Result<Sequence, PcrError> {
+    simulate_pcr_with_mismatches(template, forward, reverse, max_product_size, 0)
+}
+
+/// 在模板上模拟 PCR，每条引物最多允许 `max_mismatches` 个错配
+/// 一条引物结合在模板正链上，另一条引物 (5' -> 3') 的反向互补序列出现在正链上，
+/// 两者相对且产物不超过 `max_product_size` 时得到一个产物，产物两端为引物本身的序列
+/// 两条引物的角色可以互换，即正向引物也可以结合在负链上
+/// 环状模板上的结合位点与产物都可以跨过起点
+pub fn simulate_pcr_with_mismatches(
+    template: &Sequence,
+    forward: &Sequence,
+    reverse: &Sequence,
+    max_product_size: usize,
+    max_mismatches: usize,
+) -> Result<Sequence, PcrError> {
+    for sequence in [template, forward, reverse] {
+        if sequence.biotype != BioType::Dna {
+            return Err(PcrError::NotDna(sequence.biotype.clone()));
+        }
+    }
+    if forward.is_empty() || reverse.is_empty() {
+        return Err(PcrError::EmptyPrimer);
+    }
+    let circular = template.is_circular();
+    let bases: Vec<char> = template
+        .seq
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let n = bases.len();
+    let mut products: Vec<(usize, usize, &Sequence, Sequence)> = Vec::new();
+    let mut wrong_orientation = false;
+    // 两条引物都可能结合在正链上，依次把每条引物作为产物左端的引物
+    for (left, right) in [(forward, reverse), (reverse, forward)] {
+        let right_site = right
+            .reverse_complementary()
+            .map_err(|_| PcrError::NotDna(right.biotype.clone()))?;
+        let (l_len, r_len) = (left.len(), right.len());
+        let left_sites = binding_sites(&bases, &left.seq, max_mismatches, circular);
+        let right_sites = binding_sites(&bases, &right_site.seq, max_mismatches, circular);
+        for &l in &left_sites {
+            for &r in &right_sites {
+                // 产物为 l 到 r + r_len，环状模板上 r 在 l 之前时跨过起点
+                let end = if r >= l {
+                    r + r_len
+                } else if circular {
+                    r + n + r_len
+                } else {
+                    wrong_orientation = true;
+                    continue;
+                };
+                let size = end - l;
+                if size < l_len.max(r_len) || (circular && size > n + r_len.min(l_len)) {
+                    continue;
+                }
+                // 两条引物互为反向互补时，两种方向会得到同一个产物
+                if size <= max_product_size
+                    && !products.iter().any(|&(s, e, ..)| s == l && e == end)
+                {
+                    products.push((l, end, left, right_site.clone()));
+                }
+            }
+        }
+    }
+
+    match products.len() {
+        0 if wrong_orientation => Err(PcrError::WrongOrientation),
+        0 => Err(PcrError::NoProduct),
+        1 => {
+            let (start, end, left, right_site) = products.remove(0);
+            let (l_len, r_len) = (left.len(), right_site.len());
+            let product: String = (start..end).map(|i| bases[i % n]).collect();
+            let size = end - start;
+            // 两端替换为引物序列，模拟错配引物被掺入产物
+            let seq = if size >= l_len + r_len {
+                let middle: String = product
+                    .chars()
+                    .skip(l_len)
+                    .take(size - l_len - r_len)
+                    .collect();
+                format!(
+                    "{}{}{}",
+                    left.seq.to_ascii_uppercase(),
+                    middle,
+                    right_site.seq
+                )
+            } else {
+                product
+            };
+            Ok(Sequence::new(BioType::Dna, seq))
+        }
+        count => Err(PcrError::MultipleProducts(count)),
+    }
+}
+
+/// 引物 (已转为大写) 在模板正链上的结合位点，错配不超过 `max_mismatches`
+fn binding_sites(
+    bases: &[char],
+    primer: &str,
+    max_mismatches: usize,
+    circular: bool,
+) -> Vec<usize> {
+    let primer: Vec<char> = primer.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let n = bases.len();
+    if primer.len() > n {
+        return Vec::new();
+    }
+    let last_start = if circular { n - 1 } else { n - primer.len() };
+    (0..=last_start)
+        .filter(|&start| {
+            primer
+                .iter()
+                .enumerate()
+                .filter(|&(i, &p)| bases[(start + i) % n] != p)
+                .count()
+                <= max_mismatches
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "GGATCCATGACCGAGTACAAGCCCACGGTGCGCCTCGCCACCCGCGACGACGTCCCCAGGGCCGTACGCACCCTCGCCGCCGCGTTCGCC";
+
+    fn dna(seq: &str) -> Sequence {
+        Sequence::new(BioType::Dna, String::from(seq))
+    }
+
+    #[test]
+    fn linear_pcr() {
+        let template = dna(TEMPLATE);
+        let forward = dna("ATGACCGAGTACAAGC");
+        // 结合在 CGTACGCACCCTCGCC 处
+        let reverse = dna("GGCGAGGGTGCGTACG");
+        let product = simulate_pcr(&template, &forward, &reverse, 200).unwrap();
+        let start = TEMPLATE.find("ATGACCGAG").unwrap();
+        let end = TEMPLATE.find("CGTACGCACCCTCGCC").unwrap() + 16;
+        assert_eq!(product.seq, &TEMPLATE[start..end]);
+        // 交换引物的顺序得到同一个产物
+        assert_eq!(
+            simulate_pcr(&template, &reverse, &forward, 200).unwrap(),
+            product
+        );
+        assert_eq!(
+            simulate_pcr(&template, &forward, &reverse, 30),
+            Err(PcrError::NoProduct)
+        );
+
+        // 引物有一个错配
+        let mismatched = dna("ATGACCGTGTACAAGC");
+        assert_eq!(
+            simulate_pcr(&template, &mismatched, &reverse, 200),
+            Err(PcrError::NoProduct)
+        );
+        let product =
+            simulate_pcr_with_mismatches(&template, &mismatched, &reverse, 200, 1).unwrap();
+        assert!(product.seq.starts_with("ATGACCGTGTACAAGC"));
+        assert_eq!(product.len(), end - start);
+    }
+
+    #[test]
+    fn pcr_errors_and_circular_template() {
+        let template = dna(TEMPLATE);
+        let forward = dna("ATGACCGAGTACAAGC");
+        let reverse = dna("GGCGAGGGTGCGTACG");
+        // 交换两条引物的角色后方向相反
+        let swapped_forward = reverse.reverse_complementary().unwrap();
+        let swapped_reverse = forward.reverse_complementary().unwrap();
+        assert_eq!(
+            simulate_pcr(&template, &swapped_forward, &swapped_reverse, 200),
+            Err(PcrError::WrongOrientation)
+        );
+        let repeated = dna(&format!("{}{}", TEMPLATE, TEMPLATE));
+        assert!(matches!(
+            simulate_pcr(&repeated, &forward, &reverse, 500),
+            Err(PcrError::MultipleProducts(_))
+        ));
+
+        // 环状模板上方向相反的引物会跨过起点扩增
+        let circular = Sequence::new_circular(BioType::Dna, String::from(TEMPLATE));
+        let product = simulate_pcr(&circular, &swapped_forward, &swapped_reverse, 200).unwrap();
+        assert!(product.seq.starts_with(&swapped_forward.seq));
+        assert!(product.seq.ends_with(&forward.seq));
+        assert!(product.seq.contains("GGATCC"));
+        assert_eq!(
+            simulate_pcr(&template, &dna(""), &reverse, 200),
+            Err(PcrError::EmptyPrimer)
+        );
+    }
+}
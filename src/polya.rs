@@ -0,0 +1,108 @@
+use super::sequence::{BioType, Sequence};
+
+// poly(A) 尾与 poly(T) 的识别和去除，允许测序错误造成的少量错配
+
+/// 从 `chars` 的一端向内扫描，返回满足条件的最长同聚物区域的长度
+/// 区域必须以 `is_base` 的碱基开始 (靠内一侧)，且非目标碱基不超过长度的 10%
+/// 扫描在错配超过 10% 再加一个碱基的容错后停止
+fn homopolymer_run(
+    chars: impl Iterator<Item = char>,
+    is_base: impl Fn(char) -> bool,
+    min_length: usize,
+) -> usize {
+    let mut mismatches = 0;
+    let mut best = 0;
+    for (i, c) in chars.enumerate() {
+        let len = i + 1;
+        let matched = is_base(c.to_ascii_uppercase());
+        if !matched {
+            mismatches += 1;
+        }
+        if mismatches * 10 > len + 10 {
+            break;
+        }
+        if matched && mismatches * 10 <= len && len >= min_length.max(1) {
+            best = len;
+        }
+    }
+    best
+}
+
+impl Sequence {
+    /// 末尾 poly(A) 尾的起点，尾长至少为 `min_length`，允许不超过 10% 的非 A 碱基
+    /// 没有符合条件的尾或序列为蛋白质时返回 None
+    pub fn find_poly_a(&self, min_length: usize) -> Option<usize> {
+        if self.biotype == BioType::Protein {
+            return None;
+        }
+        let chars: Vec<char> = self.seq.chars().collect();
+        match homopolymer_run(chars.iter().rev().copied(), |c| c == 'A', min_length) {
+            0 => None,
+            len => Some(chars.len() - len),
+        }
+    }
+
+    /// 去除末尾 poly(A) 尾后的序列，没有尾时返回原序列的拷贝
+    pub fn trim_poly_a(&self, min_length: usize) -> Sequence {
+        match self.find_poly_a(min_length) {
+            Some(start) => self.subsequence(..start),
+            None => self.clone(),
+        }
+    }
+
+    /// 开头 poly(T) (RNA 中为 poly(U)) 的终点，即 oligo(dT) 引物一端，规则同 `find_poly_a`
+    /// 没有符合条件的区域或序列为蛋白质时返回 None
+    pub fn find_poly_t(&self, min_length: usize) -> Option<usize> {
+        if self.biotype == BioType::Protein {
+            return None;
+        }
+        match homopolymer_run(self.seq.chars(), |c| c == 'T' || c == 'U', min_length) {
+            0 => None,
+            len => Some(len),
+        }
+    }
+
+    /// 去除开头 poly(T) 后的序列，没有时返回原序列的拷贝
+    pub fn trim_poly_t(&self, min_length: usize) -> Sequence {
+        match self.find_poly_t(min_length) {
+            Some(end) => self.subsequence(end..),
+            None => self.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poly_a_tail() {
+        let rna = Sequence::new(BioType::Rna, format!("AUGGCC{}", "A".repeat(20)));
+        assert_eq!(rna.find_poly_a(10), Some(6));
+        assert_eq!(rna.trim_poly_a(10).seq, "AUGGCC");
+        // 一个错配的碱基仍在 10% 以内
+        let noisy = Sequence::new(
+            BioType::Dna,
+            format!("CGCG{}G{}", "a".repeat(12), "A".repeat(7)),
+        );
+        assert_eq!(noisy.find_poly_a(15), Some(4));
+        assert_eq!(rna.find_poly_a(30), None);
+        let no_tail = Sequence::new(BioType::Dna, String::from("ACGTACGT"));
+        assert_eq!(no_tail.find_poly_a(3), None);
+        assert_eq!(no_tail.trim_poly_a(3), no_tail);
+        assert_eq!(
+            Sequence::new(BioType::Protein, "A".repeat(20)).find_poly_a(5),
+            None
+        );
+    }
+
+    #[test]
+    fn poly_t_head() {
+        let dna = Sequence::new(BioType::Dna, format!("{}GCATG", "T".repeat(15)));
+        assert_eq!(dna.find_poly_t(10), Some(15));
+        assert_eq!(dna.trim_poly_t(10).seq, "GCATG");
+        let rna = Sequence::new(BioType::Rna, format!("{}GC", "U".repeat(12)));
+        assert_eq!(rna.find_poly_t(12), Some(12));
+        assert_eq!(rna.find_poly_t(13), None);
+    }
+}
@@ -0,0 +1,454 @@
+use super::sequence::{BioType, Sequence};
+
+use std::ops::RangeInclusive;
+use std::{error, fmt};
+
+// 引物相关的计算
+// 熔解温度 (Tm) 的估算：
+// 不超过 13 bp 的寡核苷酸使用 Wallace 规则 Tm = 2*(A+T) + 4*(G+C)
+// 更长的序列使用 SantaLucia (1998) 的最近邻热力学参数
+
+/// 默认的 Na+ 浓度 (mM)
+pub const DEFAULT_NA_MM: f64 = 50.0;
+
+/// 最近邻模型中使用的寡核苷酸总浓度 (M)
+const OLIGO_CONCENTRATION: f64 = 250e-9;
+
+/// 气体常数 cal/(K·mol)
+const GAS_CONSTANT: f64 = 1.987;
+
+/// 使用 Wallace 规则的最大长度
+const WALLACE_MAX_LENGTH: usize = 13;
+
+/// SantaLucia (1998) 统一最近邻参数，(二核苷酸, ΔH kcal/mol, ΔS cal/(K·mol))
+const NEAREST_NEIGHBOR: [(&str, f64, f64); 16] = [
+    ("AA", -7.9, -22.2),
+    ("TT", -7.9, -22.2),
+    ("AT", -7.2, -20.4),
+    ("TA", -7.2, -21.3),
+    ("CA", -8.5, -22.7),
+    ("TG", -8.5, -22.7),
+    ("GT", -8.4, -22.4),
+    ("AC", -8.4, -22.4),
+    ("CT", -7.8, -21.0),
+    ("AG", -7.8, -21.0),
+    ("GA", -8.2, -22.2),
+    ("TC", -8.2, -22.2),
+    ("CG", -10.6, -27.2),
+    ("GC", -9.8, -24.4),
+    ("GG", -8.0, -19.9),
+    ("CC", -8.0, -19.9),
+];
+
+/// 末端为 G/C 时的起始参数
+const INIT_GC: (f64, f64) = (0.1, -2.8);
+
+/// 末端为 A/T 时的起始参数
+const INIT_AT: (f64, f64) = (2.3, 4.1);
+
+/// 自互补序列的对称校正 ΔS
+const SYMMETRY_CORRECTION: f64 = -1.4;
+
+impl Sequence {
+    /// 估算 DNA 序列的熔解温度 (°C)，Na+ 浓度为 50 mM
+    /// 不超过 13 bp 时使用 Wallace 规则，否则使用最近邻模型
+    pub fn melting_temperature(&self) -> Result<f64, String> {
+        self.melting_temperature_with_salt(DEFAULT_NA_MM)
+    }
+
+    /// 在指定 Na+ 浓度 (mM) 下估算 DNA 序列的熔解温度 (°C)
+    /// 盐浓度校正只作用于最近邻模型: ΔS = ΔS(1 M) + 0.368 * (N - 1) * ln[Na+]
+    pub fn melting_temperature_with_salt(&self, na_mm: f64) -> Result<f64, String> {
+        self.melting_temperature_with(na_mm, OLIGO_CONCENTRATION)
+    }
+
+    /// 在指定 Na+ 浓度 (mM) 和寡核苷酸总浓度 (M) 下估算熔解温度 (°C)
+    /// 非自互补序列的有效浓度为 C_T / 4，自互补序列为 C_T
+    fn melting_temperature_with(
+        &self,
+        na_mm: f64,
+        oligo_concentration: f64,
+    ) -> Result<f64, String> {
+        if self.biotype != BioType::Dna {
+            return Err(format!("只能计算 {} 序列的熔解温度", BioType::Dna));
+        }
+        if na_mm <= 0.0 {
+            return Err(format!("Na+ 浓度必须大于 0，而不是 {}", na_mm));
+        }
+        let seq = self.seq.to_ascii_uppercase();
+        if seq.is_empty() {
+            return Err(String::from("不能计算空序列的熔解温度"));
+        }
+        if let Some(base) = seq.chars().find(|c| !matches!(c, 'A' | 'C' | 'G' | 'T')) {
+            return Err(format!("熔解温度计算不支持碱基 {}", base));
+        }
+
+        if seq.len() <= WALLACE_MAX_LENGTH {
+            let gc = seq.chars().filter(|c| matches!(c, 'G' | 'C')).count() as f64;
+            let at = seq.len() as f64 - gc;
+            return Ok(2.0 * at + 4.0 * gc);
+        }
+
+        let bases = seq.as_bytes();
+        let (mut enthalpy, mut entropy) = (0.0, 0.0);
+        for &end in [bases[0], bases[bases.len() - 1]].iter() {
+            let (h, s) = if matches!(end, b'G' | b'C') {
+                INIT_GC
+            } else {
+                INIT_AT
+            };
+            enthalpy += h;
+            entropy += s;
+        }
+        for pair in bases.windows(2) {
+            let (_, h, s) = NEAREST_NEIGHBOR
+                .iter()
+                .find(|(dinucleotide, ..)| dinucleotide.as_bytes() == pair)
+                .unwrap();
+            enthalpy += h;
+            entropy += s;
+        }
+
+        let self_complementary = self.reverse_complementary()?.seq == seq;
+        let concentration = if self_complementary {
+            entropy += SYMMETRY_CORRECTION;
+            oligo_concentration
+        } else {
+            oligo_concentration / 4.0
+        };
+        entropy += 0.368 * (bases.len() - 1) as f64 * (na_mm / 1000.0).ln();
+
+        Ok(enthalpy * 1000.0 / (entropy + GAS_CONSTANT * concentration.ln()) - 273.15)
+    }
+}
+
+/// 引物设计的约束条件，GC 含量以 0 ~ 1 的比例表示
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrimerConstraints {
+    pub min_tm: f64,
+    pub max_tm: f64,
+    pub min_gc: f64,
+    pub max_gc: f64,
+    pub length: RangeInclusive<usize>,
+    /// 含有 4 个及以上相同碱基连续出现时加罚分
+    pub penalize_runs: bool,
+    /// 3' 端不是 G/C，或最后 5 个碱基中 G/C 超过 3 个时加罚分
+    pub penalize_gc_clamp: bool,
+}
+
+impl Default for PrimerConstraints {
+    /// 常用的默认值: Tm 55 ~ 65 °C，GC 40% ~ 60%，长度 18 ~ 25，启用全部罚分
+    fn default() -> Self {
+        PrimerConstraints {
+            min_tm: 55.0,
+            max_tm: 65.0,
+            min_gc: 0.4,
+            max_gc: 0.6,
+            length: 18..=25,
+            penalize_runs: true,
+            penalize_gc_clamp: true,
+        }
+    }
+}
+
+/// 一对引物，两条引物都按 5' -> 3' 方向给出
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrimerPair {
+    pub forward: Sequence,
+    /// 目标区域下游的反向互补序列
+    pub reverse: Sequence,
+    pub forward_tm: f64,
+    pub reverse_tm: f64,
+    /// 包括两条引物在内的扩增产物长度
+    pub product_size: usize,
+}
+
+/// 引物设计失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrimerError {
+    /// 模板不是 DNA
+    NotDna(BioType),
+    /// 目标区域不合法或超出模板
+    InvalidRegion {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    /// 没有满足约束的正向引物
+    NoForwardPrimer,
+    /// 没有满足约束的反向引物
+    NoReversePrimer,
+}
+
+impl fmt::Display for PrimerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimerError::NotDna(biotype) => write!(
+                f,
+                "只能为 {} 模板设计引物，而不是 {}",
+                BioType::Dna,
+                biotype
+            ),
+            PrimerError::InvalidRegion { start, end, len } => {
+                write!(f, "目标区域 {}..{} 不在长度为 {} 的模板内", start, end, len)
+            }
+            PrimerError::NoForwardPrimer => write!(f, "没有满足约束的正向引物"),
+            PrimerError::NoReversePrimer => write!(f, "没有满足约束的反向引物"),
+        }
+    }
+}
+
+impl error::Error for PrimerError {}
+
+/// 罚分以 °C 为单位，与两条引物的 Tm 差相加
+const PRIMER_PENALTY: f64 = 1.0;
+
+/// 满足约束的候选引物
+struct Candidate {
+    primer: Sequence,
+    tm: f64,
+    penalty: f64,
+}
+
+/// 检查候选引物是否满足约束，并计算 Tm 与罚分
+fn candidate(primer: Sequence, constraints: &PrimerConstraints) -> Option<Candidate> {
+    let tm = primer.melting_temperature().ok()?;
+    let gc = primer.gc_content().ok()?;
+    if tm < constraints.min_tm || tm > constraints.max_tm {
+        return None;
+    }
+    if gc < constraints.min_gc || gc > constraints.max_gc {
+        return None;
+    }
+    let bases = primer.seq.as_bytes();
+    let mut penalty = 0.0;
+    if constraints.penalize_runs && bases.windows(4).any(|w| w.iter().all(|&b| b == w[0])) {
+        penalty += PRIMER_PENALTY;
+    }
+    if constraints.penalize_gc_clamp {
+        let is_gc = |b: &u8| matches!(b, b'G' | b'C');
+        let tail_gc = bases[bases.len().saturating_sub(5)..]
+            .iter()
+            .filter(|b| is_gc(b))
+            .count();
+        if !bases.last().is_some_and(is_gc) || tail_gc > 3 {
+            penalty += PRIMER_PENALTY;
+        }
+    }
+    Some(Candidate {
+        primer,
+        tm,
+        penalty,
+    })
+}
+
+impl Sequence {
+    /// 为 DNA 模板的目标区域 `region_start..region_end` 设计一对引物
+    /// 约束见 `PrimerConstraints`，罚分默认启用
+    #[allow(clippy::too_many_arguments)]
+    pub fn design_primers(
+        &self,
+        region_start: usize,
+        region_end: usize,
+        min_tm: f64,
+        max_tm: f64,
+        min_gc: f64,
+        max_gc: f64,
+        primer_len_range: RangeInclusive<usize>,
+    ) -> Result<PrimerPair, PrimerError> {
+        let constraints = PrimerConstraints {
+            min_tm,
+            max_tm,
+            min_gc,
+            max_gc,
+            length: primer_len_range,
+            ..Default::default()
+        };
+        self.design_primers_with(region_start, region_end, &constraints)
+    }
+
+    /// 按 `constraints` 设计引物：正向引物的 3' 端紧接 `region_start`，
+    /// 反向引物为从 `region_end` 开始的一段模板的反向互补序列
+    /// 在所有满足 Tm 与 GC 约束的组合中选出 |Tm_f - Tm_r| 加罚分最小的一对
+    pub fn design_primers_with(
+        &self,
+        region_start: usize,
+        region_end: usize,
+        constraints: &PrimerConstraints,
+    ) -> Result<PrimerPair, PrimerError> {
+        if self.biotype != BioType::Dna {
+            return Err(PrimerError::NotDna(self.biotype.clone()));
+        }
+        let template = self.seq.to_ascii_uppercase();
+        let len = template.len();
+        if region_start > region_end || region_end > len || !template.is_ascii() {
+            return Err(PrimerError::InvalidRegion {
+                start: region_start,
+                end: region_end,
+                len,
+            });
+        }
+
+        let forwards: Vec<Candidate> = constraints
+            .length
+            .clone()
+            .filter(|&l| l > 0 && l <= region_start)
+            .filter_map(|l| {
+                let primer = Sequence::new(
+                    BioType::Dna,
+                    template[region_start - l..region_start].to_string(),
+                );
+                candidate(primer, constraints)
+            })
+            .collect();
+        if forwards.is_empty() {
+            return Err(PrimerError::NoForwardPrimer);
+        }
+        let reverses: Vec<Candidate> = constraints
+            .length
+            .clone()
+            .filter(|&l| l > 0 && region_end + l <= len)
+            .filter_map(|l| {
+                let site = Sequence::new(
+                    BioType::Dna,
+                    template[region_end..region_end + l].to_string(),
+                );
+                candidate(site.reverse_complementary().ok()?, constraints)
+            })
+            .collect();
+        if reverses.is_empty() {
+            return Err(PrimerError::NoReversePrimer);
+        }
+
+        let mut best: Option<(f64, &Candidate, &Candidate)> = None;
+        for forward in &forwards {
+            for reverse in &reverses {
+                let score = (forward.tm - reverse.tm).abs() + forward.penalty + reverse.penalty;
+                if best.is_none_or(|(best_score, ..)| score < best_score) {
+                    best = Some((score, forward, reverse));
+                }
+            }
+        }
+        let (_, forward, reverse) = best.unwrap();
+        Ok(PrimerPair {
+            product_size: forward.primer.len() + (region_end - region_start) + reverse.primer.len(),
+            forward: forward.primer.clone(),
+            reverse: reverse.primer.clone(),
+            forward_tm: forward.tm,
+            reverse_tm: reverse.tm,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wallace_rule() {
+        let primer = Sequence::new(BioType::Dna, String::from("ATGCATGCAA"));
+        assert_eq!(primer.melting_temperature().unwrap(), 28.0);
+        assert!(Sequence::new(BioType::Rna, String::from("AUGC"))
+            .melting_temperature()
+            .is_err());
+        assert!(Sequence::new(BioType::Dna, String::from("ATGN"))
+            .melting_temperature()
+            .is_err());
+    }
+
+    #[test]
+    fn nearest_neighbor() {
+        // 参考值来自 Biopython Bio.SeqUtils.MeltingTemp.Tm_NN 文档中的示例:
+        // 默认 DNA_NN3 参数 (与 SantaLucia 1998 统一参数一致)，Na+ 50 mM，
+        // dnac1 = dnac2 = 25 nM (即 C_T = 50 nM)，saltcorr = 5，结果为 60.32 °C
+        let primer = Sequence::new(BioType::Dna, String::from("CGTTCCAAAGATGTGGGCATGAGCTTAC"));
+        let tm = primer.melting_temperature_with(50.0, 50e-9).unwrap();
+        assert!((tm - 60.32).abs() < 0.01);
+
+        let default = primer.melting_temperature().unwrap();
+        assert_eq!(
+            default,
+            primer
+                .melting_temperature_with(DEFAULT_NA_MM, OLIGO_CONCENTRATION)
+                .unwrap()
+        );
+        assert!(default > tm);
+        let high_salt = primer.melting_temperature_with_salt(1000.0).unwrap();
+        assert!(high_salt > default);
+        assert!(primer.melting_temperature_with_salt(0.0).is_err());
+    }
+
+    const TEMPLATE: &str = "GATCCTAGGCTGACCTGAAGCTTGCAGCATGAACGTTCCGGATAATTTTTTTTTTTTTTTTTTTTTTTTTTTTGGCTACGTCAGTCGATGCCATGCAGGTCTACGATCG";
+
+    #[test]
+    fn design_primer_pair() {
+        let template = Sequence::new(BioType::Dna, String::from(TEMPLATE));
+        let pair = template
+            .design_primers(40, 70, 50.0, 68.0, 0.4, 0.7, 18..=22)
+            .unwrap();
+        assert!(TEMPLATE[..40].ends_with(&pair.forward.seq));
+        let site = pair.reverse.reverse_complementary().unwrap();
+        assert!(TEMPLATE[70..].starts_with(&site.seq));
+        assert!((50.0..=68.0).contains(&pair.forward_tm));
+        assert!((50.0..=68.0).contains(&pair.reverse_tm));
+        assert_eq!(
+            pair.product_size,
+            pair.forward.len() + 30 + pair.reverse.len()
+        );
+        // 选出的是所有候选组合中得分最小的一对
+        let constraints = PrimerConstraints {
+            min_tm: 50.0,
+            max_tm: 68.0,
+            min_gc: 0.4,
+            max_gc: 0.7,
+            length: 18..=22,
+            penalize_runs: false,
+            penalize_gc_clamp: false,
+        };
+        let unpenalized = template.design_primers_with(40, 70, &constraints).unwrap();
+        for f in 18..=22 {
+            for r in 18..=22 {
+                let forward = Sequence::new(BioType::Dna, TEMPLATE[40 - f..40].to_string());
+                let reverse = Sequence::new(BioType::Dna, TEMPLATE[70..70 + r].to_string());
+                let (Ok(tf), Ok(tr)) =
+                    (forward.melting_temperature(), reverse.melting_temperature())
+                else {
+                    continue;
+                };
+                let gc_ok = |s: &Sequence| (0.4..=0.7).contains(&s.gc_content().unwrap());
+                if (50.0..=68.0).contains(&tf)
+                    && (50.0..=68.0).contains(&tr)
+                    && gc_ok(&forward)
+                    && gc_ok(&reverse)
+                {
+                    assert!(
+                        (unpenalized.forward_tm - unpenalized.reverse_tm).abs()
+                            <= (tf - tr).abs() + 1e-9
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn design_primer_errors() {
+        let template = Sequence::new(BioType::Dna, String::from(TEMPLATE));
+        assert!(matches!(
+            template.design_primers(40, 200, 50.0, 68.0, 0.4, 0.7, 18..=22),
+            Err(PrimerError::InvalidRegion { .. })
+        ));
+        assert_eq!(
+            template.design_primers(10, 70, 50.0, 68.0, 0.4, 0.7, 18..=22),
+            Err(PrimerError::NoForwardPrimer)
+        );
+        assert_eq!(
+            template.design_primers(40, 70, 90.0, 95.0, 0.4, 0.7, 18..=22),
+            Err(PrimerError::NoForwardPrimer)
+        );
+        let rna = Sequence::new(BioType::Rna, String::from("AUGC"));
+        assert_eq!(
+            rna.design_primers(0, 1, 50.0, 68.0, 0.4, 0.7, 18..=22),
+            Err(PrimerError::NotDna(BioType::Rna))
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use super::aminoacid::properties;
+use super::sequence::{BioType, Sequence};
+
+// 蛋白质序列的理化性质
+
+/// 游离氨基酸的平均分子量 (Da)，与 ExPASy ProtParam 使用的数值一致
+pub(crate) const AMINO_ACID_WEIGHTS: [(char, f64); 20] = [
+    ('A', 89.0932),
+    ('R', 174.201),
+    ('N', 132.1179),
+    ('D', 133.1027),
+    ('C', 121.1582),
+    ('E', 147.1293),
+    ('Q', 146.1445),
+    ('G', 75.0666),
+    ('H', 155.1546),
+    ('I', 131.1729),
+    ('L', 131.1729),
+    ('K', 146.1876),
+    ('M', 149.2113),
+    ('F', 165.1891),
+    ('P', 115.1305),
+    ('S', 105.0926),
+    ('T', 119.1192),
+    ('W', 204.2252),
+    ('Y', 181.1885),
+    ('V', 117.1463),
+];
+
+/// 水的平均分子量 (Da)
+const WATER_WEIGHT: f64 = 18.01528;
+
+/// 计算不稳定指数使用的二肽不稳定权重 (DIWV, Guruprasad 1990)
+/// 每一行为二肽的第一个残基，列按 `DIWV_ORDER` 的顺序排列为第二个残基
+const DIWV: [(char, [f64; 20]); 20] = [
+    (
+        'A',
+        [
+            1.0, 44.94, -7.49, 1.0, 1.0, 1.0, -7.49, 1.0, 1.0, 1.0, 1.0, 1.0, 20.26, 1.0, 1.0, 1.0,
+            1.0, 1.0, 1.0, 1.0,
+        ],
+    ),
+    (
+        'C',
+        [
+            1.0, 1.0, 20.26, 1.0, 1.0, 1.0, 33.6, 1.0, 1.0, 20.26, 33.6, 1.0, 20.26, -6.54, 1.0,
+            1.0, 33.6, -6.54, 24.68, 1.0,
+        ],
+    ),
+    (
+        'D',
+        [
+            1.0, 1.0, 1.0, 1.0, -6.54, 1.0, 1.0, 1.0, -7.49, 1.0, 1.0, 1.0, 1.0, 1.0, -6.54, 20.26,
+            -14.03, 1.0, 1.0, 1.0,
+        ],
+    ),
+    (
+        'E',
+        [
+            1.0, 44.94, 20.26, 33.6, 1.0, 1.0, -6.54, 20.26, 1.0, 1.0, 1.0, 1.0, 20.26, 20.26, 1.0,
+            20.26, 1.0, 1.0, -14.03, 1.0,
+        ],
+    ),
+    (
+        'F',
+        [
+            1.0, 1.0, 13.34, 1.0, 1.0, 1.0, 1.0, 1.0, -14.03, 1.0, 1.0, 1.0, 20.26, 1.0, 1.0, 1.0,
+            1.0, 1.0, 1.0, 33.601,
+        ],
+    ),
+    (
+        'G',
+        [
+            -7.49, 1.0, 1.0, -6.54, 1.0, 13.34, 1.0, -7.49, -7.49, 1.0, 1.0, -7.49, 1.0, 1.0, 1.0,
+            1.0, -7.49, 1.0, 13.34, -7.49,
+        ],
+    ),
+    (
+        'H',
+        [
+            1.0, 1.0, 1.0, 1.0, -9.37, -9.37, 1.0, 44.94, 24.68, 1.0, 1.0, 24.68, -1.88, 1.0, 1.0,
+            1.0, -6.54, 1.0, -1.88, 44.94,
+        ],
+    ),
+    (
+        'I',
+        [
+            1.0, 1.0, 1.0, 44.94, 1.0, 1.0, 13.34, 1.0, -7.49, 20.26, 1.0, 1.0, -1.88, 1.0, 1.0,
+            1.0, 1.0, -7.49, 1.0, 1.0,
+        ],
+    ),
+    (
+        'K',
+        [
+            1.0, 1.0, 1.0, 1.0, 1.0, -7.49, 1.0, -7.49, 1.0, -7.49, 33.6, 1.0, -6.54, 24.64, 33.6,
+            1.0, 1.0, -7.49, 1.0, 1.0,
+        ],
+    ),
+    (
+        'L',
+        [
+            1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, -7.49, 1.0, 1.0, 1.0, 20.26, 33.6, 20.26, 1.0,
+            1.0, 1.0, 24.68, 1.0,
+        ],
+    ),
+    (
+        'M',
+        [
+            13.34, 1.0, 1.0, 1.0, 1.0, 1.0, 58.28, 1.0, 1.0, 1.0, -1.88, 1.0, 44.94, -6.54, -6.54,
+            44.94, -1.88, 1.0, 1.0, 24.68,
+        ],
+    ),
+    (
+        'N',
+        [
+            1.0, -1.88, 1.0, 1.0, -14.03, -14.03, 1.0, 44.94, 24.68, 1.0, 1.0, 1.0, -1.88, -6.54,
+            1.0, 1.0, -7.49, 1.0, -9.37, 1.0,
+        ],
+    ),
+    (
+        'P',
+        [
+            20.26, -6.54, -6.54, 18.38, 20.26, 1.0, 1.0, 1.0, 1.0, 1.0, -6.54, 1.0, 20.26, 20.26,
+            -6.54, 20.26, 1.0, 20.26, -1.88, 1.0,
+        ],
+    ),
+    (
+        'Q',
+        [
+            1.0, -6.54, 20.26, 20.26, -6.54, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 20.26, 20.26, 1.0,
+            44.94, 1.0, -6.54, 1.0, -6.54,
+        ],
+    ),
+    (
+        'R',
+        [
+            1.0, 1.0, 1.0, 1.0, 1.0, -7.49, 20.26, 1.0, 1.0, 1.0, 1.0, 13.34, 20.26, 20.26, 58.28,
+            44.94, 1.0, 1.0, 58.28, -6.54,
+        ],
+    ),
+    (
+        'S',
+        [
+            1.0, 33.6, 1.0, 20.26, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 44.94, 20.26, 20.26,
+            20.26, 1.0, 1.0, 1.0, 1.0,
+        ],
+    ),
+    (
+        'T',
+        [
+            1.0, 1.0, 1.0, 20.26, 13.34, -7.49, 1.0, 1.0, 1.0, 1.0, 1.0, -14.03, 1.0, -6.54, 1.0,
+            1.0, 1.0, 1.0, -14.03, 1.0,
+        ],
+    ),
+    (
+        'V',
+        [
+            1.0, 1.0, -14.03, 1.0, 1.0, -7.49, 1.0, 1.0, -1.88, 1.0, 1.0, 1.0, 20.26, 1.0, 1.0,
+            1.0, -7.49, 1.0, 1.0, -6.54,
+        ],
+    ),
+    (
+        'W',
+        [
+            -14.03, 1.0, 1.0, 1.0, 1.0, -9.37, 24.68, 1.0, 1.0, 13.34, 24.68, 13.34, 1.0, 1.0, 1.0,
+            1.0, -14.03, -7.49, 1.0, 1.0,
+        ],
+    ),
+    (
+        'Y',
+        [
+            24.68, 1.0, 24.68, -6.54, 1.0, -7.49, 13.34, 1.0, 1.0, 1.0, 44.94, 1.0, 13.34, 1.0,
+            -15.91, 1.0, -7.49, 1.0, -9.37, 13.34,
+        ],
+    ),
+];
+
+/// DIWV 表中列的顺序
+const DIWV_ORDER: &str = "ACDEFGHIKLMNPQRSTVWY";
+
+/// 可电离基团的 pKa，用于计算等电点
+#[derive(Debug, Clone, PartialEq)]
+pub struct PkaTable {
+    pub n_term: f64,
+    pub c_term: f64,
+    /// 天冬氨酸
+    pub d: f64,
+    /// 谷氨酸
+    pub e: f64,
+    /// 半胱氨酸
+    pub c: f64,
+    /// 酪氨酸
+    pub y: f64,
+    /// 组氨酸
+    pub h: f64,
+    /// 赖氨酸
+    pub k: f64,
+    /// 精氨酸
+    pub r: f64,
+}
+
+impl PkaTable {
+    /// Lehninger 生物化学教材中的 pKa
+    pub fn lehninger() -> Self {
+        PkaTable {
+            n_term: 9.69,
+            c_term: 2.34,
+            d: 3.86,
+            e: 4.25,
+            c: 8.33,
+            y: 10.07,
+            h: 6.0,
+            k: 10.5,
+            r: 12.4,
+        }
+    }
+
+    /// Bjellqvist 等人的 pKa (不含末端残基相关的修正)，与 Biopython 的默认值相同
+    pub fn bjellqvist() -> Self {
+        PkaTable {
+            n_term: 7.5,
+            c_term: 3.55,
+            d: 4.05,
+            e: 4.45,
+            c: 9.0,
+            y: 10.0,
+            h: 5.98,
+            k: 10.0,
+            r: 12.0,
+        }
+    }
+}
+
+impl Default for PkaTable {
+    fn default() -> Self {
+        Self::lehninger()
+    }
+}
+
+/// 二分法求等电点时的精度
+const PH_PRECISION: f64 = 1e-4;
+
+impl Sequence {
+    /// 使用 Lehninger pKa 计算蛋白质的等电点
+    pub fn isoelectric_point(&self) -> Result<f64, String> {
+        self.isoelectric_point_with_pka(&PkaTable::lehninger())
+    }
+
+    /// 使用指定的 pKa 计算等电点：用 `charge_at_ph_with_pka` 在 pH 0 ~ 14 之间二分查找净电荷为 0 的位置
+    /// 非蛋白质序列返回错误，遇到终止符 `*` 时停止
+    pub fn isoelectric_point_with_pka(&self, table: &PkaTable) -> Result<f64, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能计算 {} 序列的等电点", BioType::Protein));
+        }
+        let (mut low, mut high) = (0.0, 14.0);
+        while high - low > PH_PRECISION {
+            let middle = (low + high) / 2.0;
+            if self.charge_at_ph_with_pka(middle, table)? > 0.0 {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Ok((low + high) / 2.0)
+    }
+
+    /// 使用 Lehninger pKa 计算蛋白质在 `ph` 下的净电荷
+    pub fn charge_at_ph(&self, ph: f64) -> Result<f64, String> {
+        self.charge_at_ph_with_pka(ph, &PkaTable::lehninger())
+    }
+
+    /// 按 Henderson-Hasselbalch 方程计算指定 pH 下的净电荷，即 D、E、C、Y、H、K、R 侧链与两个末端的电荷之和
+    /// 半胱氨酸按游离巯基计算，不考虑二硫键；非蛋白质序列返回错误，遇到终止符 `*` 时停止
+    pub fn charge_at_ph_with_pka(&self, ph: f64, table: &PkaTable) -> Result<f64, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能计算 {} 序列的电荷", BioType::Protein));
+        }
+        let positive = |pka: f64| 1.0 / (1.0 + 10f64.powf(ph - pka));
+        let negative = |pka: f64| -1.0 / (1.0 + 10f64.powf(pka - ph));
+        let mut charge = positive(table.n_term) + negative(table.c_term);
+        for residue in self.seq.chars().take_while(|&c| c != '*') {
+            charge += side_chain_charge(residue, ph, table);
+        }
+        Ok(charge)
+    }
+
+    /// 计算蛋白质的平均分子量 (Da)
+    /// 各氨基酸分子量之和减去形成 n-1 个肽键时脱去的水，遇到终止符 `*` 时停止计算
+    /// 非蛋白质序列或含有非标准氨基酸时返回错误
+    pub fn molecular_weight(&self) -> Result<f64, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能计算 {} 序列的分子量", BioType::Protein));
+        }
+        let mut weight = 0.0;
+        let mut residues = 0;
+        for residue in self.seq.chars().take_while(|&c| c != '*') {
+            let residue = residue.to_ascii_uppercase();
+            match AMINO_ACID_WEIGHTS.iter().find(|(aa, _)| *aa == residue) {
+                Some((_, residue_weight)) => weight += residue_weight,
+                None => return Err(format!("未知的氨基酸: {}", residue)),
+            }
+            residues += 1;
+        }
+        if residues == 0 {
+            return Ok(0.0);
+        }
+        Ok(weight - (residues - 1) as f64 * WATER_WEIGHT)
+    }
+
+    /// 取出 `*` 之前的残基并转为大写，要求为非空的标准氨基酸序列
+    fn standard_residues(&self, property: &str) -> Result<Vec<char>, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能计算 {} 序列的{}", BioType::Protein, property));
+        }
+        let residues: Vec<char> = self
+            .seq
+            .chars()
+            .take_while(|&c| c != '*')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if residues.is_empty() {
+            return Err(format!("不能计算空序列的{}", property));
+        }
+        if let Some(residue) = residues.iter().find(|&&c| !DIWV_ORDER.contains(c)) {
+            return Err(format!("未知的氨基酸: {}", residue));
+        }
+        Ok(residues)
+    }
+
+    /// 总平均亲水性 (GRAVY)，即所有残基 Kyte-Doolittle 疏水性的平均值
+    pub fn gravy(&self) -> Result<f64, String> {
+        let residues = self.standard_residues("GRAVY")?;
+        let mut total = 0.0;
+        for &residue in residues.iter() {
+            total += properties(residue)?.hydrophobicity_kd;
+        }
+        Ok(total / residues.len() as f64)
+    }
+
+    /// 脂肪族指数 (Ikai 1980)：Ala% + 2.9 * Val% + 3.9 * (Ile% + Leu%)，百分比为摩尔百分比
+    pub fn aliphatic_index(&self) -> Result<f64, String> {
+        let residues = self.standard_residues("脂肪族指数")?;
+        let percent = |aa: char| {
+            residues.iter().filter(|&&c| c == aa).count() as f64 * 100.0 / residues.len() as f64
+        };
+        Ok(percent('A') + 2.9 * percent('V') + 3.9 * (percent('I') + percent('L')))
+    }
+
+    /// 不稳定指数 (Guruprasad 1990)：(10 / L) * 所有相邻二肽的 DIWV 之和
+    /// 小于 40 的蛋白质通常被认为是稳定的
+    pub fn instability_index(&self) -> Result<f64, String> {
+        let residues = self.standard_residues("不稳定指数")?;
+        let column = |aa: char| DIWV_ORDER.find(aa).unwrap();
+        let total: f64 = residues
+            .windows(2)
+            .map(|pair| {
+                let (_, row) = DIWV.iter().find(|(aa, _)| *aa == pair[0]).unwrap();
+                row[column(pair[1])]
+            })
+            .sum();
+        Ok(10.0 / residues.len() as f64 * total)
+    }
+}
+
+/// 按 Henderson-Hasselbalch 方程计算单个残基侧链在 `ph` 下的电荷，不可电离的残基为 0.0
+pub(crate) fn side_chain_charge(residue: char, ph: f64, table: &PkaTable) -> f64 {
+    let positive = |pka: f64| 1.0 / (1.0 + 10f64.powf(ph - pka));
+    let negative = |pka: f64| -1.0 / (1.0 + 10f64.powf(pka - ph));
+    match residue.to_ascii_uppercase() {
+        'K' => positive(table.k),
+        'R' => positive(table.r),
+        'H' => positive(table.h),
+        'D' => negative(table.d),
+        'E' => negative(table.e),
+        'C' => negative(table.c),
+        'Y' => negative(table.y),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insulin_b_chain_weight() {
+        let insulin_b = Sequence::new(
+            BioType::Protein,
+            String::from("FVNQHLCGSHLVEALYLVCGERGFFYTPKT"),
+        );
+        assert!((insulin_b.molecular_weight().unwrap() - 3429.92).abs() < 0.01);
+        let with_stop = Sequence::new(
+            BioType::Protein,
+            String::from("FVNQHLCGSHLVEALYLVCGERGFFYTPKT*MKV"),
+        );
+        assert_eq!(
+            with_stop.molecular_weight().unwrap(),
+            insulin_b.molecular_weight().unwrap()
+        );
+        let glycine = Sequence::new(BioType::Protein, String::from("G"));
+        assert_eq!(glycine.molecular_weight().unwrap(), 75.0666);
+        assert!(Sequence::new(BioType::Dna, String::from("ACGT"))
+            .molecular_weight()
+            .is_err());
+        assert!(Sequence::new(BioType::Protein, String::from("MXK"))
+            .molecular_weight()
+            .is_err());
+    }
+
+    /// 鸡蛋清溶菌酶成熟肽链
+    const LYSOZYME: &str = "KVFGRCELAAAMKRHGLDNYRGYSLGNWVCAAKFESNFNTQATNRNTDGSTDYGILQINSRWWCNDGRTPGSRNLCNIPCSALLSSDITASVNCAKKIVSDGNGMNAWVAWRNRCKGTDVQAWIRGCRL";
+
+    #[test]
+    fn isoelectric_point() {
+        let lysozyme = Sequence::new(BioType::Protein, String::from(LYSOZYME));
+        let insulin_b = Sequence::new(
+            BioType::Protein,
+            String::from("FVNQHLCGSHLVEALYLVCGERGFFYTPKT"),
+        );
+        let bjellqvist = PkaTable::bjellqvist();
+        assert!((lysozyme.isoelectric_point_with_pka(&bjellqvist).unwrap() - 9.32).abs() < 0.01);
+        assert!((insulin_b.isoelectric_point_with_pka(&bjellqvist).unwrap() - 6.90).abs() < 0.01);
+        assert!((lysozyme.isoelectric_point().unwrap() - 9.22).abs() < 0.01);
+        assert!(Sequence::new(BioType::Rna, String::from("ACGU"))
+            .isoelectric_point()
+            .is_err());
+    }
+
+    #[test]
+    fn charge_at_ph() {
+        let lysozyme = Sequence::new(BioType::Protein, String::from(LYSOZYME));
+        let neutral = lysozyme.charge_at_ph(7.0).unwrap();
+        let acidic = lysozyme.charge_at_ph(4.0).unwrap();
+        // 文献中溶菌酶在 pH 7 时的净电荷约为 +8，pH 4 时约为 +13；
+        // 按 Lehninger pKa 计算时 8 个半胱氨酸都按游离巯基计算，因此 pH 7 时略低
+        assert!((neutral - 8.0).abs() < 0.5);
+        assert!((neutral - 7.74).abs() < 0.01);
+        assert!((acidic - 13.23).abs() < 0.01);
+        assert!(
+            lysozyme
+                .charge_at_ph(lysozyme.isoelectric_point().unwrap())
+                .unwrap()
+                .abs()
+                < 0.01
+        );
+        let bjellqvist = PkaTable::bjellqvist();
+        assert!(lysozyme.charge_at_ph_with_pka(7.0, &bjellqvist).unwrap() > 0.0);
+        assert!(Sequence::new(BioType::Dna, String::from("ACGT"))
+            .charge_at_ph(7.0)
+            .is_err());
+    }
+
+    #[test]
+    fn protparam_indices() {
+        // ExPASy ProtParam 对溶菌酶给出 GRAVY -0.472，脂肪族指数 65.12
+        let lysozyme = Sequence::new(BioType::Protein, String::from(LYSOZYME));
+        assert!((lysozyme.gravy().unwrap() + 0.472).abs() < 0.001);
+        assert!((lysozyme.aliphatic_index().unwrap() - 65.12).abs() < 0.01);
+        assert!((lysozyme.instability_index().unwrap() - 16.09).abs() < 0.01);
+        let insulin_b = Sequence::new(
+            BioType::Protein,
+            String::from("FVNQHLCGSHLVEALYLVCGERGFFYTPKT*"),
+        );
+        assert!((insulin_b.gravy().unwrap() - 0.22).abs() < 0.001);
+        assert!((insulin_b.aliphatic_index().unwrap() - 84.33).abs() < 0.01);
+        let dna = Sequence::new(BioType::Dna, String::from("ACGT"));
+        assert!(dna.gravy().is_err());
+        assert!(dna.aliphatic_index().is_err());
+        assert!(dna.instability_index().is_err());
+        assert!(Sequence::new(BioType::Protein, String::from("MKBX"))
+            .instability_index()
+            .is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use super::codon::{CodonTable, CodonUsage};
+use super::sequence::{BioType, Sequence, AMINO_ACIDS};
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use std::collections::HashMap;
+
+// 随机序列，用于模拟与显著性检验，需要启用 `rand` 特性
+
+/// 按 GC 含量 `gc_content` (0 ~ 1，超出范围时截断) 生成长度为 `length` 的随机 DNA 序列
+/// G 与 C、A 与 T 各占一半，GC 含量为 0.5 时四种碱基等概率
+pub fn random_dna(length: usize, gc_content: f64, rng: &mut impl Rng) -> Sequence {
+    Sequence::new(
+        BioType::Dna,
+        random_nucleotides(length, gc_content, 'T', rng),
+    )
+}
+
+/// 与 `random_dna` 相同，但生成 RNA 序列
+pub fn random_rna(length: usize, gc_content: f64, rng: &mut impl Rng) -> Sequence {
+    Sequence::new(
+        BioType::Rna,
+        random_nucleotides(length, gc_content, 'U', rng),
+    )
+}
+
+fn random_nucleotides(length: usize, gc_content: f64, t: char, rng: &mut impl Rng) -> String {
+    let gc = gc_content.clamp(0.0, 1.0);
+    (0..length)
+        .map(|_| {
+            let strong = rng.gen_bool(gc);
+            match (strong, rng.gen_bool(0.5)) {
+                (true, true) => 'G',
+                (true, false) => 'C',
+                (false, true) => 'A',
+                (false, false) => t,
+            }
+        })
+        .collect()
+}
+
+/// 20 种标准氨基酸等概率的随机蛋白质序列
+pub fn random_protein(length: usize, rng: &mut impl Rng) -> Sequence {
+    let amino_acids: Vec<char> = AMINO_ACIDS.chars().collect();
+    let seq = (0..length)
+        .map(|_| *amino_acids.choose(rng).unwrap())
+        .collect();
+    Sequence::new(BioType::Protein, seq)
+}
+
+/// 按频率表 `frequencies` (氨基酸, 权重) 生成随机蛋白质序列，权重不需要归一化
+/// 频率表为空、权重为负或全为 0 时返回错误
+pub fn random_protein_weighted(
+    length: usize,
+    frequencies: &[(char, f64)],
+    rng: &mut impl Rng,
+) -> Result<Sequence, String> {
+    let distribution = WeightedIndex::new(frequencies.iter().map(|&(_, weight)| weight))
+        .map_err(|err| format!("无效的氨基酸频率表: {}", err))?;
+    let seq = (0..length)
+        .map(|_| frequencies[distribution.sample(rng)].0)
+        .collect();
+    Ok(Sequence::new(BioType::Protein, seq))
+}
+
+impl Sequence {
+    /// 与 `optimize_codons` 相同，但按目标物种中的使用次数随机选择同义密码子
+    /// 同义密码子都没有出现过时保留原密码子
+    pub fn optimize_codons_stochastic(
+        &self,
+        target_usage: &CodonUsage,
+        rng: &mut impl Rng,
+    ) -> Result<Sequence, String> {
+        self.replace_codons(target_usage, |candidates, _| {
+            let distribution =
+                WeightedIndex::new(candidates.iter().map(|&(_, count)| count)).ok()?;
+            Some(candidates[distribution.sample(rng)].0.clone())
+        })
+    }
+
+    /// 与 `back_translate` 相同，但使用调用者提供的 `rng` 按 `usage` 中的使用次数抽样同义密码子
+    pub fn back_translate_stochastic(
+        &self,
+        codon_table: &CodonTable,
+        usage: &CodonUsage,
+        rng: &mut impl Rng,
+    ) -> Result<Sequence, String> {
+        self.back_translate_with(codon_table, usage, |candidates, _| {
+            let distribution =
+                WeightedIndex::new(candidates.iter().map(|&(_, count)| count)).ok()?;
+            Some(candidates[distribution.sample(rng)].0.clone())
+        })
+    }
+
+    /// 保持二核苷酸 (相邻字符对) 频率不变的随机打乱 (Altschul & Erickson 1985)
+    /// 结果与原序列首尾字符相同，且每种相邻字符对出现的次数不变，区分大小写
+    /// 把序列看作字符之间的有向图，随机选取一条欧拉路径
+    pub fn shuffle(&self, rng: &mut impl Rng) -> Sequence {
+        let chars: Vec<char> = self.seq.chars().collect();
+        if chars.len() < 3 {
+            return self.clone();
+        }
+        let first = chars[0];
+        let last = chars[chars.len() - 1];
+
+        let mut edges: HashMap<char, Vec<char>> = HashMap::new();
+        for pair in chars.windows(2) {
+            edges.entry(pair[0]).or_default().push(pair[1]);
+        }
+
+        // 为除终点外的每个字符随机选一条 "最后离开" 的边，这些边必须构成指向终点的树
+        let mut last_edges: HashMap<char, usize> = HashMap::new();
+        loop {
+            last_edges.clear();
+            for (&vertex, targets) in &edges {
+                if vertex != last {
+                    last_edges.insert(vertex, rng.gen_range(0..targets.len()));
+                }
+            }
+            let reaches_last = |start: char| {
+                let mut vertex = start;
+                for _ in 0..=edges.len() {
+                    if vertex == last {
+                        return true;
+                    }
+                    vertex = edges[&vertex][last_edges[&vertex]];
+                }
+                false
+            };
+            if last_edges.keys().all(|&vertex| reaches_last(vertex)) {
+                break;
+            }
+        }
+
+        for (vertex, targets) in edges.iter_mut() {
+            match last_edges.get(vertex) {
+                Some(&index) => {
+                    let last_target = targets.swap_remove(index);
+                    targets.shuffle(rng);
+                    targets.push(last_target);
+                }
+                None => targets.shuffle(rng),
+            }
+        }
+
+        let mut next: HashMap<char, usize> = HashMap::new();
+        let mut seq = String::with_capacity(self.seq.len());
+        let mut vertex = first;
+        seq.push(vertex);
+        for _ in 1..chars.len() {
+            let index = next.entry(vertex).or_insert(0);
+            let target = edges[&vertex][*index];
+            *index += 1;
+            seq.push(target);
+            vertex = target;
+        }
+        Sequence::new(self.biotype.clone(), seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn random_composition() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let dna = random_dna(10_000, 0.7, &mut rng);
+        assert_eq!(dna.len(), 10_000);
+        assert_eq!(dna.biotype, BioType::Dna);
+        assert!((dna.gc_content().unwrap() - 0.7).abs() < 0.03);
+        assert!(dna.validate().is_ok());
+        assert!(!random_rna(100, 0.5, &mut rng).seq.contains('T'));
+        assert!(random_dna(50, 1.0, &mut rng)
+            .seq
+            .chars()
+            .all(|c| c == 'G' || c == 'C'));
+
+        let protein = random_protein(200, &mut rng);
+        assert!(protein.validate().is_ok());
+        let weighted = random_protein_weighted(50, &[('M', 1.0), ('K', 0.0)], &mut rng).unwrap();
+        assert_eq!(weighted.seq, "M".repeat(50));
+        assert!(random_protein_weighted(10, &[], &mut rng).is_err());
+    }
+
+    #[test]
+    fn dinucleotide_shuffle() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let dna = Sequence::new(BioType::Dna, String::from("ATGCGCGTATTAGCCGATAGGCTTACG"));
+        let shuffled = dna.shuffle(&mut rng);
+        assert_eq!(shuffled.len(), dna.len());
+        assert_eq!(shuffled.seq.chars().next(), Some('A'));
+        assert!(shuffled.seq.ends_with('G'));
+        assert_eq!(shuffled.kmer_count(2), dna.kmer_count(2));
+        assert_eq!(shuffled.kmer_count(1), dna.kmer_count(1));
+        let short = Sequence::new(BioType::Rna, String::from("AU"));
+        assert_eq!(short.shuffle(&mut rng), short);
+    }
+
+    #[test]
+    fn stochastic_codon_optimization() {
+        let mut rng = StdRng::seed_from_u64(1);
+        // Ala 中 GCC 与 GCU 各占一半，Lys 只有 AAG
+        let target = Sequence::new(BioType::Dna, String::from("GCCGCTAAG"))
+            .codon_usage()
+            .unwrap();
+        let dna = Sequence::new(BioType::Dna, "GCAAAATGG".repeat(50));
+        let optimized = dna.optimize_codons_stochastic(&target, &mut rng).unwrap();
+        assert_eq!(optimized.translate().unwrap(), dna.translate().unwrap());
+        let codons: Vec<&str> = optimized.codons(0).unwrap().collect();
+        assert!(codons.iter().all(|&c| c != "GCA" && c != "AAA"));
+        let gcc = codons.iter().filter(|&&c| c == "GCC").count();
+        assert!(gcc > 10 && gcc < 40);
+
+        let protein = Sequence::new(BioType::Protein, "AK".repeat(50));
+        let dna = protein
+            .back_translate_stochastic(&CodonTable::standard(), &target, &mut rng)
+            .unwrap();
+        assert_eq!(dna.translate().unwrap().seq, protein.seq);
+        let codons: Vec<&str> = dna.codons(0).unwrap().collect();
+        assert!(codons
+            .iter()
+            .all(|&c| c == "GCC" || c == "GCT" || c == "AAG"));
+    }
+}
@@ -0,0 +1,222 @@
+use super::feature::{Feature, FeatureIndex};
+use super::sequence::Sequence;
+
+/// 从 fasta 标题行中解析出的附加信息，各字段都可能缺失
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceMetadata {
+    pub name: Option<String>,
+    pub organism: Option<String>,
+    pub accession: Option<String>,
+}
+
+/// NCBI 风格的 `|` 分隔 id 中紧跟登录号的数据库标记
+const ACCESSION_TAGS: [&str; 8] = ["ref", "gb", "emb", "dbj", "pdb", "sp", "tr", "lcl"];
+
+impl SequenceMetadata {
+    /// 从标题行的 id 与描述中解析附加信息
+    /// - UniProt：`sp|P69905|HBA_HUMAN Hemoglobin subunit alpha OS=Homo sapiens OX=9606`
+    /// - NCBI：`gi|4504347|ref|NP_000549.1| hemoglobin alpha [Homo sapiens]`
+    /// - 描述中的 `[organism=...]`、`[name=...]` 与 `[accession=...]` 标签
+    pub fn from_header(id: &str, description: &str) -> Self {
+        let mut metadata = SequenceMetadata::default();
+
+        let fields: Vec<&str> = id.split('|').collect();
+        if fields.len() > 1 {
+            if let Some(pos) = fields.iter().position(|f| ACCESSION_TAGS.contains(f)) {
+                metadata.accession = fields
+                    .get(pos + 1)
+                    .filter(|f| !f.is_empty())
+                    .map(|f| f.to_string());
+                if matches!(fields[pos], "sp" | "tr") {
+                    metadata.name = fields
+                        .get(pos + 2)
+                        .filter(|f| !f.is_empty())
+                        .map(|f| f.to_string());
+                }
+            }
+        }
+
+        if let Some(value) = bracket_tag(description, "accession") {
+            metadata.accession = Some(value);
+        }
+        if let Some(value) = bracket_tag(description, "name") {
+            metadata.name = Some(value);
+        }
+        metadata.organism = bracket_tag(description, "organism")
+            .or_else(|| uniprot_organism(description))
+            .or_else(|| trailing_bracket(description));
+        metadata
+    }
+
+    /// 所有字段均缺失
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.organism.is_none() && self.accession.is_none()
+    }
+}
+
+/// 带有名称与描述的序列记录
+/// 对应 fasta 中的一条记录：`>id description` 以及其后的序列
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NamedSequence {
+    pub id: String,
+    pub description: String,
+    pub sequence: Sequence,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: SequenceMetadata,
+    /// 序列上的注释特征
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub features: FeatureIndex,
+}
+
+impl NamedSequence {
+    /// 新建 NamedSequence 对象，附加信息从 id 与描述中解析
+    pub fn new(id: String, description: String, sequence: Sequence) -> Self {
+        let metadata = SequenceMetadata::from_header(&id, &description);
+        NamedSequence {
+            id,
+            description,
+            sequence,
+            metadata,
+            features: FeatureIndex::new(),
+        }
+    }
+
+    /// 返回覆盖位置 `pos` 的所有特征，按起点排序
+    pub fn features_at(&self, pos: usize) -> Vec<&Feature> {
+        self.features.features_at(pos)
+    }
+
+    /// 返回与区间 `start..end` 重叠的所有特征，按起点排序
+    pub fn features_overlapping(&self, start: usize, end: usize) -> Vec<&Feature> {
+        self.features.features_overlapping(start, end)
+    }
+
+    /// 写出 fasta 时使用的标题行 (不含 `>`)
+    /// 没有出现在描述中的物种会以 `[organism=...]` 标签补充
+    pub fn header(&self) -> String {
+        let mut header = self.id.clone();
+        if !self.description.is_empty() {
+            header.push(' ');
+            header.push_str(&self.description);
+        }
+        if let Some(organism) = &self.metadata.organism {
+            if !self.description.contains(organism.as_str()) {
+                header.push_str(&format!(" [organism={}]", organism));
+            }
+        }
+        header
+    }
+}
+
+/// 描述中 `[key=value]` 形式的标签
+fn bracket_tag(description: &str, key: &str) -> Option<String> {
+    let pattern = format!("[{}=", key);
+    let start = description.find(&pattern)? + pattern.len();
+    let end = description[start..].find(']')? + start;
+    let value = description[start..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// UniProt 描述中的 `OS=物种`，到下一个 `XX=` 字段为止
+fn uniprot_organism(description: &str) -> Option<String> {
+    let start = if description.starts_with("OS=") {
+        3
+    } else {
+        description.find(" OS=")? + 4
+    };
+    let rest = &description[start..];
+    let end = rest
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .find(|&i| {
+            let field = &rest.as_bytes()[i + 1..];
+            field.len() > 2
+                && field[0].is_ascii_uppercase()
+                && field[1].is_ascii_uppercase()
+                && field[2] == b'='
+        })
+        .unwrap_or(rest.len());
+    let value = rest[..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// NCBI 蛋白描述末尾的 `[物种]`
+fn trailing_bracket(description: &str) -> Option<String> {
+    let trimmed = description.trim_end().strip_suffix(']')?;
+    let start = trimmed.rfind('[')?;
+    let value = trimmed[start + 1..].trim();
+    (!value.is_empty() && !value.contains('=')).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feature::FeatureType;
+    use crate::sequence::{BioType, Strand};
+
+    #[test]
+    fn parse_header_metadata() {
+        let uniprot = SequenceMetadata::from_header(
+            "sp|P69905|HBA_HUMAN",
+            "Hemoglobin subunit alpha OS=Homo sapiens OX=9606 GN=HBA1 PE=1 SV=2",
+        );
+        assert_eq!(uniprot.accession.as_deref(), Some("P69905"));
+        assert_eq!(uniprot.name.as_deref(), Some("HBA_HUMAN"));
+        assert_eq!(uniprot.organism.as_deref(), Some("Homo sapiens"));
+
+        let ncbi = SequenceMetadata::from_header(
+            "gi|4504347|ref|NP_000549.1|",
+            "hemoglobin subunit alpha [Homo sapiens]",
+        );
+        assert_eq!(ncbi.accession.as_deref(), Some("NP_000549.1"));
+        assert_eq!(ncbi.name, None);
+        assert_eq!(ncbi.organism.as_deref(), Some("Homo sapiens"));
+
+        let tagged =
+            SequenceMetadata::from_header("circ1", "[organism=Mus musculus] [name=Cdr1as]");
+        assert_eq!(tagged.organism.as_deref(), Some("Mus musculus"));
+        assert_eq!(tagged.name.as_deref(), Some("Cdr1as"));
+        assert!(SequenceMetadata::from_header("seq1", "plain description").is_empty());
+    }
+
+    #[test]
+    fn header_includes_organism() {
+        let mut record = NamedSequence::new(
+            String::from("circ1"),
+            String::from("back-spliced"),
+            Sequence::new(BioType::Rna, String::from("AUGC")),
+        );
+        assert_eq!(record.header(), "circ1 back-spliced");
+        record.metadata.organism = Some(String::from("Homo sapiens"));
+        assert_eq!(
+            record.header(),
+            "circ1 back-spliced [organism=Homo sapiens]"
+        );
+    }
+
+    #[test]
+    fn annotated_sequence() {
+        let mut record = NamedSequence::new(
+            String::from("circ1"),
+            String::new(),
+            Sequence::new(BioType::Rna, "AUGC".repeat(25)),
+        );
+        record.features = vec![
+            Feature::new(0, 100, Strand::Forward, FeatureType::CircRna),
+            Feature::new(10, 40, Strand::Forward, FeatureType::Exon),
+            Feature::new(60, 90, Strand::Forward, FeatureType::Exon),
+        ]
+        .into();
+        assert_eq!(record.features.len(), 3);
+        assert_eq!(record.features_at(50).len(), 1);
+        let exons: Vec<&Feature> = record
+            .features_overlapping(35, 65)
+            .into_iter()
+            .filter(|f| f.feature_type == FeatureType::Exon)
+            .collect();
+        assert_eq!(exons.len(), 2);
+        assert!(record.features_overlapping(100, 120).is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+use super::circrna::CircSequence;
+use super::sequence::{BioType, Sequence};
+
+// 重复序列的查找
+
+/// 串联重复 (微卫星 / 小卫星)
+/// start 与 end 为从 0 开始的左闭右开区间，最后一个拷贝可以不完整
+#[derive(Debug, Clone, PartialEq)]
+pub struct TandemRepeat {
+    pub start: usize,
+    pub end: usize,
+    /// 重复单元
+    pub unit_seq: String,
+    /// 拷贝数，区间长度除以单元长度
+    pub copy_number: f32,
+}
+
+impl Sequence {
+    /// 查找单元长度在 `min_unit..=max_unit` 之间、连续重复至少 `min_copies` 次的串联重复
+    /// 不区分大小写；本身由更短单元重复构成的单元 (例如 ATAT) 不会重复报告
+    /// 每种单元长度只扫描一遍序列，总耗时为 O(n * (max_unit - min_unit + 1))
+    pub fn find_tandem_repeats(
+        &self,
+        min_unit: usize,
+        max_unit: usize,
+        min_copies: f32,
+    ) -> Vec<TandemRepeat> {
+        let bases: Vec<char> = self.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let n = bases.len();
+        let mut repeats = Vec::new();
+
+        for unit in min_unit.max(1)..=max_unit {
+            let mut i = 0;
+            while i + unit < n {
+                let mut j = i;
+                while j + unit < n && bases[j] == bases[j + unit] {
+                    j += 1;
+                }
+                if j == i {
+                    i += 1;
+                    continue;
+                }
+                let span = j - i + unit;
+                let copies = span as f32 / unit as f32;
+                let unit_bases = &bases[i..i + unit];
+                if copies >= min_copies && !has_shorter_period(unit_bases) {
+                    repeats.push(TandemRepeat {
+                        start: i,
+                        end: i + span,
+                        unit_seq: unit_bases.iter().collect(),
+                        copy_number: copies,
+                    });
+                }
+                i = j + 1;
+            }
+        }
+        repeats.sort_by_key(|repeat| (repeat.start, repeat.unit_seq.len()));
+        repeats
+    }
+}
+
+/// 回文序列 (反向重复)，左臂与右臂反向互补，中间可以有 gap 个不配对的碱基
+/// 例如 EcoRI 位点 GAATTC 的臂长为 3，gap 为 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palindrome {
+    /// 左臂第一个碱基的位置
+    pub start: usize,
+    pub arm_length: usize,
+    pub gap: usize,
+    /// 左臂、间隔与右臂组成的完整序列
+    pub sequence: Sequence,
+}
+
+impl Palindrome {
+    /// 回文序列的总长度
+    pub fn len(&self) -> usize {
+        2 * self.arm_length + self.gap
+    }
+
+    /// 总长度是否为 0
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Sequence {
+    /// 查找臂长在 `min_length..=max_length` 之间、间隔不超过 `max_gap` 的回文序列
+    /// 每个中心只报告最长的臂 (超过 `max_length` 时截断为 `max_length`)，
+    /// 间隔两端的碱基可以配对时只报告间隔更短的那一个
+    /// 只支持 DNA 与 RNA
+    pub fn find_palindromes(
+        &self,
+        min_length: usize,
+        max_length: usize,
+        max_gap: usize,
+    ) -> Result<Vec<Palindrome>, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("不能在 {} 序列中查找回文序列", self.biotype));
+        }
+        let bases: Vec<char> = self.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        Ok(
+            palindromes(&bases, 0..bases.len(), min_length, max_length, max_gap)
+                .into_iter()
+                .map(|(start, arm_length, gap)| Palindrome {
+                    start,
+                    arm_length,
+                    gap,
+                    sequence: self.subsequence(start..start + 2 * arm_length + gap),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl CircSequence {
+    /// 在环状序列中查找回文序列，回文可以跨过接头，参数含义见 `Sequence::find_palindromes`
+    pub fn find_palindromes(
+        &self,
+        min_length: usize,
+        max_length: usize,
+        max_gap: usize,
+    ) -> Result<Vec<Palindrome>, String> {
+        if self.sequence.biotype == BioType::Protein {
+            return Err(format!(
+                "不能在 {} 序列中查找回文序列",
+                self.sequence.biotype
+            ));
+        }
+        let len = self.len();
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        // 两侧各补上足够长的序列，使跨过接头的臂可以完整延伸
+        let pad = (max_length + max_gap).min(len);
+        let extended = self.subsequence(len - pad..2 * len + pad);
+        let bases: Vec<char> = extended
+            .seq
+            .chars()
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let mut found: Vec<Palindrome> =
+            palindromes(&bases, pad..pad + len, min_length, max_length, max_gap)
+                .into_iter()
+                .filter(|&(_, arm_length, gap)| 2 * arm_length + gap <= len)
+                .map(|(start, arm_length, gap)| {
+                    let start = (start + len - pad) % len;
+                    Palindrome {
+                        start,
+                        arm_length,
+                        gap,
+                        sequence: self.subsequence(start..start + 2 * arm_length + gap),
+                    }
+                })
+                .collect();
+        found.sort_by_key(|palindrome| (palindrome.start, palindrome.gap));
+        Ok(found)
+    }
+}
+
+/// 两个碱基是否按 Watson-Crick 规则配对
+fn pairs(a: char, b: char) -> bool {
+    matches!(
+        (a, b),
+        ('A', 'T') | ('T', 'A') | ('A', 'U') | ('U', 'A') | ('G', 'C') | ('C', 'G')
+    )
+}
+
+/// 查找左臂末端 (不含) 位于 `centers` 中的回文，返回 (左臂起点, 臂长, 间隔)
+fn palindromes(
+    bases: &[char],
+    centers: std::ops::Range<usize>,
+    min_length: usize,
+    max_length: usize,
+    max_gap: usize,
+) -> Vec<(usize, usize, usize)> {
+    let n = bases.len();
+    let mut found = Vec::new();
+    for left_end in centers {
+        for gap in 0..=max_gap {
+            let right_start = left_end + gap;
+            if right_start > n {
+                break;
+            }
+            // 间隔两端能够配对时，同一个回文会以更短的间隔报告
+            if gap >= 2 && pairs(bases[left_end], bases[right_start - 1]) {
+                continue;
+            }
+            let mut arm = 0;
+            while arm < max_length
+                && arm < left_end
+                && right_start + arm < n
+                && pairs(bases[left_end - arm - 1], bases[right_start + arm])
+            {
+                arm += 1;
+            }
+            if arm > 0 && arm >= min_length {
+                found.push((left_end - arm, arm, gap));
+            }
+        }
+    }
+    found
+}
+
+/// 单元是否由更短的单元整数次重复构成
+fn has_shorter_period(unit: &[char]) -> bool {
+    (1..unit.len())
+        .filter(|&period| unit.len() % period == 0)
+        .any(|period| (period..unit.len()).all(|i| unit[i] == unit[i - period]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn microsatellites() {
+        let dna = Sequence::new(BioType::Dna, String::from("GGCACACACACATTTTTTGCAGCAGCAGC"));
+        let repeats = dna.find_tandem_repeats(1, 6, 3.0);
+        let summary: Vec<(usize, usize, &str)> = repeats
+            .iter()
+            .map(|r| (r.start, r.end, r.unit_seq.as_str()))
+            .collect();
+        assert_eq!(summary, vec![(2, 12, "CA"), (12, 18, "T"), (18, 29, "GCA")]);
+        assert_eq!(repeats[0].copy_number, 5.0);
+        assert!((repeats[2].copy_number - 11.0 / 3.0).abs() < 1e-6);
+        assert!(dna.find_tandem_repeats(2, 2, 6.0).is_empty());
+    }
+
+    #[test]
+    fn palindromes() {
+        let dna = Sequence::new(BioType::Dna, String::from("CCGAATTCAAGCGTTTTACGCTT"));
+        let found = dna.find_palindromes(3, 10, 4).unwrap();
+        let summary: Vec<(usize, usize, usize, &str)> = found
+            .iter()
+            .map(|p| (p.start, p.arm_length, p.gap, p.sequence.seq.as_str()))
+            .collect();
+        assert!(summary.contains(&(2, 3, 0, "GAATTC")));
+        assert!(summary.contains(&(8, 6, 3, "AAGCGTTTTACGCTT")));
+        assert_eq!(found.iter().filter(|p| p.gap > 0).count(), 1);
+        assert!(Sequence::new(BioType::Protein, String::from("MK"))
+            .find_palindromes(2, 4, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn circular_palindromes() {
+        // GAATTC 跨过接头
+        let circ = CircSequence::new(Sequence::new(BioType::Dna, String::from("TTCCCCCGAA")));
+        let found = circ.find_palindromes(3, 3, 0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start, 7);
+        assert_eq!(found[0].sequence.seq, "GAATTC");
+    }
+}
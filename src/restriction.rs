@@ -0,0 +1,250 @@
+use super::sequence::{BioType, Sequence};
+
+use std::collections::BTreeMap;
+
+// 限制性内切酶酶切的模拟
+// 切割位置相对于识别位点在正链上的起点，例如 EcoRI (G^AATTC) 的切割位置为 1
+
+/// 常用限制性内切酶 (名称, 识别序列, 正链切割位置)
+const ENZYMES: [(&str, &str, isize); 16] = [
+    ("EcoRI", "GAATTC", 1),
+    ("HindIII", "AAGCTT", 1),
+    ("BamHI", "GGATCC", 1),
+    ("XhoI", "CTCGAG", 1),
+    ("NcoI", "CCATGG", 1),
+    ("NdeI", "CATATG", 2),
+    ("NotI", "GCGGCCGC", 2),
+    ("XbaI", "TCTAGA", 1),
+    ("SalI", "GTCGAC", 1),
+    ("SpeI", "ACTAGT", 1),
+    ("BglII", "AGATCT", 1),
+    ("PstI", "CTGCAG", 5),
+    ("KpnI", "GGTACC", 5),
+    ("SacI", "GAGCTC", 5),
+    ("SmaI", "CCCGGG", 3),
+    ("EcoRV", "GATATC", 3),
+];
+
+/// 限制性内切酶
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestrictionEnzyme {
+    pub name: String,
+    pub recognition: String,
+    /// 正链上的切割位置，相对于识别位点起点，可以为负数或超过识别序列长度
+    pub cut_position: isize,
+}
+
+impl RestrictionEnzyme {
+    /// 新建 RestrictionEnzyme 对象
+    pub fn new(name: String, recognition: String, cut_position: isize) -> Self {
+        RestrictionEnzyme {
+            name,
+            recognition,
+            cut_position,
+        }
+    }
+
+    /// 从内置酶表中按名称获取，不区分大小写
+    pub fn from_name(name: &str) -> Result<RestrictionEnzyme, String> {
+        ENZYMES
+            .iter()
+            .find(|(enzyme, ..)| enzyme.eq_ignore_ascii_case(name))
+            .map(|&(enzyme, recognition, cut_position)| {
+                RestrictionEnzyme::new(enzyme.to_string(), recognition.to_string(), cut_position)
+            })
+            .ok_or(format!("未知的限制性内切酶: {}", name))
+    }
+
+    /// 内置酶表中的全部酶
+    pub fn all() -> Vec<RestrictionEnzyme> {
+        ENZYMES
+            .iter()
+            .map(|&(enzyme, recognition, cut_position)| {
+                RestrictionEnzyme::new(enzyme.to_string(), recognition.to_string(), cut_position)
+            })
+            .collect()
+    }
+
+    /// 回文识别位点切割后单链突出末端的长度
+    /// 正数为 5' 突出，负数为 3' 突出，0 为平末端
+    pub fn overhang(&self) -> isize {
+        self.recognition.len() as isize - 2 * self.cut_position
+    }
+
+    /// 是否产生平末端
+    pub fn is_blunt(&self) -> bool {
+        self.overhang() == 0
+    }
+
+    /// 识别位点在序列中的全部起点，不区分大小写
+    pub fn sites(&self, seq: &Sequence) -> Vec<usize> {
+        seq.find_all_overlapping(&self.recognition)
+    }
+
+    /// 正链上的全部切割位置，按顺序排列并去重，落在序列之外的切割位置会被忽略
+    pub fn cut_sites(&self, seq: &Sequence) -> Vec<usize> {
+        let mut cuts: Vec<usize> = self
+            .sites(seq)
+            .into_iter()
+            .map(|site| site as isize + self.cut_position)
+            .filter(|&cut| cut > 0 && cut < seq.len() as isize)
+            .map(|cut| cut as usize)
+            .collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        cuts
+    }
+
+    /// 酶切 DNA 序列，按顺序返回切割后的各个片段
+    /// 没有识别位点时返回原序列
+    pub fn digest(&self, seq: &Sequence) -> Result<Vec<Sequence>, String> {
+        if seq.biotype != BioType::Dna {
+            return Err(format!("限制性内切酶只能切割 {} 序列", BioType::Dna));
+        }
+        let mut fragments = Vec::new();
+        let mut start = 0;
+        for cut in self.cut_sites(seq) {
+            fragments.push(seq.subsequence(start..cut));
+            start = cut;
+        }
+        fragments.push(seq.subsequence(start..));
+        Ok(fragments)
+    }
+
+    /// 酶切后各片段的长度，按从大到小排列，类似电泳的结果
+    /// 非 DNA 序列返回空结果
+    pub fn fragment_sizes(&self, seq: &Sequence) -> Vec<usize> {
+        let mut sizes: Vec<usize> = match self.digest(seq) {
+            Ok(fragments) => fragments.iter().map(|fragment| fragment.len()).collect(),
+            Err(_) => Vec::new(),
+        };
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+/// 多种内切酶在同一条序列上的酶切图谱
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestrictionMap<'a> {
+    /// 正链切割位置 -> 在该位置切割的酶，按 `restriction_map` 参数中的顺序排列
+    pub sites: BTreeMap<usize, Vec<&'a RestrictionEnzyme>>,
+    sequence: &'a Sequence,
+}
+
+/// 所有酶在序列上的切割位置，切割位置与 `RestrictionEnzyme::cut_sites` 相同
+/// 非 DNA 序列返回空图谱
+pub fn restriction_map<'a>(
+    seq: &'a Sequence,
+    enzymes: &'a [RestrictionEnzyme],
+) -> RestrictionMap<'a> {
+    let mut sites: BTreeMap<usize, Vec<&RestrictionEnzyme>> = BTreeMap::new();
+    if seq.biotype == BioType::Dna {
+        for enzyme in enzymes {
+            for cut in enzyme.cut_sites(seq) {
+                sites.entry(cut).or_default().push(enzyme);
+            }
+        }
+    }
+    RestrictionMap {
+        sites,
+        sequence: seq,
+    }
+}
+
+impl RestrictionMap<'_> {
+    /// 以文本形式绘制酶切图谱，序列每行 `line_width` 个碱基，为 0 时整条序列输出在一行
+    /// 每个切割位置在所在行的上方单独占一行，`|` 位于切割位置之后第一个碱基的正上方，后面是酶的名称与切割位置
+    /// 序列行首是该行第一个碱基的位置 (以 1 开始)，例如：
+    /// ```text
+    ///       |EcoRI 3
+    ///  1 AAGAATTCTT
+    ///       |EcoRI 13
+    /// 11 TTgaattcCC
+    /// ```
+    pub fn display_map(&self, line_width: usize) -> String {
+        let chars: Vec<char> = self.sequence.seq.chars().collect();
+        let size = if line_width == 0 {
+            chars.len().max(1)
+        } else {
+            line_width
+        };
+        let margin = chars.len().max(1).to_string().len() + 1;
+        let mut lines: Vec<String> = Vec::new();
+        for (i, chunk) in chars.chunks(size).enumerate() {
+            let start = i * size;
+            for (&cut, enzymes) in self.sites.range(start..start + chunk.len()) {
+                let names: Vec<&str> = enzymes.iter().map(|enzyme| enzyme.name.as_str()).collect();
+                lines.push(format!(
+                    "{}|{} {}",
+                    " ".repeat(margin + cut - start),
+                    names.join(","),
+                    cut
+                ));
+            }
+            let line: String = chunk.iter().collect();
+            lines.push(format!(
+                "{:>width$} {}",
+                start + 1,
+                line,
+                width = margin - 1
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest() {
+        let ecori = RestrictionEnzyme::from_name("ecori").unwrap();
+        assert_eq!(ecori.overhang(), 4);
+        let dna = Sequence::new(BioType::Dna, String::from("AAGAATTCTTTTgaattcCC"));
+        let fragments: Vec<String> = ecori
+            .digest(&dna)
+            .unwrap()
+            .into_iter()
+            .map(|fragment| fragment.seq)
+            .collect();
+        assert_eq!(fragments, vec!["AAG", "AATTCTTTTg", "aattcCC"]);
+        assert_eq!(ecori.fragment_sizes(&dna), vec![10, 7, 3]);
+
+        let smai = RestrictionEnzyme::from_name("SmaI").unwrap();
+        assert!(smai.is_blunt());
+        assert_eq!(RestrictionEnzyme::from_name("KpnI").unwrap().overhang(), -4);
+        assert_eq!(smai.digest(&dna).unwrap(), vec![dna.clone()]);
+
+        assert!(RestrictionEnzyme::from_name("Unknown").is_err());
+        let rna = Sequence::new(BioType::Rna, String::from("GAAUUC"));
+        assert!(ecori.digest(&rna).is_err());
+    }
+
+    #[test]
+    fn restriction_site_map() {
+        let enzymes = vec![
+            RestrictionEnzyme::from_name("EcoRI").unwrap(),
+            RestrictionEnzyme::from_name("HindIII").unwrap(),
+            RestrictionEnzyme::new(String::from("ApoI"), String::from("AATT"), -1),
+        ];
+        let dna = Sequence::new(BioType::Dna, String::from("AAGAATTCTTaagcttCC"));
+        let map = restriction_map(&dna, &enzymes);
+        let positions: Vec<(usize, Vec<&str>)> = map
+            .sites
+            .iter()
+            .map(|(&cut, enzymes)| (cut, enzymes.iter().map(|e| e.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(2, vec!["ApoI"]), (3, vec!["EcoRI"]), (11, vec!["HindIII"])]
+        );
+        assert_eq!(
+            map.display_map(10),
+            "     |ApoI 2\n      |EcoRI 3\n 1 AAGAATTCTT\n    |HindIII 11\n11 aagcttCC"
+        );
+        assert_eq!(map.display_map(0).lines().count(), 4);
+        let rna = Sequence::new(BioType::Rna, String::from("GAAUUC"));
+        assert!(restriction_map(&rna, &enzymes).sites.is_empty());
+    }
+}
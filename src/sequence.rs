@@ -1,25 +1,241 @@
-use super::codon;
+use super::codon::CodonTable;
 
-use std::ops::Add;
-use std::slice::Chunks;
 use std::collections::HashMap;
-use std::{cmp::PartialEq, fmt};
-/// 用于生物 CircRNA以及 基础生物序列操作
-/// 主要是尝试输出来让自己熟练
+use std::iter::Enumerate;
+use std::ops::{Add, Bound, Index, Range, RangeBounds};
+use std::slice::Chunks;
+use std::str::{Chars, FromStr};
+use std::{error, fmt};
+// 用于生物 CircRNA以及 基础生物序列操作
+// 主要是尝试输出来让自己熟练
 
-/// 首先是生物序列对象
+// 首先是生物序列对象
 
 const DNA_BASE_PAIRING: [(char, char); 4] = [('A', 'T'), ('G', 'C'), ('T', 'A'), ('C', 'G')];
 
 const RNA_BASE_PAIRING: [(char, char); 4] = [('A', 'U'), ('G', 'C'), ('U', 'A'), ('C', 'G')];
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// 所有 ASCII 字符，`Index<usize>` 需要返回引用，而 String 中并没有保存 char
+static ASCII_CHARS: [char; 128] = {
+    let mut chars = ['\0'; 128];
+    let mut i = 0;
+    while i < 128 {
+        chars[i] = i as u8 as char;
+        i += 1;
+    }
+    chars
+};
+
+/// IUPAC 简并碱基
+/// 用于表示测序或组装结果中不确定的位置
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmbiguousBase {
+    /// A 或 G
+    R,
+    /// C 或 T
+    Y,
+    /// G 或 C
+    S,
+    /// A 或 T
+    W,
+    /// G 或 T
+    K,
+    /// A 或 C
+    M,
+    /// C、G 或 T
+    B,
+    /// A、G 或 T
+    D,
+    /// A、C 或 T
+    H,
+    /// A、C 或 G
+    V,
+    /// 任意碱基
+    N,
+}
+
+impl AmbiguousBase {
+    /// 从字符解析简并碱基，不区分大小写
+    pub fn from_char(ch: char) -> Option<AmbiguousBase> {
+        match ch.to_ascii_uppercase() {
+            'R' => Some(AmbiguousBase::R),
+            'Y' => Some(AmbiguousBase::Y),
+            'S' => Some(AmbiguousBase::S),
+            'W' => Some(AmbiguousBase::W),
+            'K' => Some(AmbiguousBase::K),
+            'M' => Some(AmbiguousBase::M),
+            'B' => Some(AmbiguousBase::B),
+            'D' => Some(AmbiguousBase::D),
+            'H' => Some(AmbiguousBase::H),
+            'V' => Some(AmbiguousBase::V),
+            'N' => Some(AmbiguousBase::N),
+            _ => None,
+        }
+    }
+
+    /// 简并碱基或普通碱基 `code` 是否可以代表 `base`，均不区分大小写，U 视为 T
+    pub fn matches(code: char, base: char) -> bool {
+        let normalize = |c: char| match c.to_ascii_uppercase() {
+            'U' => 'T',
+            c => c,
+        };
+        let (code, base) = (normalize(code), normalize(base));
+        if !matches!(base, 'A' | 'C' | 'G' | 'T') {
+            return false;
+        }
+        match AmbiguousBase::from_char(code) {
+            Some(ambiguous) => ambiguous.bases().contains(&base),
+            None => code == base,
+        }
+    }
+
+    /// 表示一组 DNA 碱基 (A C G T，U 视为 T) 的简并碱基，少于两种碱基或含有其它字符时返回 None
+    pub fn from_bases(bases: &[char]) -> Option<AmbiguousBase> {
+        let mut set: Vec<char> = Vec::new();
+        for &base in bases {
+            let base = match base.to_ascii_uppercase() {
+                'U' => 'T',
+                b @ ('A' | 'C' | 'G' | 'T') => b,
+                _ => return None,
+            };
+            if !set.contains(&base) {
+                set.push(base);
+            }
+        }
+        set.sort_unstable();
+        ['R', 'Y', 'S', 'W', 'K', 'M', 'B', 'D', 'H', 'V', 'N']
+            .into_iter()
+            .filter_map(AmbiguousBase::from_char)
+            .find(|code| code.bases() == set.as_slice())
+    }
+
+    /// 返回对应的大写字符
+    pub fn to_char(self) -> char {
+        match self {
+            AmbiguousBase::R => 'R',
+            AmbiguousBase::Y => 'Y',
+            AmbiguousBase::S => 'S',
+            AmbiguousBase::W => 'W',
+            AmbiguousBase::K => 'K',
+            AmbiguousBase::M => 'M',
+            AmbiguousBase::B => 'B',
+            AmbiguousBase::D => 'D',
+            AmbiguousBase::H => 'H',
+            AmbiguousBase::V => 'V',
+            AmbiguousBase::N => 'N',
+        }
+    }
+
+    /// 互补的简并碱基，DNA 与 RNA 相同
+    pub fn complement(self) -> AmbiguousBase {
+        match self {
+            AmbiguousBase::R => AmbiguousBase::Y,
+            AmbiguousBase::Y => AmbiguousBase::R,
+            AmbiguousBase::S => AmbiguousBase::S,
+            AmbiguousBase::W => AmbiguousBase::W,
+            AmbiguousBase::K => AmbiguousBase::M,
+            AmbiguousBase::M => AmbiguousBase::K,
+            AmbiguousBase::B => AmbiguousBase::V,
+            AmbiguousBase::V => AmbiguousBase::B,
+            AmbiguousBase::D => AmbiguousBase::H,
+            AmbiguousBase::H => AmbiguousBase::D,
+            AmbiguousBase::N => AmbiguousBase::N,
+        }
+    }
+
+    /// 该简并碱基可能代表的 DNA 碱基
+    pub fn bases(self) -> &'static [char] {
+        match self {
+            AmbiguousBase::R => &['A', 'G'],
+            AmbiguousBase::Y => &['C', 'T'],
+            AmbiguousBase::S => &['C', 'G'],
+            AmbiguousBase::W => &['A', 'T'],
+            AmbiguousBase::K => &['G', 'T'],
+            AmbiguousBase::M => &['A', 'C'],
+            AmbiguousBase::B => &['C', 'G', 'T'],
+            AmbiguousBase::D => &['A', 'G', 'T'],
+            AmbiguousBase::H => &['A', 'C', 'T'],
+            AmbiguousBase::V => &['A', 'C', 'G'],
+            AmbiguousBase::N => &['A', 'C', 'G', 'T'],
+        }
+    }
+}
+
+/// 序列类型，序列化时使用小写字符串 `"dna"`、`"rna"`、`"protein"`
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase", try_from = "String")
+)]
 pub enum BioType {
     Dna,
     Rna,
     Protein,
 }
 
+/// 推断为核酸时，A、C、G、T、U、N 至少占全部字符的比例
+/// 只由 IUPAC 字母组成的短肽 (如 MKVHRDSW) 也会全部落在简并碱基中，因此不能只看字符是否合法
+const NUCLEOTIDE_MIN_FRACTION: f64 = 0.9;
+
+impl BioType {
+    /// 根据字符组成推断序列类型，不区分大小写
+    /// 只含核苷酸 (包括 IUPAC 简并碱基) 且 A、C、G、T、U、N 至少占 90% 时为核酸，
+    /// 其中含 U 且不含 T 时为 RNA，否则为 DNA；其余视为蛋白质，空序列为 DNA
+    pub fn infer(seq: &str) -> BioType {
+        let mut has_t = false;
+        let mut has_u = false;
+        let (mut total, mut unambiguous) = (0usize, 0usize);
+        for c in seq.chars() {
+            match c.to_ascii_uppercase() {
+                'A' | 'C' | 'G' | 'N' => unambiguous += 1,
+                'T' => {
+                    has_t = true;
+                    unambiguous += 1;
+                }
+                'U' => {
+                    has_u = true;
+                    unambiguous += 1;
+                }
+                other if AmbiguousBase::from_char(other).is_some() => {}
+                _ => return BioType::Protein,
+            }
+            total += 1;
+        }
+        if (unambiguous as f64) < NUCLEOTIDE_MIN_FRACTION * total as f64 {
+            return BioType::Protein;
+        }
+        match (has_t, has_u) {
+            (false, true) => BioType::Rna,
+            (true, true) => BioType::Protein,
+            _ => BioType::Dna,
+        }
+    }
+}
+
+/// 从字符串解析序列类型，不区分大小写
+impl TryFrom<&str> for BioType {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "dna" => Ok(BioType::Dna),
+            "rna" => Ok(BioType::Rna),
+            "protein" => Ok(BioType::Protein),
+            _ => Err(format!("未知的序列类型: {}", value)),
+        }
+    }
+}
+
+impl TryFrom<String> for BioType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        BioType::try_from(value.as_str())
+    }
+}
+
 // fmt trait 用于错误处理中实现格式化
 impl fmt::Display for BioType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -31,29 +247,227 @@ impl fmt::Display for BioType {
     }
 }
 
-#[derive(Debug, Clone)]
+/// 20 种标准氨基酸
+pub(crate) const AMINO_ACIDS: &str = "ACDEFGHIKLMNPQRSTVWY";
+
+/// 序列中出现了与类型不符的字符
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub biotype: BioType,
+    pub position: usize,
+    pub base: char,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "第 {} 位的字符 '{}' 不是合法的 {} 字符",
+            self.position, self.base, self.biotype
+        )
+    }
+}
+
+impl error::Error for ValidationError {}
+
+/// 通过 `str::parse` 解析序列时可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// 字符串为空，无法推断类型
+    Empty,
+    /// 含有任何序列类型都不允许的字符
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "空字符串无法推断序列类型"),
+            ParseError::Invalid(err) => write!(f, "无法解析序列: {}", err),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ParseError::Invalid(err) => Some(err),
+            ParseError::Empty => None,
+        }
+    }
+}
+
+/// `Sequence::normalize_with` 中要执行的处理，默认全部执行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizationOptions {
+    /// 转为大写
+    pub uppercase: bool,
+    /// 去除空白字符
+    pub strip_whitespace: bool,
+    /// 去除比对中的空位 `-`
+    pub strip_gaps: bool,
+    /// 去除数字 (例如 GenBank 序列中的行号)
+    pub strip_digits: bool,
+    /// 按序列类型纠正 T 与 U：DNA 中的 U 改为 T，RNA 中的 T 改为 U，蛋白质不处理
+    pub fix_t_u: bool,
+}
 
-// 重新设计？ 
+impl Default for NormalizationOptions {
+    fn default() -> Self {
+        NormalizationOptions {
+            uppercase: true,
+            strip_whitespace: true,
+            strip_gaps: true,
+            strip_digits: true,
+            fix_t_u: true,
+        }
+    }
+}
+
+// 重新设计？
 // 添加一个密码子表，并赋予一个默认值
 // 然后缓存对应的密码子表？ 如何实现
 
+/// 序列的拓扑结构，序列化时使用小写字符串 `"linear"`、`"circular"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Topology {
+    #[default]
+    Linear,
+    /// 首尾相接，例如 circRNA 与质粒
+    Circular,
+}
+
+/// 双链核酸中的链方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Strand {
+    /// 正链 (+)
+    Forward,
+    /// 负链 (-)
+    Reverse,
+    /// 两条链，用于指定搜索范围；搜索结果中的链总是 `Forward` 或 `Reverse`
+    Both,
+}
+
+impl Strand {
+    /// 作为搜索范围时是否包括 `strand`，`Both` 包括两条链
+    pub fn includes(self, strand: Strand) -> bool {
+        self == Strand::Both || self == strand
+    }
+}
+
+/// 两个序列只有在类型、拓扑结构与内容都相同时才相等，因此可以作为 HashMap 的键
+/// 如果只需要比较序列字符串，请使用 `seq_eq`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sequence {
     pub biotype: BioType,
     pub seq: String,
+    /// 缺省时为线性
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub topology: Topology,
 }
 
 impl Sequence {
+    /// 新建线性序列
     pub fn new(biotype: BioType, seq: String) -> Self {
-        Sequence { biotype, seq }
+        Sequence {
+            biotype,
+            seq,
+            topology: Topology::Linear,
+        }
+    }
+
+    /// 新建环状序列
+    pub fn new_circular(biotype: BioType, seq: String) -> Self {
+        Sequence {
+            biotype,
+            seq,
+            topology: Topology::Circular,
+        }
+    }
+
+    /// 是否为环状序列
+    pub fn is_circular(&self) -> bool {
+        self.topology == Topology::Circular
+    }
+
+    /// 将环状序列的起点移到第 `n` 个字符 (超过长度时取余)，返回同一环状序列的另一种线性表示
+    /// 结果保持原序列的拓扑结构；线性序列同样按首尾相接处理
+    pub fn rotate(&self, n: usize) -> Sequence {
+        let chars: Vec<char> = self.seq.chars().collect();
+        if chars.is_empty() {
+            return self.clone();
+        }
+        let n = n % chars.len();
+        Sequence {
+            biotype: self.biotype.clone(),
+            seq: chars[n..].iter().chain(&chars[..n]).collect(),
+            topology: self.topology,
+        }
+    }
+    /// 新建序列并检查字符是否符合序列类型，见 `validate`
+    pub fn new_validated(biotype: BioType, seq: String) -> Result<Self, ValidationError> {
+        let sequence = Sequence::new(biotype, seq);
+        sequence.validate()?;
+        Ok(sequence)
     }
+
+    /// 按指定的类型解析字符串，并检查字符是否符合该类型
+    pub fn from_str_typed(s: &str, biotype: BioType) -> Result<Self, ValidationError> {
+        Sequence::new_validated(biotype, s.to_string())
+    }
+
+    /// 检查每个字符是否属于序列类型允许的字母表，不区分大小写
+    /// DNA: A C G T 以及 IUPAC 简并碱基；RNA: A C G U 以及同样的简并碱基 (与 `complementary` 支持的一致)；
+    /// 蛋白质: 20 种标准氨基酸以及终止符 *
+    /// 返回第一个非法字符及其位置
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let allowed = |base: char| match self.biotype {
+            BioType::Dna => {
+                matches!(base, 'A' | 'C' | 'G' | 'T') || AmbiguousBase::from_char(base).is_some()
+            }
+            BioType::Rna => {
+                matches!(base, 'A' | 'C' | 'G' | 'U') || AmbiguousBase::from_char(base).is_some()
+            }
+            BioType::Protein => base == '*' || AMINO_ACIDS.contains(base),
+        };
+        match self
+            .seq
+            .chars()
+            .enumerate()
+            .find(|&(_, base)| !allowed(base.to_ascii_uppercase()))
+        {
+            Some((position, base)) => Err(ValidationError {
+                biotype: self.biotype.clone(),
+                position,
+                base,
+            }),
+            None => Ok(()),
+        }
+    }
+
     /// 获取对应索引并返回字符对象，不存在修改
     pub fn index(&self, index: usize) -> char {
         self.seq[index..=index].chars().next().unwrap()
     }
     /// 添加字符
-    pub fn push(&mut self, ch: char) -> () {
+    pub fn push(&mut self, ch: char) {
         self.seq.push(ch);
     }
+    /// 在开头插入 `prefix`，常用于添加标签，不检查字符是否符合序列类型
+    pub fn prepend(&mut self, prefix: &str) {
+        self.seq.insert_str(0, prefix);
+    }
+    /// 在末尾追加 `suffix`，常用于添加标签，不检查字符是否符合序列类型
+    pub fn append(&mut self, suffix: &str) {
+        self.seq.push_str(suffix);
+    }
     /// 用于修改字符串中某位置的某值，如果需要大片段替换请直接操作字符串，因为可能会非常慢
     pub fn change(&mut self, index: usize, ch: char) {
         let mut replaced = String::with_capacity(self.seq.len());
@@ -71,10 +485,519 @@ impl Sequence {
         self.seq.len()
     }
 
+    /// 序列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+
+    /// 截取一段区间作为新的序列，区间以字符位置计算，类型保持不变
+    /// 与切片一致，区间越界或起点大于终点时会 panic
+    pub fn subsequence(&self, range: impl RangeBounds<usize>) -> Sequence {
+        let range = self.byte_range(range);
+        Sequence::new(self.biotype.clone(), self.seq[range].to_string())
+    }
+
+    /// 将字符位置区间转换为字节区间，纯 ASCII 序列两者相同
+    fn byte_range(&self, range: impl RangeBounds<usize>) -> Range<usize> {
+        let char_len = if self.seq.is_ascii() {
+            self.seq.len()
+        } else {
+            self.seq.chars().count()
+        };
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => char_len,
+        };
+        if start > end {
+            panic!("区间起点 {} 大于终点 {}", start, end);
+        }
+        if end > char_len {
+            panic!("区间终点 {} 超出序列长度 {}", end, char_len);
+        }
+        if self.seq.is_ascii() {
+            return start..end;
+        }
+        let offset = |position: usize| {
+            self.seq
+                .char_indices()
+                .nth(position)
+                .map_or(self.seq.len(), |(i, _)| i)
+        };
+        offset(start)..offset(end)
+    }
+
+    /// 从读码框 `frame` (0、1 或 2) 开始依次取出密码子
+    pub fn codons(&self, frame: usize) -> Result<CodonIter<'_>, String> {
+        if frame > 2 {
+            return Err(format!("读码框只能是 0、1 或 2，而不是 {}", frame));
+        }
+        let start = self
+            .seq
+            .char_indices()
+            .nth(frame)
+            .map_or(self.seq.len(), |(i, _)| i);
+        Ok(CodonIter {
+            rest: &self.seq[start..],
+        })
+    }
+
+    /// 读码框 `frame` 中第 `codon_index` 个 (以 0 开始) 密码子
+    /// 读码框不是 0、1 或 2，或者该密码子超出序列范围 (包括末尾不足三个碱基) 时返回错误
+    pub fn codon_at(&self, codon_index: usize, frame: usize) -> Result<String, String> {
+        self.codons(frame)?
+            .nth(codon_index)
+            .map(String::from)
+            .ok_or_else(|| {
+                format!(
+                    "读码框 {} 中没有第 {} 个密码子，序列长度为 {}",
+                    frame,
+                    codon_index,
+                    self.len()
+                )
+            })
+    }
+
+    /// 读码框 `frame` 中的全部密码子，末尾不足三个碱基的部分会被忽略
+    pub fn codon_sequence(&self, frame: usize) -> Result<Vec<String>, String> {
+        Ok(self.codons(frame)?.map(String::from).collect())
+    }
+
+    /// 依次产生 (位置, 字符)，位置以字符计算
+    pub fn iter(&self) -> SeqIter<'_> {
+        SeqIter {
+            chars: self.seq.chars().enumerate(),
+        }
+    }
+
+    /// 从头开始依次产生 (位置, 密码子)，位置为密码子第一个碱基的位置
+    /// 末尾不足三个碱基的部分会被忽略，需要其他读码框时请使用 `codons`
+    pub fn iter_codons(&self) -> IndexedCodonIter<'_> {
+        IndexedCodonIter {
+            chars: self.seq.chars(),
+            position: 0,
+        }
+    }
+
+    /// 切分为长度为 `chunk_size` 的互不重叠的线状片段，最后一段可能较短，与 `Add` 拼接互为逆操作
+    /// 与 `slice::chunks` 一致，`chunk_size` 为 0 时会 panic
+    pub fn chunks(&self, chunk_size: usize) -> Vec<Sequence> {
+        assert!(chunk_size != 0, "片段长度不能为 0");
+        let chars: Vec<char> = self.seq.chars().collect();
+        chars
+            .chunks(chunk_size)
+            .map(|chunk| Sequence::new(self.biotype.clone(), chunk.iter().collect()))
+            .collect()
+    }
+
+    /// 与 `chunks` 相同，但丢弃末尾不足 `chunk_size` 的部分，与 `slice::chunks_exact` 一致
+    pub fn chunks_exact(&self, chunk_size: usize) -> Vec<Sequence> {
+        assert!(chunk_size != 0, "片段长度不能为 0");
+        let chars: Vec<char> = self.seq.chars().collect();
+        chars
+            .chunks_exact(chunk_size)
+            .map(|chunk| Sequence::new(self.biotype.clone(), chunk.iter().collect()))
+            .collect()
+    }
+
+    /// 以步长 `step` 滑动长度为 `size` 的窗口，窗口长度超过序列长度时不产生结果
+    /// 与 `Iterator::step_by` 一致，`step` 为 0 时会 panic
+    pub fn windows(&self, size: usize, step: usize) -> WindowIter<'_> {
+        assert!(step != 0, "滑动窗口的步长不能为 0");
+        let boundaries = self
+            .seq
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(self.seq.len()))
+            .collect();
+        WindowIter {
+            sequence: self,
+            boundaries,
+            start: 0,
+            size,
+            step,
+        }
+    }
+
+    /// 查找 pattern 所有互不重叠的出现位置，不区分大小写
+    /// 环状序列中的匹配可以跨过起点，跨起点的匹配若与开头的匹配重叠则不计入
+    pub fn find_all(&self, pattern: &str) -> Vec<usize> {
+        match self.topology {
+            Topology::Linear => find_positions(&self.seq, pattern, false),
+            Topology::Circular => find_positions_circular(&self.seq, pattern, false),
+        }
+    }
+
+    /// 在 `strand` 指定的链上查找 pattern 所有互不重叠的出现位置，不区分大小写
+    /// 负链上的匹配在反向互补序列中查找，位置以正链坐标表示 (匹配区间在正链上的起点)
+    /// 结果按位置排序，同一位置正链在前；蛋白质序列只查找正链
+    pub fn find_all_on_strand(&self, pattern: &str, strand: Strand) -> Vec<(usize, Strand)> {
+        let mut hits: Vec<(usize, Strand)> = Vec::new();
+        if strand.includes(Strand::Forward) {
+            hits.extend(
+                self.find_all(pattern)
+                    .into_iter()
+                    .map(|p| (p, Strand::Forward)),
+            );
+        }
+        if strand.includes(Strand::Reverse) {
+            if let Ok(reverse) = self.reverse_complementary() {
+                let len = self.seq.chars().count();
+                let pattern_len = pattern.chars().count();
+                // 环状序列中的匹配可以跨过起点，换算后取模
+                hits.extend(
+                    reverse
+                        .find_all(pattern)
+                        .into_iter()
+                        .map(|p| ((2 * len - p - pattern_len) % len, Strand::Reverse)),
+                );
+            }
+        }
+        hits.sort_by_key(|&(position, strand)| (position, strand == Strand::Reverse));
+        hits
+    }
+
+    /// 查找 pattern 的所有出现位置，允许重叠，例如 ATATAT 中的 ATAT 出现在 0 和 2
+    /// 环状序列中的匹配可以跨过起点
+    pub fn find_all_overlapping(&self, pattern: &str) -> Vec<usize> {
+        match self.topology {
+            Topology::Linear => find_positions(&self.seq, pattern, true),
+            Topology::Circular => find_positions_circular(&self.seq, pattern, true),
+        }
+    }
+
+    /// 统计所有长度为 k 的子串 (k-mer) 出现的次数，不区分大小写
+    /// k 为 0 或大于序列长度时返回空表
+    pub fn kmer_count(&self, k: usize) -> HashMap<String, usize> {
+        let chars: Vec<char> = self.seq.chars().map(|c| c.to_ascii_uppercase()).collect();
+        count_kmers(&chars, k)
+    }
+
+    /// k-mer 出现的频率，即次数除以 k-mer 总数，所有频率之和为 1
+    pub fn kmer_frequency(&self, k: usize) -> HashMap<String, f64> {
+        kmer_frequency(self.kmer_count(k))
+    }
+
+    /// 按默认选项规范化序列：转为大写，去除空白、`-` 与数字，并按序列类型纠正 T 与 U
+    pub fn normalize(&mut self) {
+        self.normalize_with(&NormalizationOptions::default());
+    }
+
+    /// 按 `options` 规范化序列
+    pub fn normalize_with(&mut self, options: &NormalizationOptions) {
+        let fix = |c: char| match (&self.biotype, c) {
+            (BioType::Dna, 'U') => 'T',
+            (BioType::Dna, 'u') => 't',
+            (BioType::Rna, 'T') => 'U',
+            (BioType::Rna, 't') => 'u',
+            _ => c,
+        };
+        let seq: String = self
+            .seq
+            .chars()
+            .filter(|c| !(options.strip_whitespace && c.is_whitespace()))
+            .filter(|&c| !(options.strip_gaps && c == '-'))
+            .filter(|c| !(options.strip_digits && c.is_ascii_digit()))
+            .map(|c| {
+                if options.uppercase {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .map(|c| if options.fix_t_u { fix(c) } else { c })
+            .collect();
+        self.seq = seq;
+    }
+
+    /// 返回按默认选项规范化后的新序列，见 `normalize`
+    pub fn normalized(&self) -> Sequence {
+        self.normalized_with(&NormalizationOptions::default())
+    }
+
+    /// 返回按 `options` 规范化后的新序列
+    pub fn normalized_with(&self, options: &NormalizationOptions) -> Sequence {
+        let mut sequence = self.clone();
+        sequence.normalize_with(options);
+        sequence
+    }
+
+    /// 字典序最小的旋转 (Booth 算法，O(n))，作为环状序列的规范形式
+    /// 互为旋转的两条序列得到相同的结果，可用于去重；比较区分大小写，结果保持原序列的拓扑结构
+    /// 只接受 DNA 与 RNA 序列
+    pub fn canonical_rotation(&self) -> Result<Sequence, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("你不能求一段{}序列的规范旋转", self.biotype));
+        }
+        let chars: Vec<char> = self.seq.chars().collect();
+        Ok(self.rotate(least_rotation(&chars)))
+    }
+
+    /// 只比较序列字符串，忽略序列类型
+    pub fn seq_eq(&self, other: &Sequence) -> bool {
+        self.seq == other.seq
+    }
+
     /// 计数
     pub fn count(&self, string: &str) -> usize {
         self.seq.matches(string).count()
     }
+
+    /// 计算 GC 含量，返回 G 与 C 占全部碱基的比例 (0.0 ~ 1.0)，不区分大小写
+    /// 空序列返回 0.0，蛋白质序列返回错误
+    pub fn gc_content(&self) -> Result<f64, String> {
+        match self.biotype {
+            BioType::Dna | BioType::Rna => {
+                if self.is_empty() {
+                    return Ok(0.0);
+                }
+                let gc = self
+                    .seq
+                    .chars()
+                    .filter(|c| matches!(c.to_ascii_uppercase(), 'G' | 'C'))
+                    .count();
+                Ok(gc as f64 / self.seq.chars().count() as f64)
+            }
+            BioType::Protein => Err(format!("你不能计算一段{}序列的GC含量", self.biotype)),
+        }
+    }
+}
+
+/// 翻译时对终止密码子的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StopCodon {
+    /// 遇到终止密码子时停止，结果不含 `*`
+    Exclude,
+    /// 遇到终止密码子时停止，结果以 `*` 结尾
+    Include,
+    /// 翻译整条读码框，终止密码子以 `*` 保留
+    ReadThrough,
+}
+
+impl StopCodon {
+    fn stop(include_stop: bool) -> Self {
+        if include_stop {
+            StopCodon::Include
+        } else {
+            StopCodon::Exclude
+        }
+    }
+}
+
+/// 按读码框依次产生密码子的迭代器，末尾不足三个碱基的部分会被忽略
+/// 由 `Sequence::codons` 创建
+pub struct CodonIter<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for CodonIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut indices = self.rest.char_indices().map(|(i, _)| i).skip(3);
+        let end = match indices.next() {
+            Some(end) => end,
+            None if self.rest.chars().count() == 3 => self.rest.len(),
+            None => {
+                self.rest = "";
+                return None;
+            }
+        };
+        let (codon, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(codon)
+    }
+}
+
+/// 产生 (位置, 字符) 的迭代器，由 `Sequence::iter` 创建
+pub struct SeqIter<'a> {
+    chars: Enumerate<Chars<'a>>,
+}
+
+impl Iterator for SeqIter<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chars.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl<'a> IntoIterator for &'a Sequence {
+    type Item = (usize, char);
+    type IntoIter = SeqIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// 产生 (位置, 密码子) 的迭代器，由 `Sequence::iter_codons` 创建
+pub struct IndexedCodonIter<'a> {
+    chars: Chars<'a>,
+    position: usize,
+}
+
+impl Iterator for IndexedCodonIter<'_> {
+    type Item = (usize, [char; 3]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let codon = [self.chars.next()?, self.chars.next()?, self.chars.next()?];
+        let position = self.position;
+        self.position += 3;
+        Some((position, codon))
+    }
+}
+
+/// 不区分大小写地查找 pattern 在 text 中的全部起点 (字符位置)
+/// `overlapping` 为 false 时匹配之间互不重叠，空的 pattern 没有匹配
+pub(crate) fn find_positions(text: &str, pattern: &str, overlapping: bool) -> Vec<usize> {
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let mut positions = Vec::new();
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return positions;
+    }
+    let mut start = 0;
+    while start + pattern.len() <= text.len() {
+        if text[start..start + pattern.len()] == pattern[..] {
+            positions.push(start);
+            start += if overlapping { 1 } else { pattern.len() };
+        } else {
+            start += 1;
+        }
+    }
+    positions
+}
+
+/// 在首尾相接的序列上查找，只保留起点在序列内的匹配
+/// 不允许重叠时，跨过起点且与第一个匹配重叠的最后一个匹配会被去掉
+pub(crate) fn find_positions_circular(text: &str, pattern: &str, overlapping: bool) -> Vec<usize> {
+    let len = text.chars().count();
+    let pattern_len = pattern.chars().count();
+    if pattern_len == 0 || pattern_len > len {
+        return Vec::new();
+    }
+    let extended: String = text
+        .chars()
+        .chain(text.chars().take(pattern_len - 1))
+        .collect();
+    let mut positions: Vec<usize> = find_positions(&extended, pattern, overlapping)
+        .into_iter()
+        .filter(|&position| position < len)
+        .collect();
+    if !overlapping {
+        if let (Some(&first), Some(&last)) = (positions.first(), positions.last()) {
+            if positions.len() > 1 && last + pattern_len > len + first {
+                positions.pop();
+            }
+        }
+    }
+    positions
+}
+
+/// Booth 算法：字典序最小的旋转的起点
+fn least_rotation(chars: &[char]) -> usize {
+    let n = chars.len();
+    if n == 0 {
+        return 0;
+    }
+    let at = |i: isize| chars[i as usize % n];
+    // failure 为 KMP 失配函数，-1 表示不存在
+    let mut failure: Vec<isize> = vec![-1; 2 * n];
+    let mut k: isize = 0;
+    for j in 1..2 * n as isize {
+        let c = at(j);
+        let mut i = failure[(j - k - 1) as usize];
+        while i != -1 && c != at(k + i + 1) {
+            if c < at(k + i + 1) {
+                k = j - i - 1;
+            }
+            i = failure[i as usize];
+        }
+        if i == -1 && c != at(k + i + 1) {
+            if c < at(k + i + 1) {
+                k = j;
+            }
+            failure[(j - k) as usize] = -1;
+        } else {
+            failure[(j - k) as usize] = i + 1;
+        }
+    }
+    k as usize
+}
+
+/// 统计字符序列中的 k-mer，k 为 0 或大于长度时返回空表
+pub(crate) fn count_kmers(chars: &[char], k: usize) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if k == 0 {
+        return counts;
+    }
+    for kmer in chars.windows(k) {
+        *counts.entry(kmer.iter().collect()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// 将 k-mer 次数转换为频率
+pub(crate) fn kmer_frequency(counts: HashMap<String, usize>) -> HashMap<String, f64> {
+    let total: usize = counts.values().sum();
+    counts
+        .into_iter()
+        .map(|(kmer, count)| (kmer, count as f64 / total as f64))
+        .collect()
+}
+
+/// 滑动窗口迭代器，每次产生一段长度为 size 的子序列
+/// 由 `Sequence::windows` 创建，窗口以字符而不是字节计数
+pub struct WindowIter<'a> {
+    sequence: &'a Sequence,
+    /// 每个字符的字节起点，最后附加序列的字节长度
+    boundaries: Vec<usize>,
+    start: usize,
+    size: usize,
+    step: usize,
+}
+
+impl Iterator for WindowIter<'_> {
+    type Item = Sequence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let char_len = self.boundaries.len() - 1;
+        if self.size == 0 || self.start + self.size > char_len {
+            return None;
+        }
+        let range = self.boundaries[self.start]..self.boundaries[self.start + self.size];
+        self.start += self.step;
+        Some(Sequence::new(
+            self.sequence.biotype.clone(),
+            self.sequence.seq[range].to_string(),
+        ))
+    }
+}
+
+/// 根据字符组成推断类型并解析序列，首尾的空白会被去掉
+/// 类型由 `BioType::infer` 决定，与 fasta/fastq 读取、`SequenceBuilder` 等使用同一规则
+/// 按推断的类型校验后仍含有非法字符时返回错误
+impl FromStr for Sequence {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let biotype = BioType::infer(s);
+        Sequence::from_str_typed(s, biotype).map_err(ParseError::Invalid)
+    }
 }
 
 /// 两个序列可以直接使用加号
@@ -82,10 +1005,7 @@ impl Add for Sequence {
     type Output = Sequence;
     fn add(self, rhs: Self) -> Self::Output {
         if self.biotype == rhs.biotype {
-            Sequence {
-                biotype: self.biotype,
-                seq: self.seq + &rhs.seq,
-            }
+            Sequence::new(self.biotype, self.seq + &rhs.seq)
         } else {
             panic!("类型错误{}加到{}", self.biotype, rhs.biotype);
         }
@@ -96,18 +1016,56 @@ impl<T: Into<String>> Add<T> for Sequence {
     type Output = Self;
 
     fn add(self, rhs: T) -> Self::Output {
-        Sequence {
-            biotype: self.biotype.clone(),
-            seq: self.seq + &rhs.into(),
+        Sequence::new(self.biotype.clone(), self.seq + &rhs.into())
+    }
+}
+
+/// 依次连接 `sequences`，相邻两条序列之间插入 `linker` (如 GS 接头、TEV 酶切位点)，用于设计融合蛋白或多表位构建
+/// 结果为线状序列；任意序列与 `linker` 的类型不同时返回错误，`sequences` 为空时返回空序列
+pub fn join(sequences: &[Sequence], linker: &Sequence) -> Result<Sequence, String> {
+    if let Some(seq) = sequences.iter().find(|seq| seq.biotype != linker.biotype) {
+        return Err(format!(
+            "{} 序列不能与 {} 接头连接",
+            seq.biotype, linker.biotype
+        ));
+    }
+    let joined = sequences
+        .iter()
+        .map(|seq| seq.seq.as_str())
+        .collect::<Vec<&str>>()
+        .join(&linker.seq);
+    Ok(Sequence::new(linker.biotype.clone(), joined))
+}
+
+/// 在末尾追加迭代器产生的字符，不检查字符是否符合序列类型
+/// 序列类型无法只从字符推断，所以没有实现 `FromIterator<char>`，请使用 `SequenceBuilder::chars`
+impl Extend<char> for Sequence {
+    fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
+        self.seq.extend(iter);
+    }
+}
+
+/// `seq[i]` 获取第 i 个字符，与 `index` 方法相同
+/// 只支持 ASCII 字符，越界或遇到非 ASCII 字符时会 panic
+/// String 无法给出单个字符的可变引用，所以没有实现 IndexMut，修改字符请使用 `change`
+impl Index<usize> for Sequence {
+    type Output = char;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let c = Sequence::index(self, index);
+        if !c.is_ascii() {
+            panic!("位置 {} 的字符 '{}' 不是 ASCII 字符", index, c);
         }
+        &ASCII_CHARS[c as usize]
     }
 }
 
+/// `seq[start..end]` 获取字符串切片，区间以字符位置计算，与 `subsequence` 一致
+impl Index<Range<usize>> for Sequence {
+    type Output = str;
 
-/// 直接判断两个序列是否相等，虽然感觉没有用的功能
-impl PartialEq for Sequence {
-    fn eq(&self, other: &Self) -> bool {
-        self.seq == other.seq
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        &self.seq[self.byte_range(range)]
     }
 }
 
@@ -119,45 +1077,173 @@ impl fmt::Display for Sequence {
             .map(|chunk| std::str::from_utf8(chunk).unwrap())
             .collect::<Vec<&str>>()
             .join("\n");
+        let topology = match self.topology {
+            Topology::Linear => "",
+            Topology::Circular => " (circular)",
+        };
         write!(
             f,
-            "Bio Sequence Type is :{}\nSequence:\n{}",
-            self.biotype, fmt_seq
+            "Bio Sequence Type is :{}{}\nSequence:\n{}",
+            self.biotype, topology, fmt_seq
         )
     }
 }
 
 impl Sequence {
-    /// 将序列翻译为蛋白质
+    /// 将序列翻译为蛋白质，遇到终止密码子时停止，使用标准密码子表
+    /// 从第一个字符开始按读码框 0 翻译，不查找起始密码子，需要时请使用 `translate_to_protein`
+    /// 或 `translate_with_starts`；结果中不包含终止符 `*`，需要保留时请使用 `translate_with_stop`
     pub fn translate(&self) -> Result<Sequence, String> {
-        let codon_table: HashMap<&str, &str> = codon::CODON_TABLE.iter().cloned().collect();
-        let seq: String = if self.biotype == BioType::Dna {
-            Self::transcribe(&self).unwrap().seq
-        } else {
-            self.seq.clone().to_uppercase()
+        self.translate_with_stop(false)
+    }
+
+    /// 与 `translate` 相同，`include_stop` 为 true 时在结果末尾保留遇到的终止符 `*`
+    pub fn translate_with_stop(&self, include_stop: bool) -> Result<Sequence, String> {
+        self.translate_frame(0, StopCodon::stop(include_stop))
+    }
+
+    /// 从第 `offset` 个字符开始翻译 (读码框 0、1 或 2)，其余规则与 `translate` 相同
+    /// `offset` 大于 2 时返回错误，更靠后的起点请先用 `subsequence` 截取
+    pub fn translate_from(&self, offset: usize) -> Result<Sequence, String> {
+        if offset > 2 {
+            return Err(format!(
+                "翻译的起点只能是 0、1 或 2，而不是 {}，更靠后的起点请先截取子序列",
+                offset
+            ));
+        }
+        self.translate_frame(offset, StopCodon::Exclude)
+    }
+
+    /// 翻译为蛋白质，遇到终止密码子时停止，结果中不包含终止符
+    /// - `start_from_atg` 为 false 时与 `translate` 相同，从第一个字符开始按读码框 0 翻译，
+    ///   不检查第一个密码子是否为起始密码子，因此结果不一定以 M 开头
+    /// - `start_from_atg` 为 true 时从第一个 ATG (RNA 为 AUG，不区分大小写) 开始翻译，
+    ///   读码框由 ATG 的位置决定，结果总是以 M 开头；序列中没有 ATG 时返回错误
+    ///
+    /// 只识别 ATG，不使用 CTG、TTG 等非经典起始密码子
+    pub fn translate_to_protein(&self, start_from_atg: bool) -> Result<Sequence, String> {
+        if !start_from_atg {
+            return self.translate();
+        }
+        if self.biotype == BioType::Protein {
+            return Err(format!("你不能翻译一段{}序列", BioType::Protein));
+        }
+        let start_codon = match self.biotype {
+            BioType::Rna => "AUG",
+            _ => "ATG",
         };
+        let start = find_positions(&self.seq, start_codon, false)
+            .first()
+            .copied()
+            .ok_or(format!("序列中没有起始密码子 {}", start_codon))?;
+        self.subsequence(start..).translate()
+    }
 
-        match self.biotype {
-            BioType::Dna | BioType::Rna => {
-                let seq_chars: Vec<char> = seq.chars().collect();
-                let mut chunks = seq_chars.chunks(3);
-                let mut protein_seq = String::new();
-                while let Some(chunk) = chunks.next() {
-                    if chunk.len() < 3 {
-                        break;
-                    }
-                    let chunk_str: String = chunk.iter().collect();
-                    let coden = codon_table[&chunk_str[..]];
-                    protein_seq.push_str(coden);
-                    if coden == "*" {
-                        break;
-                    } // 如果遇到终止密码子则提前返回
+    /// 允许非经典起始密码子 (如 GTG、TTG、CTG) 的翻译，遇到终止密码子时停止，结果中不包含终止符
+    /// 起始密码子不区分大小写，T 与 U 视为相同；作为起始密码子的第一个密码子总是翻译为 M
+    /// - `start_from_first_start` 为 true 时从任一起始密码子第一次出现的位置开始翻译，
+    ///   读码框由该位置决定；序列中没有起始密码子时返回错误
+    /// - 为 false 时从第一个字符开始翻译，只有第一个密码子是起始密码子时才改为 M
+    ///
+    /// 蛋白质序列或起始密码子不是三个字符时返回错误
+    pub fn translate_with_starts(
+        &self,
+        start_codons: &[&str],
+        start_from_first_start: bool,
+    ) -> Result<Sequence, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("你不能翻译一段{}序列", BioType::Protein));
+        }
+        let normalize = |c: char| match c.to_ascii_uppercase() {
+            'U' => 'T',
+            other => other,
+        };
+        let starts = start_codons
+            .iter()
+            .map(|codon| {
+                let normalized: Vec<char> = codon.chars().map(normalize).collect();
+                if normalized.len() == 3 {
+                    Ok(normalized)
+                } else {
+                    Err(format!("起始密码子 {} 不是三个字符", codon))
                 }
+            })
+            .collect::<Result<Vec<Vec<char>>, String>>()?;
+        let chars: Vec<char> = self.seq.chars().map(normalize).collect();
+        let is_start = |i: usize| {
+            i + 3 <= chars.len() && starts.iter().any(|codon| codon[..] == chars[i..i + 3])
+        };
+
+        let start = if start_from_first_start {
+            (0..chars.len())
+                .find(|&i| is_start(i))
+                .ok_or(format!("序列中没有起始密码子 {}", start_codons.join("、")))?
+        } else if is_start(0) {
+            0
+        } else {
+            return self.translate();
+        };
+        let rest = self.subsequence(start + 3..).translate()?;
+        Ok(Sequence::new(BioType::Protein, format!("M{}", rest.seq)))
+    }
+
+    /// 使用指定的密码子表翻译序列，遇到终止密码子时停止，结果中不包含终止符
+    pub fn translate_with_table(&self, table: &CodonTable) -> Result<Sequence, String> {
+        self.translate_frame_with_table(table, 0, StopCodon::Exclude)
+    }
+
+    /// 将 DNA 序列按六个读码框翻译
+    /// 前三个为正链从 0、1、2 开始的翻译结果，后三个为反向互补链的对应结果
+    /// 与 `translate` 不同，这里会翻译整条读码框，终止密码子以 `*` 保留在结果中
+    pub fn translate_6frame(&self) -> Result<[Sequence; 6], String> {
+        if self.biotype != BioType::Dna {
+            return Err(format!("只能对 {} 序列进行六框翻译", BioType::Dna));
+        }
+        let reverse = self.reverse_complementary()?;
+        Ok([
+            self.translate_frame(0, StopCodon::ReadThrough)?,
+            self.translate_frame(1, StopCodon::ReadThrough)?,
+            self.translate_frame(2, StopCodon::ReadThrough)?,
+            reverse.translate_frame(0, StopCodon::ReadThrough)?,
+            reverse.translate_frame(1, StopCodon::ReadThrough)?,
+            reverse.translate_frame(2, StopCodon::ReadThrough)?,
+        ])
+    }
+
+    /// 使用标准密码子表从读码框 `frame` 开始翻译
+    pub(crate) fn translate_frame(
+        &self,
+        frame: usize,
+        stop: StopCodon,
+    ) -> Result<Sequence, String> {
+        self.translate_frame_with_table(&CodonTable::standard(), frame, stop)
+    }
 
-                Ok(Sequence::new(BioType::Protein, seq))
+    /// 从读码框 `frame` 开始翻译，终止密码子按 `stop` 处理
+    /// 密码子表中不存在的密码子 (例如含有 N) 翻译为 X
+    pub(crate) fn translate_frame_with_table(
+        &self,
+        table: &CodonTable,
+        frame: usize,
+        stop: StopCodon,
+    ) -> Result<Sequence, String> {
+        if self.biotype == BioType::Protein {
+            return Err(format!("你不能翻译一段{}序列", BioType::Protein));
+        }
+
+        let mut protein_seq = String::new();
+        for codon in self.codons(frame)? {
+            let amino_acid = table.translate_codon(codon).unwrap_or('X');
+            match (amino_acid, stop) {
+                ('*', StopCodon::Exclude) => break,
+                ('*', StopCodon::Include) => {
+                    protein_seq.push(amino_acid);
+                    break;
+                }
+                _ => protein_seq.push(amino_acid),
             }
-            BioType::Protein => Err(format!("你不能翻译一段{}序列", BioType::Protein)),
         }
+        Ok(Sequence::new(BioType::Protein, protein_seq))
     }
 
     /// 将DNA序列转录为RNA
@@ -167,64 +1253,600 @@ impl Sequence {
                 let seq: String = self.seq.clone().to_uppercase().replace("T", "U");
                 Ok(Sequence {
                     biotype: BioType::Rna,
-                    seq: seq,
+                    seq,
+                    topology: self.topology,
                 })
             }
             BioType::Protein | BioType::Rna => Err(format!("你不能转录一段{}序列", self.biotype)),
         }
     }
 
+    /// 将RNA序列逆转录为DNA
     pub fn back_transcription(&self) -> Result<Sequence, String> {
         match self.biotype {
             BioType::Rna => {
                 let seq = self.seq.clone().to_uppercase().replace("U", "T");
                 Ok(Sequence {
-                    biotype: BioType::Rna,
-                    seq: seq,
+                    biotype: BioType::Dna,
+                    seq,
+                    topology: self.topology,
                 })
             }
             BioType::Protein | BioType::Dna => Err(format!("你不能逆转录一段{}序列", self.biotype)),
         }
     }
 
-    /// 获得一段序列的互补序列 DNA 或 RNA
+    /// 获得一段序列的互补序列 DNA 或 RNA，结果保持原序列的拓扑结构
+    /// 支持 IUPAC 简并碱基，例如 N 互补为 N、R 互补为 Y
+    /// 保持碱基的大小写，软屏蔽 (小写) 的区域互补后仍为小写
     pub fn complementary(&self) -> Result<Sequence, String> {
-        match self.biotype {
-            BioType::Dna => {
-                let pairing_table: HashMap<char, char> = DNA_BASE_PAIRING.iter().cloned().collect();
-                let seq = self.seq.clone().to_uppercase();
-                let mut complement = String::with_capacity(seq.len());
-
-                for base in seq.chars() {
-                    match pairing_table.get(&base) {
-                        Some(&complement_base) => complement.push(complement_base),
-                        None => return Err(format!("Invalid DNA base: {}", base)),
-                    }
-                }
-                Ok(Sequence::new(self.biotype.clone(), complement))
-            }
-            BioType::Rna => {
-                let pairing_table: HashMap<char, char> = RNA_BASE_PAIRING.iter().cloned().collect();
-
-                let seq = self.seq.clone().to_uppercase();
-                let mut complement = String::with_capacity(seq.len());
+        let pairing = match self.biotype {
+            BioType::Dna => DNA_BASE_PAIRING,
+            BioType::Rna => RNA_BASE_PAIRING,
+            BioType::Protein => return Err(format!("你不能反向互补一段 {} 序列", self.biotype)),
+        };
+        let pairing_table: HashMap<char, char> = pairing.iter().cloned().collect();
+        let mut complement = String::with_capacity(self.seq.len());
 
-                for base in seq.chars() {
-                    match pairing_table.get(&base) {
-                        Some(&complement_base) => complement.push(complement_base),
-                        None => return Err(format!("Invalid RNA base: {}", base)),
-                    }
-                }
-                Ok(Sequence::new(self.biotype.clone(), complement))
+        for original in self.seq.chars() {
+            let base = original.to_ascii_uppercase();
+            let complement_base = match pairing_table.get(&base) {
+                Some(&complement_base) => complement_base,
+                None => match AmbiguousBase::from_char(base) {
+                    Some(ambiguous) => ambiguous.complement().to_char(),
+                    None => return Err(format!("Invalid {} base: {}", self.biotype, original)),
+                },
+            };
+            if original.is_ascii_lowercase() {
+                complement.push(complement_base.to_ascii_lowercase());
+            } else {
+                complement.push(complement_base);
             }
-            BioType::Protein => Err(format!("你不能反向互补一段 {} 序列", self.biotype)),
+        }
+        Ok(Sequence {
+            biotype: self.biotype.clone(),
+            seq: complement,
+            topology: self.topology,
+        })
+    }
+
+    /// 序列中是否含有 IUPAC 简并碱基，蛋白质序列总是返回 false
+    pub fn is_ambiguous(&self) -> bool {
+        match self.biotype {
+            BioType::Dna | BioType::Rna => self
+                .seq
+                .chars()
+                .any(|c| AmbiguousBase::from_char(c).is_some()),
+            BioType::Protein => false,
         }
     }
 
-    /// 获得一段序列的反向互补序列 DNA 或 RNA
+    /// 获得一段序列的反向互补序列 DNA 或 RNA，结果保持原序列的拓扑结构
     pub fn reverse_complementary(&self) -> Result<Sequence, String> {
-        let mut sequence = Self::complementary(&self)?;
+        let mut sequence = Self::complementary(self)?;
         sequence.seq = sequence.seq.chars().rev().collect();
         Ok(sequence)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gc_content() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGCgcAT"));
+        assert_eq!(dna.gc_content().unwrap(), 0.5);
+        let rna = Sequence::new(BioType::Rna, String::from("GGCU"));
+        assert_eq!(rna.gc_content().unwrap(), 0.75);
+        let empty = Sequence::new(BioType::Dna, String::new());
+        assert_eq!(empty.gc_content().unwrap(), 0.0);
+        let protein = Sequence::new(BioType::Protein, String::from("MGC"));
+        assert!(protein.gc_content().is_err());
+    }
+
+    #[test]
+    fn back_transcription() {
+        let rna = Sequence::new(BioType::Rna, String::from("AUGCUUAG"));
+        let dna = rna.back_transcription().unwrap();
+        assert_eq!(dna.biotype, BioType::Dna);
+        assert_eq!(dna.seq, "ATGCTTAG");
+        let round_trip = dna.transcribe().unwrap();
+        assert_eq!(round_trip.biotype, BioType::Rna);
+        assert_eq!(round_trip.seq, rna.seq);
+        assert!(dna.back_transcription().is_err());
+    }
+
+    #[test]
+    fn index_operators() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGTN"));
+        assert_eq!(dna[0], 'A');
+        assert_eq!(dna[4], 'N');
+        assert_eq!(&dna[1..3], "CG");
+        assert_eq!(&dna[5..5], "");
+    }
+
+    #[test]
+    fn position_iterators() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGGCCTA"));
+        let gc: Vec<usize> = dna
+            .iter()
+            .filter(|&(_, base)| base == 'G' || base == 'C')
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(gc, vec![2, 3, 4, 5]);
+        assert_eq!((&dna).into_iter().count(), 8);
+        let codons: Vec<(usize, [char; 3])> = dna.iter_codons().collect();
+        assert_eq!(codons, vec![(0, ['A', 'T', 'G']), (3, ['G', 'C', 'C'])]);
+    }
+
+    #[test]
+    fn parse_sequences() {
+        let dna: Sequence = "ATCGATCG".parse().unwrap();
+        assert_eq!(dna.biotype, BioType::Dna);
+        let rna: Sequence = " acgu\n".parse().unwrap();
+        assert_eq!(rna.biotype, BioType::Rna);
+        assert_eq!(rna.seq, "acgu");
+        let protein: Sequence = "MKVL*".parse().unwrap();
+        assert_eq!(protein.biotype, BioType::Protein);
+        for peptide in ["MKV", "MKVHRDSW"] {
+            let protein: Sequence = peptide.parse().unwrap();
+            assert_eq!(protein.biotype, BioType::Protein);
+        }
+        let dna: Sequence = "ACGTN".parse().unwrap();
+        assert_eq!(dna.biotype, BioType::Dna);
+        // 与 fasta 读取等使用的 BioType::infer 结果一致
+        for text in ["ACGTACGTACGTACGTACGTR", "ACGUACGUYN", "MKVHRDSW"] {
+            let parsed: Sequence = text.parse().unwrap();
+            assert_eq!(parsed.biotype, BioType::infer(text));
+        }
+        let ambiguous: Sequence = "ACGTACGTACGTACGTACGTR".parse().unwrap();
+        assert_eq!(ambiguous.biotype, BioType::Dna);
+        assert_eq!("".parse::<Sequence>(), Err(ParseError::Empty));
+        assert!(matches!(
+            "ACGT-12".parse::<Sequence>(),
+            Err(ParseError::Invalid(ValidationError { position: 4, .. }))
+        ));
+
+        assert!(Sequence::from_str_typed("ACGU", BioType::Rna).is_ok());
+        let err = Sequence::from_str_typed("ACGU", BioType::Dna).unwrap_err();
+        assert_eq!((err.position, err.base), (3, 'U'));
+    }
+
+    #[test]
+    fn translate_from_start_codon() {
+        let mrna = Sequence::new(BioType::Rna, String::from("GCCACCaugGCUUAAGG"));
+        assert_eq!(mrna.translate_to_protein(false).unwrap().seq, "ATMA");
+        assert_eq!(mrna.translate_to_protein(true).unwrap().seq, "MA");
+        let dna = Sequence::new(BioType::Dna, String::from("CCATGAAATGA"));
+        assert_eq!(dna.translate_to_protein(true).unwrap().seq, "MK");
+        assert!(Sequence::new(BioType::Dna, String::from("CCCGGG"))
+            .translate_to_protein(true)
+            .is_err());
+
+        // CTG 通常编码 Leu，作为起始密码子时翻译为 M
+        let circ = Sequence::new(BioType::Rna, String::from("GCcugAAAUGGUAAGG"));
+        assert_eq!(
+            circ.translate_with_starts(&["ATG", "CTG"], true)
+                .unwrap()
+                .seq,
+            "MKW"
+        );
+        assert_eq!(
+            circ.translate_with_starts(&["AUG"], true).unwrap().seq,
+            "MVR"
+        );
+        assert_eq!(
+            circ.translate_with_starts(&["GTG"], false).unwrap().seq,
+            circ.translate().unwrap().seq
+        );
+        assert_eq!(
+            circ.subsequence(2..)
+                .translate_with_starts(&["CTG"], false)
+                .unwrap()
+                .seq,
+            "MKW"
+        );
+        assert!(circ.translate_with_starts(&["TTG"], true).is_err());
+        assert!(circ.translate_with_starts(&["AT"], true).is_err());
+    }
+
+    #[test]
+    fn ambiguous_complement() {
+        let dna = Sequence::new(BioType::Dna, String::from("ANRYSWKMBDHV"));
+        assert!(dna.is_ambiguous());
+        assert_eq!(dna.complementary().unwrap().seq, "TNYRSWMKVHDB");
+        assert_eq!(dna.reverse_complementary().unwrap().seq, "BDHVKMWSRYNT");
+        let rna = Sequence::new(BioType::Rna, String::from("AUNg"));
+        assert_eq!(rna.complementary().unwrap().seq, "UANc");
+        assert!(!Sequence::new(BioType::Dna, String::from("ACGT")).is_ambiguous());
+        assert!(Sequence::new(BioType::Dna, String::from("ACXT"))
+            .complementary()
+            .is_err());
+    }
+
+    #[test]
+    fn infer_biotype() {
+        assert_eq!(BioType::infer("ACGTN"), BioType::Dna);
+        assert_eq!(BioType::infer("acgu"), BioType::Rna);
+        assert_eq!(BioType::infer("MKVLE"), BioType::Protein);
+        assert_eq!(BioType::infer("ACGTU"), BioType::Protein);
+        // 只由 IUPAC 字母组成、没有终止符的短肽
+        assert_eq!(BioType::infer("MKV"), BioType::Protein);
+        assert_eq!(BioType::infer("MKVHRDSW"), BioType::Protein);
+        assert_eq!(BioType::infer("GAWKR"), BioType::Protein);
+        // 少量简并碱基的核酸
+        assert_eq!(BioType::infer("ACGTRACGTACGTACGTACG"), BioType::Dna);
+        assert_eq!(BioType::infer("ACGUACGUYN"), BioType::Rna);
+        assert_eq!(BioType::infer(""), BioType::Dna);
+    }
+
+    #[test]
+    fn subsequence() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGTACGT"));
+        assert_eq!(dna.subsequence(2..5).seq, "GTA");
+        assert_eq!(dna.subsequence(..=1).seq, "AC");
+        assert_eq!(dna.subsequence(6..).seq, "GT");
+        assert_eq!(dna.subsequence(..).seq, dna.seq);
+        assert_eq!(dna.subsequence(8..).seq, "");
+        assert_eq!(dna.subsequence(1..3).biotype, BioType::Dna);
+        let unicode = Sequence::new(BioType::Protein, String::from("MéKV"));
+        assert_eq!(unicode.subsequence(1..3).seq, "éK");
+    }
+
+    #[test]
+    #[should_panic]
+    fn subsequence_out_of_bounds() {
+        Sequence::new(BioType::Dna, String::from("ACGT")).subsequence(2..5);
+    }
+
+    #[test]
+    fn codons() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGAAACCCGG"));
+        let frame0: Vec<&str> = dna.codons(0).unwrap().collect();
+        assert_eq!(frame0, vec!["ATG", "AAA", "CCC"]);
+        let frame1: Vec<&str> = dna.codons(1).unwrap().collect();
+        assert_eq!(frame1, vec!["TGA", "AAC", "CCG"]);
+        let frame2: Vec<&str> = dna.codons(2).unwrap().collect();
+        assert_eq!(frame2, vec!["GAA", "ACC", "CGG"]);
+        assert!(dna.codons(3).is_err());
+        let short = Sequence::new(BioType::Dna, String::from("A"));
+        assert_eq!(short.codons(2).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn translate_6frame() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGGCCTAAGGT"));
+        assert_eq!(dna.translate().unwrap().seq, "MA");
+        let frames = dna.translate_6frame().unwrap();
+        let proteins: Vec<&str> = frames.iter().map(|s| s.seq.as_str()).collect();
+        assert_eq!(proteins, vec!["MA*G", "WPK", "GLR", "TLGH", "P*A", "LRP"]);
+        assert!(frames.iter().all(|s| s.biotype == BioType::Protein));
+        let rna = Sequence::new(BioType::Rna, String::from("AUGGCC"));
+        assert!(rna.translate_6frame().is_err());
+    }
+
+    #[test]
+    fn translate_with_table() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGTGAATAAGA"));
+        assert_eq!(dna.translate().unwrap().seq, "M");
+        let mito = CodonTable::from_ncbi_id(2).unwrap();
+        assert_eq!(dna.translate_with_table(&mito).unwrap().seq, "MWM");
+        let rna = Sequence::new(BioType::Rna, String::from("augnnnuaa"));
+        assert_eq!(rna.translate().unwrap().seq, "MX");
+    }
+
+    #[test]
+    fn translate_returns_protein() {
+        // translate 曾经把输入的核酸序列原样放进蛋白质序列中返回
+        let dna = Sequence::new(BioType::Dna, String::from("ATGGCC"));
+        let protein = dna.translate().unwrap();
+        assert_eq!(protein.seq, "MA");
+        assert_eq!(protein.biotype, BioType::Protein);
+    }
+
+    #[test]
+    fn translate_known_coding_sequences() {
+        // 人胰岛素 B 链的编码序列，以 TAA 结尾
+        let insulin_b = Sequence::new(
+            BioType::Dna,
+            String::from(
+                "TTTGTGAACCAACACCTGTGCGGCTCACACCTGGTGGAAGCTCTCTACCTAGTGTGCGGGGAACGAGGCTTCTTCTACACACCCAAGACCTAA",
+            ),
+        );
+        let protein = insulin_b.translate().unwrap();
+        assert_eq!(protein.biotype, BioType::Protein);
+        assert_eq!(protein.seq, "FVNQHLCGSHLVEALYLVCGERGFFYTPKT");
+        assert_eq!(
+            insulin_b.translate_with_stop(true).unwrap().seq,
+            "FVNQHLCGSHLVEALYLVCGERGFFYTPKT*"
+        );
+        // 没有终止密码子时两者一致，末尾不足一个密码子的碱基被忽略
+        let gfp_start = Sequence::new(
+            BioType::Rna,
+            String::from("AUGGUGAGCAAGGGCGAGGAGCUGUUCACCGG"),
+        );
+        assert_eq!(gfp_start.translate().unwrap().seq, "MVSKGEELFT");
+        assert_eq!(
+            gfp_start.translate_with_stop(true).unwrap(),
+            gfp_start.translate().unwrap()
+        );
+        // 终止密码子之后的序列不再翻译
+        let early_stop = Sequence::new(BioType::Dna, String::from("ATGTAGATGAAA"));
+        assert_eq!(early_stop.translate().unwrap().seq, "M");
+        assert_eq!(early_stop.translate_with_stop(true).unwrap().seq, "M*");
+        assert!(Sequence::new(BioType::Protein, String::from("MK"))
+            .translate_with_stop(true)
+            .is_err());
+    }
+
+    #[test]
+    fn validate() {
+        assert!(Sequence::new(BioType::Dna, String::from("ACGTNRYacgt"))
+            .validate()
+            .is_ok());
+        let error = Sequence::new(BioType::Dna, String::from("AUGCX"))
+            .validate()
+            .unwrap_err();
+        assert_eq!((error.position, error.base), (1, 'U'));
+        assert!(Sequence::new(BioType::Rna, String::from("ACGUN"))
+            .validate()
+            .is_ok());
+        // RNA 同样接受 IUPAC 简并碱基，能被 complementary 处理的序列也能通过检查
+        let ambiguous = Sequence::new(BioType::Rna, String::from("ACGRYKMSWBDHVn"));
+        assert!(ambiguous.validate().is_ok());
+        assert!(ambiguous.complementary().unwrap().validate().is_ok());
+        let error = Sequence::new(BioType::Rna, String::from("ACGT"))
+            .validate()
+            .unwrap_err();
+        assert_eq!((error.position, error.base), (3, 'T'));
+        assert!(Sequence::new(BioType::Protein, String::from("MKWV*"))
+            .validate()
+            .is_ok());
+        let error = Sequence::new_validated(BioType::Protein, String::from("MKB")).unwrap_err();
+        assert_eq!((error.position, error.base), (2, 'B'));
+    }
+
+    #[test]
+    fn biotype_from_str() {
+        assert_eq!(BioType::try_from("dna"), Ok(BioType::Dna));
+        assert_eq!(BioType::try_from("RNA"), Ok(BioType::Rna));
+        assert_eq!(BioType::try_from("Protein"), Ok(BioType::Protein));
+        assert!(BioType::try_from("peptide").is_err());
+    }
+
+    #[test]
+    fn equality_and_hash() {
+        use std::collections::HashSet;
+
+        let dna = Sequence::new(BioType::Dna, String::from("AUGC"));
+        let rna = Sequence::new(BioType::Rna, String::from("AUGC"));
+        assert_ne!(dna, rna);
+        assert!(dna.seq_eq(&rna));
+        assert_eq!(dna, Sequence::new(BioType::Dna, String::from("AUGC")));
+
+        let set: HashSet<Sequence> = [dna.clone(), rna, dna].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn windows() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGTAC"));
+        let windows: Vec<String> = dna.windows(3, 2).map(|w| w.seq).collect();
+        assert_eq!(windows, vec!["ACG", "GTA"]);
+        assert_eq!(dna.windows(6, 1).count(), 1);
+        assert_eq!(dna.windows(7, 1).count(), 0);
+        assert!(dna.windows(2, 1).all(|w| w.biotype == BioType::Dna));
+        let gc: Vec<f64> = dna.windows(2, 2).map(|w| w.gc_content().unwrap()).collect();
+        assert_eq!(gc, vec![0.5, 0.5, 0.5]);
+        let unicode = Sequence::new(BioType::Protein, String::from("MéK"));
+        let windows: Vec<String> = unicode.windows(2, 1).map(|w| w.seq).collect();
+        assert_eq!(windows, vec!["Mé", "éK"]);
+    }
+
+    #[test]
+    fn kmer_count() {
+        let dna = Sequence::new(BioType::Dna, String::from("ACGTacg"));
+        let counts = dna.kmer_count(3);
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts["ACG"], 2);
+        assert_eq!(counts["CGT"], 1);
+        assert!(dna.kmer_count(8).is_empty());
+        assert!(dna.kmer_count(0).is_empty());
+        let total: f64 = dna.kmer_frequency(2).values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn find_all() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATATATgaattc"));
+        assert_eq!(dna.find_all("ATAT"), vec![0]);
+        assert_eq!(dna.find_all_overlapping("atat"), vec![0, 2]);
+        assert_eq!(dna.find_all("GAATTC"), vec![6]);
+        assert_eq!(dna.find_all("AT"), vec![0, 2, 4, 8]);
+        assert!(dna.find_all("").is_empty());
+        assert!(dna.find_all("CCC").is_empty());
+    }
+
+    #[test]
+    fn translate_from_offset() {
+        let dna = Sequence::new(BioType::Dna, String::from("GCATGGCCTAAGG"));
+        assert_eq!(dna.translate_from(0).unwrap().seq, "AWPK");
+        assert_eq!(dna.translate_from(2).unwrap().seq, "MA");
+        assert_eq!(dna.translate_from(1).unwrap().seq, "HGLR");
+        assert!(dna.translate_from(3).is_err());
+        let short = Sequence::new(BioType::Rna, String::from("A"));
+        assert_eq!(short.translate_from(2).unwrap().seq, "");
+    }
+
+    #[test]
+    fn normalize_sequence() {
+        let mut dna = Sequence::new(BioType::Dna, String::from(" 1 acgu-acg\tu\n61 NN"));
+        dna.normalize();
+        assert_eq!(dna.seq, "ACGTACGTNN");
+        let rna = Sequence::new(BioType::Rna, String::from("acgt-t"));
+        assert_eq!(rna.normalized().seq, "ACGUU");
+        let protein = Sequence::new(BioType::Protein, String::from("mt-ku"));
+        assert_eq!(protein.normalized().seq, "MTKU");
+
+        let options = NormalizationOptions {
+            uppercase: false,
+            strip_gaps: false,
+            ..Default::default()
+        };
+        assert_eq!(rna.normalized_with(&options).seq, "acgu-u");
+        assert_eq!(rna.seq, "acgt-t");
+    }
+
+    #[test]
+    fn circular_topology() {
+        let circular = Sequence::new_circular(BioType::Dna, String::from("TTACGA"));
+        let linear = Sequence::new(BioType::Dna, String::from("TTACGA"));
+        assert!(circular.is_circular());
+        assert_ne!(circular, linear);
+        assert_eq!(circular.len(), 6);
+        // GATT 跨过起点
+        assert_eq!(circular.find_all("GATT"), vec![4]);
+        assert!(linear.find_all("GATT").is_empty());
+        assert_eq!(circular.find_all_overlapping("AT"), vec![5]);
+
+        let rc = circular.reverse_complementary().unwrap();
+        assert_eq!(rc.seq, "TCGTAA");
+        assert_eq!(rc.topology, Topology::Circular);
+        assert_eq!(circular.transcribe().unwrap().topology, Topology::Circular);
+
+        assert_eq!(circular.rotate(2).seq, "ACGATT");
+        assert_eq!(circular.rotate(8), circular.rotate(2));
+        assert!(circular.rotate(2).is_circular());
+        assert!(circular
+            .to_string()
+            .starts_with("Bio Sequence Type is :DNA (circular)\n"));
+        assert!(linear
+            .to_string()
+            .starts_with("Bio Sequence Type is :DNA\n"));
+    }
+
+    #[test]
+    fn canonical_rotation() {
+        let a = Sequence::new_circular(BioType::Rna, String::from("GCAUAC"));
+        let b = a.rotate(4);
+        assert_eq!(a.canonical_rotation().unwrap().seq, "ACGCAU");
+        assert_eq!(a.canonical_rotation(), b.canonical_rotation());
+        let repeated = Sequence::new_circular(BioType::Dna, String::from("TATA"));
+        assert_eq!(repeated.canonical_rotation().unwrap().seq, "ATAT");
+        let empty = Sequence::new_circular(BioType::Dna, String::new());
+        assert_eq!(empty.canonical_rotation().unwrap().seq, "");
+        assert!(Sequence::new(BioType::Protein, String::from("MKV"))
+            .canonical_rotation()
+            .is_err());
+        // 与逐个比较所有旋转的结果一致
+        for bits in 0..1u32 << 10 {
+            let seq: String = (0..10)
+                .map(|i| if bits >> i & 1 == 1 { 'C' } else { 'A' })
+                .collect();
+            let dna = Sequence::new_circular(BioType::Dna, seq);
+            let brute = (0..dna.len()).map(|i| dna.rotate(i).seq).min().unwrap();
+            assert_eq!(dna.canonical_rotation().unwrap().seq, brute);
+        }
+    }
+
+    #[test]
+    fn codon_level_access() {
+        let dna = Sequence::new(BioType::Dna, String::from("ATGGCCTAAG"));
+        assert_eq!(dna.codon_at(0, 0).unwrap(), "ATG");
+        assert_eq!(dna.codon_at(2, 0).unwrap(), "TAA");
+        assert!(dna.codon_at(3, 0).is_err());
+        assert_eq!(dna.codon_at(2, 1).unwrap(), "AAG");
+        assert!(dna.codon_at(0, 3).is_err());
+        assert_eq!(dna.codon_sequence(0).unwrap(), vec!["ATG", "GCC", "TAA"]);
+        assert_eq!(dna.codon_sequence(2).unwrap(), vec!["GGC", "CTA"]);
+        assert!(dna.codon_sequence(3).is_err());
+    }
+
+    #[test]
+    fn sequence_chunks() {
+        let rna = Sequence::new_circular(BioType::Rna, String::from("ACGUACGUAC"));
+        let chunks = rna.chunks(4);
+        let pieces: Vec<&str> = chunks.iter().map(|chunk| chunk.seq.as_str()).collect();
+        assert_eq!(pieces, vec!["ACGU", "ACGU", "AC"]);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.biotype == BioType::Rna && !chunk.is_circular()));
+        let joined = chunks
+            .into_iter()
+            .reduce(|joined, chunk| joined + chunk)
+            .unwrap();
+        assert_eq!(joined.seq, rna.seq);
+
+        let exact = rna.chunks_exact(4);
+        assert_eq!(exact.len(), 2);
+        assert_eq!(exact[1].seq, "ACGU");
+        assert!(Sequence::new(BioType::Dna, String::new())
+            .chunks(3)
+            .is_empty());
+    }
+
+    #[test]
+    fn find_on_both_strands() {
+        // GAATTC 是回文，两条链上都能找到；反向互补为 CTTTG 的 CAAAG 只在负链上
+        let dna = Sequence::new(BioType::Dna, String::from("CAAAGGAATTCTT"));
+        assert_eq!(
+            dna.find_all_on_strand("gaattc", Strand::Both),
+            vec![(5, Strand::Forward), (5, Strand::Reverse)]
+        );
+        assert_eq!(
+            dna.find_all_on_strand("CTTTG", Strand::Both),
+            vec![(0, Strand::Reverse)]
+        );
+        assert!(dna.find_all_on_strand("CTTTG", Strand::Forward).is_empty());
+
+        // 环状序列中跨过起点的负链匹配
+        let circular = Sequence::new_circular(BioType::Dna, String::from("AGGGGGGCA"));
+        assert_eq!(
+            circular.find_all_on_strand("TTGC", Strand::Reverse),
+            vec![(6, Strand::Reverse)]
+        );
+        let protein = Sequence::new(BioType::Protein, String::from("MKKM"));
+        assert_eq!(
+            protein.find_all_on_strand("KK", Strand::Both),
+            vec![(1, Strand::Forward)]
+        );
+    }
+
+    #[test]
+    fn extend_from_chars() {
+        let mut dna = Sequence::new(BioType::Dna, String::from("ATG"));
+        dna.extend("GCC".chars());
+        dna.extend(['T', 'A', 'A']);
+        assert_eq!(dna.seq, "ATGGCCTAA");
+        let reverse = dna.reverse_complementary().unwrap();
+        let mut joined = Sequence::new(BioType::Dna, String::new());
+        joined.extend(
+            dna.iter()
+                .map(|(_, c)| c)
+                .chain(reverse.iter().map(|(_, c)| c)),
+        );
+        assert_eq!(joined.seq, "ATGGCCTAATTAGGCCAT");
+    }
+
+    #[test]
+    fn join_with_linker() {
+        let linker = Sequence::new(BioType::Protein, String::from("GGGGS"));
+        let epitopes = [
+            Sequence::new(BioType::Protein, String::from("SIINFEKL")),
+            Sequence::new_circular(BioType::Protein, String::from("GILGFVFTL")),
+            Sequence::new(BioType::Protein, String::from("NLVPMVATV")),
+        ];
+        let mut fusion = join(&epitopes, &linker).unwrap();
+        assert_eq!(fusion.seq, "SIINFEKLGGGGSGILGFVFTLGGGGSNLVPMVATV");
+        assert!(!fusion.is_circular());
+        fusion.prepend("M");
+        fusion.append("HHHHHH");
+        assert_eq!(fusion.seq, "MSIINFEKLGGGGSGILGFVFTLGGGGSNLVPMVATVHHHHHH");
+
+        assert_eq!(join(&epitopes[..1], &linker).unwrap().seq, "SIINFEKL");
+        assert!(join(&[], &linker).unwrap().is_empty());
+        let dna = Sequence::new(BioType::Dna, String::from("ATG"));
+        assert!(join(&[dna], &linker).is_err());
+    }
+}
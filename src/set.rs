@@ -0,0 +1,353 @@
+use super::distance::levenshtein_within;
+use super::fasta::{self, FastaError};
+use super::record::NamedSequence;
+use super::sequence::{BioType, Sequence};
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::path::Path;
+
+// 序列集合，以及对整个集合的批量操作
+
+/// 保持加入顺序的序列记录集合，可以像切片一样遍历
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct SequenceSet {
+    records: Vec<NamedSequence>,
+}
+
+impl SequenceSet {
+    /// 新建空的集合
+    pub fn new() -> Self {
+        SequenceSet::default()
+    }
+
+    /// 读取 fasta 文件中的全部记录
+    pub fn from_fasta(path: impl AsRef<Path>) -> Result<SequenceSet, FastaError> {
+        fasta::read(path).map(SequenceSet::from)
+    }
+
+    /// 加入一条记录
+    pub fn add(&mut self, seq: NamedSequence) {
+        self.records.push(seq);
+    }
+
+    /// 按 id 查找记录，id 重复时返回第一条
+    pub fn get_by_id(&self, id: &str) -> Option<&NamedSequence> {
+        self.records.iter().find(|record| record.id == id)
+    }
+
+    /// 返回满足条件的记录组成的新集合
+    pub fn filter<F: Fn(&NamedSequence) -> bool>(&self, predicate: F) -> SequenceSet {
+        self.records
+            .iter()
+            .filter(|record| predicate(record))
+            .cloned()
+            .collect()
+    }
+
+    /// 按序列长度从长到短排序，长度相同时保持原有顺序
+    pub fn sort_by_length(&mut self) {
+        self.records
+            .sort_by_key(|record| std::cmp::Reverse(record.sequence.len()));
+    }
+
+    /// 去掉序列完全相同 (包括类型与拓扑结构) 的记录，只保留第一次出现的记录
+    pub fn deduplicate(&mut self) {
+        let mut seen: HashSet<Sequence> = HashSet::new();
+        self.records
+            .retain(|record| seen.insert(record.sequence.clone()));
+    }
+
+    /// 所有序列的长度之和
+    pub fn total_length(&self) -> usize {
+        self.records
+            .iter()
+            .map(|record| record.sequence.len())
+            .sum()
+    }
+
+    /// N50：按长度从长到短累加，累计长度达到总长度一半时的序列长度，空集合为 0
+    pub fn n50(&self) -> usize {
+        nx(&self.lengths_descending(), 50).0
+    }
+
+    /// 所有序列的长度，从长到短排列
+    fn lengths_descending(&self) -> Vec<usize> {
+        let mut lengths: Vec<usize> = self
+            .records
+            .iter()
+            .map(|record| record.sequence.len())
+            .collect();
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        lengths
+    }
+
+    /// 取出全部记录
+    pub fn into_vec(self) -> Vec<NamedSequence> {
+        self.records
+    }
+}
+
+/// 组装结果 (contig 或 scaffold 集合) 的长度统计
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssemblyStats {
+    pub total_length: usize,
+    pub num_sequences: usize,
+    pub max_length: usize,
+    pub min_length: usize,
+    pub mean_length: f64,
+    /// 序列条数为偶数时取中间两条的平均值
+    pub median_length: f64,
+    pub n50: usize,
+    pub n90: usize,
+    /// 从长到短累加到 N50 时用到的序列条数
+    pub l50: usize,
+    /// 从长到短累加到 N90 时用到的序列条数
+    pub l90: usize,
+}
+
+/// 计算集合的组装统计量，空集合的各项均为 0
+pub fn assembly_stats(seqs: &SequenceSet) -> AssemblyStats {
+    let lengths = seqs.lengths_descending();
+    let num_sequences = lengths.len();
+    let total_length: usize = lengths.iter().sum();
+    let (n50, l50) = nx(&lengths, 50);
+    let (n90, l90) = nx(&lengths, 90);
+    let (mean_length, median_length) = match num_sequences {
+        0 => (0.0, 0.0),
+        _ if num_sequences % 2 == 1 => (
+            total_length as f64 / num_sequences as f64,
+            lengths[num_sequences / 2] as f64,
+        ),
+        _ => (
+            total_length as f64 / num_sequences as f64,
+            (lengths[num_sequences / 2 - 1] + lengths[num_sequences / 2]) as f64 / 2.0,
+        ),
+    };
+    AssemblyStats {
+        total_length,
+        num_sequences,
+        max_length: lengths.first().copied().unwrap_or(0),
+        min_length: lengths.last().copied().unwrap_or(0),
+        mean_length,
+        median_length,
+        n50,
+        n90,
+        l50,
+        l90,
+    }
+}
+
+/// Nx 与 Lx：`lengths` 从长到短排列，累计长度达到总长度的 `percent`% 时的序列长度与序列条数
+fn nx(lengths: &[usize], percent: usize) -> (usize, usize) {
+    let total: usize = lengths.iter().sum();
+    let mut cumulative = 0;
+    for (i, &len) in lengths.iter().enumerate() {
+        cumulative += len;
+        if cumulative * 100 >= total * percent {
+            return (len, i + 1);
+        }
+    }
+    (0, 0)
+}
+
+/// 按一致度去除冗余序列，一致度为 1 减去以较长序列长度归一化的编辑距离 (不区分大小写)
+/// 按长度从长到短依次处理，与已有代表序列的一致度不低于 `identity_threshold` 时归入该代表所在的簇，
+/// 否则成为新的代表；只有类型相同的序列才会归为一簇，每个簇只保留最长 (长度相同时为最先出现) 的序列，
+/// 保留的序列维持原有顺序
+/// `identity_threshold` 不小于 1.0 时按序列内容做哈希去重，时间复杂度为 O(n)；
+/// 否则逐对计算编辑距离，距离超过阈值时提前结束
+/// 返回含有重复的簇，每个簇中第一个 id 为保留的代表序列
+pub fn deduplicate(seqs: &mut SequenceSet, identity_threshold: f64) -> Vec<Vec<String>> {
+    let records = std::mem::take(&mut seqs.records);
+    let mut order: Vec<usize> = (0..records.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(records[i].sequence.len()));
+
+    // 每个簇保存记录的下标，第一个为代表序列
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    if identity_threshold >= 1.0 {
+        let mut by_content: HashMap<(BioType, String), usize> = HashMap::new();
+        for i in order {
+            let sequence = &records[i].sequence;
+            let key = (sequence.biotype.clone(), sequence.seq.to_ascii_uppercase());
+            match by_content.get(&key) {
+                Some(&cluster) => clusters[cluster].push(i),
+                None => {
+                    by_content.insert(key, clusters.len());
+                    clusters.push(vec![i]);
+                }
+            }
+        }
+    } else {
+        for i in order {
+            let sequence = &records[i].sequence;
+            let found = clusters.iter().position(|cluster| {
+                let representative = &records[cluster[0]].sequence;
+                if representative.biotype != sequence.biotype {
+                    return false;
+                }
+                let len = representative.len().max(sequence.len());
+                let max_distance = ((1.0 - identity_threshold) * len as f64 + 1e-9).floor();
+                levenshtein_within(&representative.seq, &sequence.seq, max_distance as usize)
+                    .is_some()
+            });
+            match found {
+                Some(cluster) => clusters[cluster].push(i),
+                None => clusters.push(vec![i]),
+            }
+        }
+    }
+
+    let duplicates: Vec<Vec<String>> = clusters
+        .iter()
+        .filter(|cluster| cluster.len() > 1)
+        .map(|cluster| cluster.iter().map(|&i| records[i].id.clone()).collect())
+        .collect();
+    let representatives: HashSet<usize> = clusters.iter().map(|cluster| cluster[0]).collect();
+    seqs.records = records
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| representatives.contains(i))
+        .map(|(_, record)| record)
+        .collect();
+    duplicates
+}
+
+impl From<Vec<NamedSequence>> for SequenceSet {
+    fn from(records: Vec<NamedSequence>) -> Self {
+        SequenceSet { records }
+    }
+}
+
+impl From<SequenceSet> for Vec<NamedSequence> {
+    fn from(set: SequenceSet) -> Self {
+        set.records
+    }
+}
+
+impl FromIterator<NamedSequence> for SequenceSet {
+    fn from_iter<T: IntoIterator<Item = NamedSequence>>(iter: T) -> Self {
+        SequenceSet {
+            records: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for SequenceSet {
+    type Item = NamedSequence;
+    type IntoIter = std::vec::IntoIter<NamedSequence>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+/// 可以像切片一样遍历记录
+impl Deref for SequenceSet {
+    type Target = [NamedSequence];
+
+    fn deref(&self) -> &Self::Target {
+        &self.records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, seq: &str) -> NamedSequence {
+        NamedSequence::new(
+            id.to_string(),
+            String::new(),
+            Sequence::new(BioType::Dna, seq.to_string()),
+        )
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut set = SequenceSet::new();
+        for (id, len) in [("a", 2), ("b", 3), ("c", 4), ("d", 5), ("e", 6)] {
+            set.add(record(id, &"A".repeat(len)));
+        }
+        set.add(record("f", "AA"));
+        assert_eq!(set.len(), 6);
+        assert_eq!(set.total_length(), 22);
+        // 6 + 5 = 11 达到总长度 22 的一半
+        assert_eq!(set.n50(), 5);
+        assert_eq!(set.get_by_id("c").unwrap().sequence.len(), 4);
+        assert!(set.get_by_id("x").is_none());
+
+        let long = set.filter(|record| record.sequence.len() > 3);
+        assert_eq!(long.len(), 3);
+
+        set.deduplicate();
+        assert_eq!(set.len(), 5);
+        set.sort_by_length();
+        let ids: Vec<&str> = set.iter().map(|record| record.id.as_str()).collect();
+        assert_eq!(ids, vec!["e", "d", "c", "b", "a"]);
+        assert_eq!(SequenceSet::new().n50(), 0);
+    }
+
+    #[test]
+    fn identity_deduplication() {
+        let records = vec![
+            record("a", "ACGTACGTAC"),
+            record("b", "acgtacgtac"),
+            record("c", "ACGTACGTACGT"),
+            record("d", "ACGTACCTAC"),
+            record("e", "TTTTTTTTTT"),
+        ];
+        let mut exact = SequenceSet::from(records.clone());
+        let clusters = deduplicate(&mut exact, 1.0);
+        assert_eq!(clusters, vec![vec![String::from("a"), String::from("b")]]);
+        let ids: Vec<&str> = exact.iter().map(|record| record.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d", "e"]);
+
+        // a、b 与 c 相差两个碱基 (一致度 10/12)，d 与 c 相差三个碱基 (一致度 0.75)
+        let mut similar = SequenceSet::from(records.clone());
+        let clusters = deduplicate(&mut similar, 0.8);
+        assert_eq!(
+            clusters,
+            vec![vec![
+                String::from("c"),
+                String::from("a"),
+                String::from("b")
+            ]]
+        );
+        let ids: Vec<&str> = similar.iter().map(|record| record.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "d", "e"]);
+
+        let mut loose = SequenceSet::from(records);
+        let clusters = deduplicate(&mut loose, 0.75);
+        assert_eq!(clusters[0].len(), 4);
+        assert_eq!(loose.len(), 2);
+    }
+
+    #[test]
+    fn assembly_statistics() {
+        let set: SequenceSet = [("a", 2), ("b", 3), ("c", 4), ("d", 5), ("e", 6), ("f", 10)]
+            .iter()
+            .map(|&(id, len)| record(id, &"A".repeat(len)))
+            .collect();
+        let stats = assembly_stats(&set);
+        assert_eq!(stats.total_length, 30);
+        assert_eq!(stats.num_sequences, 6);
+        assert_eq!((stats.max_length, stats.min_length), (10, 2));
+        assert_eq!(stats.mean_length, 5.0);
+        assert_eq!(stats.median_length, 4.5);
+        // 10 + 6 = 16 达到 15；10 + 6 + 5 + 4 + 3 = 28 达到 27
+        assert_eq!((stats.n50, stats.l50), (6, 2));
+        assert_eq!((stats.n90, stats.l90), (3, 5));
+        assert_eq!(stats.n50, set.n50());
+
+        let empty = assembly_stats(&SequenceSet::new());
+        assert_eq!((empty.n50, empty.l90, empty.max_length), (0, 0, 0));
+        assert_eq!(empty.median_length, 0.0);
+    }
+}
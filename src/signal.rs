@@ -0,0 +1,118 @@
+use super::aminoacid::properties;
+use super::sequence::{BioType, Sequence};
+
+// 信号肽的简化预测，规则参考 SignalP 3.0 对信号肽三个区域的描述 (Bendtsen et al. 2004)
+// n 区含有带正电的残基，h 区为疏水核心，c 区末端的切割位点满足 von Heijne 的 (-3, -1) 规则
+
+/// 只在前 30 个残基中查找信号肽
+const SEARCH_LENGTH: usize = 30;
+/// n 区的最大长度 (包括起始的 Met)
+const MAX_N_REGION: usize = 10;
+/// n 区中带正电残基 (K、R) 个数的范围
+const N_REGION_CHARGES: (usize, usize) = (1, 5);
+/// h 区长度的范围
+const H_REGION_LENGTH: (usize, usize) = (7, 15);
+/// h 区 Kyte-Doolittle 疏水性指数平均值的下限
+const H_REGION_MIN_KD: f64 = 1.7;
+/// c 区长度的范围
+const C_REGION_LENGTH: (usize, usize) = (3, 7);
+/// 切割位点 -1 与 -3 位置允许的小型中性残基
+const SMALL_NEUTRAL: [char; 5] = ['A', 'G', 'S', 'C', 'T'];
+
+impl Sequence {
+    /// 是否有 N 端信号肽，规则见 `signal_peptide_cleavage_site`
+    pub fn has_signal_peptide(&self) -> Result<bool, String> {
+        Ok(self.signal_peptide_cleavage_site()?.is_some())
+    }
+
+    /// 预测信号肽的切割位点，返回成熟肽第一个残基的位置 (以 0 开始)，没有信号肽时返回 None
+    /// 在前 30 个残基中依次查找：
+    /// - n 区：长度不超过 10，含有 1 ~ 5 个 K 或 R
+    /// - h 区：紧接 n 区的 7 ~ 15 个残基，Kyte-Doolittle 平均值大于 1.7，取满足条件的最长区段
+    /// - c 区：紧接 h 区的 3 ~ 7 个残基，切割位点前第 1 与第 3 个残基为 A、G、S、C 或 T
+    ///
+    /// 从最靠前的 h 区开始，返回第一个满足条件的切割位点；非蛋白质序列或前 30 个残基中含有非标准氨基酸时返回错误
+    pub fn signal_peptide_cleavage_site(&self) -> Result<Option<usize>, String> {
+        if self.biotype != BioType::Protein {
+            return Err(format!("只能预测 {} 序列的信号肽", BioType::Protein));
+        }
+        let residues: Vec<char> = self
+            .seq
+            .chars()
+            .take(SEARCH_LENGTH)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let scores = residues
+            .iter()
+            .map(|&aa| properties(aa).map(|p| p.hydrophobicity_kd))
+            .collect::<Result<Vec<f64>, String>>()?;
+        let n = residues.len();
+
+        for h_start in 1..=MAX_N_REGION.min(n) {
+            let charges = residues[..h_start]
+                .iter()
+                .filter(|&&aa| aa == 'K' || aa == 'R')
+                .count();
+            if charges < N_REGION_CHARGES.0 || charges > N_REGION_CHARGES.1 {
+                continue;
+            }
+            let h_end = (H_REGION_LENGTH.0..=H_REGION_LENGTH.1)
+                .rev()
+                .map(|len| h_start + len)
+                .filter(|&end| end <= n)
+                .find(|&end| {
+                    scores[h_start..end].iter().sum::<f64>() / (end - h_start) as f64
+                        > H_REGION_MIN_KD
+                });
+            let h_end = match h_end {
+                Some(h_end) => h_end,
+                None => continue,
+            };
+            let site = (h_end + C_REGION_LENGTH.0..=h_end + C_REGION_LENGTH.1)
+                .filter(|&site| site <= n)
+                .find(|&site| {
+                    SMALL_NEUTRAL.contains(&residues[site - 1])
+                        && SMALL_NEUTRAL.contains(&residues[site - 3])
+                });
+            if site.is_some() {
+                return Ok(site);
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signal_peptide_prediction() {
+        // 人前胰岛素原，信号肽为前 24 个残基
+        let insulin = Sequence::new(
+            BioType::Protein,
+            String::from("MALWMRLLPLLALLALWGPDPAAAFVNQHLCGSHLVEALYLVCGERGFFYTPKT"),
+        );
+        assert_eq!(insulin.signal_peptide_cleavage_site().unwrap(), Some(24));
+        assert!(insulin.has_signal_peptide().unwrap());
+
+        // 胞质蛋白 (人泛素) 没有疏水的 h 区
+        let ubiquitin = Sequence::new(
+            BioType::Protein,
+            String::from(
+                "MQIFVKTLTGKTITLEVEPSDTIENVKAKIQDKEGIPPDQQRLIFAGKQLEDGRTLSDYNIQKESTLHLVLRLRGG",
+            ),
+        );
+        assert!(!ubiquitin.has_signal_peptide().unwrap());
+        // 没有带正电的 n 区
+        let uncharged = Sequence::new(BioType::Protein, format!("MS{}AGAAFQ", "L".repeat(12)));
+        assert!(!uncharged.has_signal_peptide().unwrap());
+        assert!(Sequence::new(BioType::Protein, String::from("MK"))
+            .signal_peptide_cleavage_site()
+            .unwrap()
+            .is_none());
+        assert!(Sequence::new(BioType::Dna, String::from("ATG"))
+            .has_signal_peptide()
+            .is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use super::sequence::{BioType, Sequence, Strand};
+
+// 剪接位点的共有序列打分，用于判断反向剪接 (back-splicing) 两端是否为常规剪接位点
+// 位置权重矩阵中各碱基的频率参考 Shapiro & Senapathy (1987) 对人类剪接位点的统计
+// 得分为各位置 log2(频率 / 0.25) 之和，越高越接近共有序列
+// 另外提供按外显子坐标拼接编码序列 (CDS) 的函数
+
+/// 5' 剪接位点 (供体) 的 9 个位置：外显子末端 3 个碱基与内含子开头 6 个碱基 (MAG|GURAGU)
+/// 每一行为 A、C、G、T 的频率
+#[rustfmt::skip]
+const DONOR_PWM: [[f64; 4]; 9] = [
+    [0.33, 0.37, 0.18, 0.12],
+    [0.60, 0.13, 0.14, 0.13],
+    [0.08, 0.04, 0.80, 0.08],
+    [0.00, 0.00, 1.00, 0.00],
+    [0.00, 0.00, 0.00, 1.00],
+    [0.60, 0.02, 0.36, 0.02],
+    [0.71, 0.08, 0.12, 0.09],
+    [0.06, 0.05, 0.84, 0.05],
+    [0.15, 0.16, 0.21, 0.48],
+];
+
+/// 供体窗口中外显子碱基的个数
+const DONOR_EXON_LENGTH: usize = 3;
+
+/// 3' 剪接位点 (受体) 的 15 个位置：内含子末端 14 个碱基 (多聚嘧啶区、YAG) 与外显子的第一个碱基
+#[rustfmt::skip]
+const ACCEPTOR_PWM: [[f64; 4]; 15] = [
+    [0.10, 0.31, 0.11, 0.48],
+    [0.09, 0.33, 0.10, 0.48],
+    [0.09, 0.33, 0.09, 0.49],
+    [0.08, 0.34, 0.08, 0.50],
+    [0.09, 0.33, 0.08, 0.50],
+    [0.09, 0.34, 0.08, 0.49],
+    [0.09, 0.36, 0.07, 0.48],
+    [0.09, 0.38, 0.07, 0.46],
+    [0.08, 0.37, 0.06, 0.49],
+    [0.10, 0.32, 0.08, 0.50],
+    [0.23, 0.31, 0.23, 0.23],
+    [0.04, 0.65, 0.01, 0.30],
+    [1.00, 0.00, 0.00, 0.00],
+    [0.00, 0.00, 1.00, 0.00],
+    [0.25, 0.14, 0.52, 0.09],
+];
+
+/// 受体窗口中内含子碱基的个数
+const ACCEPTOR_INTRON_LENGTH: usize = 14;
+
+/// 频率为 0 的碱基按该频率计算，避免得分为负无穷
+const MIN_FREQUENCY: f64 = 0.001;
+
+/// 内含子两端二核苷酸的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntronType {
+    /// GT-AG，由主要剪接体 (U2) 剪接
+    U2,
+    /// AT-AC，由次要剪接体 (U12) 剪接，打分时两端二核苷酸以 AT 与 AC 为共有序列
+    U12,
+    /// 其他二核苷酸
+    NonCanonical,
+}
+
+/// 内含子两端剪接位点的得分
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpliceSiteScore {
+    pub donor_score: f64,
+    pub acceptor_score: f64,
+    pub intron_type: IntronType,
+}
+
+impl SpliceSiteScore {
+    /// 两端是否为 GT-AG 或 AT-AC
+    pub fn is_canonical(&self) -> bool {
+        self.intron_type != IntronType::NonCanonical
+    }
+}
+
+impl Sequence {
+    /// 用位置权重矩阵为内含子 `intron_start..intron_end` (以 0 开始的左闭右开区间) 两端的剪接位点打分
+    /// 供体窗口为外显子末端 3 个碱基与内含子开头 6 个碱基，受体窗口为内含子末端 14 个碱基与下游外显子的第一个碱基
+    /// 不区分大小写，T 与 U 视为相同；ACGTU 以外的字符以及超出线状序列两端的位置得分为 0，
+    /// 环状序列的窗口可以跨过起点
+    /// 区间起点大于终点或终点超出序列长度时会 panic
+    pub fn score_splice_sites(&self, intron_start: usize, intron_end: usize) -> SpliceSiteScore {
+        let chars: Vec<char> = self.seq.chars().collect();
+        if intron_start > intron_end {
+            panic!("区间起点 {} 大于终点 {}", intron_start, intron_end);
+        }
+        if intron_end > chars.len() {
+            panic!("区间终点 {} 超出序列长度 {}", intron_end, chars.len());
+        }
+        let base_at = |position: isize| -> Option<usize> {
+            let n = chars.len() as isize;
+            let position = if self.is_circular() && n > 0 {
+                position.rem_euclid(n)
+            } else if position < 0 || position >= n {
+                return None;
+            } else {
+                position
+            };
+            match chars[position as usize].to_ascii_uppercase() {
+                'A' => Some(0),
+                'C' => Some(1),
+                'G' => Some(2),
+                'T' | 'U' => Some(3),
+                _ => None,
+            }
+        };
+        let dinucleotide = |start: isize| [base_at(start), base_at(start + 1)];
+        let (start, end) = (intron_start as isize, intron_end as isize);
+        // 碱基下标 0 ~ 3 依次为 A、C、G、T
+        let intron_type = match (dinucleotide(start), dinucleotide(end - 2)) {
+            ([Some(2), Some(3)], [Some(0), Some(2)]) => IntronType::U2,
+            ([Some(0), Some(3)], [Some(0), Some(1)]) => IntronType::U12,
+            _ => IntronType::NonCanonical,
+        };
+
+        let mut donor = DONOR_PWM;
+        let mut acceptor = ACCEPTOR_PWM;
+        if intron_type == IntronType::U12 {
+            donor[DONOR_EXON_LENGTH] = [1.0, 0.0, 0.0, 0.0];
+            acceptor[ACCEPTOR_INTRON_LENGTH - 1] = [0.0, 1.0, 0.0, 0.0];
+        }
+        let score = |pwm: &[[f64; 4]], offset: isize| -> f64 {
+            pwm.iter()
+                .enumerate()
+                .filter_map(|(i, row)| base_at(offset + i as isize).map(|base| row[base]))
+                .map(|frequency| (frequency.max(MIN_FREQUENCY) / 0.25).log2())
+                .sum()
+        };
+        SpliceSiteScore {
+            donor_score: score(&donor, start - DONOR_EXON_LENGTH as isize),
+            acceptor_score: score(&acceptor, end - ACCEPTOR_INTRON_LENGTH as isize),
+            intron_type,
+        }
+    }
+}
+
+/// 按外显子坐标拼接编码序列
+/// `exon_coords` 为以 0 开始的左闭右开区间，不要求按顺序给出，会先按起点排序；
+/// `Strand::Forward` 时按坐标升序拼接，`Strand::Reverse` 时按坐标降序拼接各外显子的反向互补序列
+/// 拼接后从 5' 端去掉 `phase` (0、1 或 2，即 GFF3 的 phase 列，GenBank 的 codon_start 减 1) 个碱基，
+/// 使结果从第一个完整的密码子开始，可以直接用于 `translate`
+/// 蛋白质序列、`Strand::Both`、phase 大于 2、没有外显子、外显子为空、超出序列范围或相互重叠时返回错误
+pub fn extract_cds(
+    seq: &Sequence,
+    exon_coords: &[(usize, usize)],
+    strand: Strand,
+    phase: u8,
+) -> Result<Sequence, String> {
+    if seq.biotype == BioType::Protein {
+        return Err(format!("不能从 {} 序列中提取编码序列", seq.biotype));
+    }
+    if strand == Strand::Both {
+        return Err(String::from("编码序列只能位于一条链上"));
+    }
+    if phase > 2 {
+        return Err(format!("phase 只能是 0、1 或 2，而不是 {}", phase));
+    }
+    if exon_coords.is_empty() {
+        return Err(String::from("没有外显子"));
+    }
+    let mut exons = exon_coords.to_vec();
+    exons.sort_unstable();
+    for &(start, end) in &exons {
+        if start >= end || end > seq.len() {
+            return Err(format!(
+                "外显子 {}..{} 为空或超出序列长度 {}",
+                start,
+                end,
+                seq.len()
+            ));
+        }
+    }
+    if let Some(pair) = exons.windows(2).find(|pair| pair[0].1 > pair[1].0) {
+        return Err(format!(
+            "外显子 {}..{} 与 {}..{} 重叠",
+            pair[0].0, pair[0].1, pair[1].0, pair[1].1
+        ));
+    }
+
+    let mut spliced = Sequence::new(seq.biotype.clone(), String::new());
+    for &(start, end) in &exons {
+        spliced.append(&seq[start..end]);
+    }
+    if strand == Strand::Reverse {
+        spliced = spliced.reverse_complementary()?;
+    }
+    let phase = (phase as usize).min(spliced.len());
+    Ok(spliced.subsequence(phase..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    #[test]
+    fn splice_site_scores() {
+        // 外显子 CAG | 内含子 GTAAGT ... TTTCCCTTCTTCAG | 外显子 G
+        let intron = format!("GTAAGT{}TTTCCCTTCTTCAG", "A".repeat(20));
+        let dna = Sequence::new(BioType::Dna, format!("ACGCAG{}GCC", intron));
+        let score = dna.score_splice_sites(6, 6 + intron.len());
+        assert_eq!(score.intron_type, IntronType::U2);
+        assert!(score.is_canonical());
+        assert!(score.donor_score > 8.0);
+        assert!(score.acceptor_score > 8.0);
+
+        // 供体 GT 改为 GC 后为非经典内含子，得分明显下降
+        let mutated = Sequence::new(BioType::Dna, dna.seq.replacen("CAGGTAAGT", "CAGGCAAGT", 1));
+        let weak = mutated.score_splice_sites(6, 6 + intron.len());
+        assert_eq!(weak.intron_type, IntronType::NonCanonical);
+        assert!(weak.donor_score < score.donor_score - 5.0);
+        assert_eq!(weak.acceptor_score, score.acceptor_score);
+
+        // AT-AC 内含子
+        let u12 = Sequence::new(
+            BioType::Rna,
+            format!("CAGAUAUCCU{}UCCUUAAC", "A".repeat(20)) + "GCC",
+        );
+        let score = u12.score_splice_sites(3, u12.len() - 3);
+        assert_eq!(score.intron_type, IntronType::U12);
+        assert!(score.donor_score > 0.0);
+    }
+
+    #[test]
+    fn extract_spliced_cds() {
+        use crate::genbank;
+        use std::io::Cursor;
+
+        // 负链上的两个外显子，codon_start=2
+        let record = "\
+LOCUS       TEST0002                  34 bp    DNA     linear   SYN 01-JAN-2024
+FEATURES             Location/Qualifiers
+     CDS             complement(join(5..13,24..30))
+                     /codon_start=2
+                     /translation=\"MKWV\"
+ORIGIN
+        1 ggggttaaac ccaccccccc ccctttcatg gggg
+//
+";
+        let record = &genbank::parse(Cursor::new(record)).unwrap()[0];
+        let cds = &record.features[0];
+        let phase = cds.qualifier("codon_start").unwrap().parse::<u8>().unwrap() - 1;
+        let extracted = extract_cds(
+            &record.sequence,
+            &[(4, 13), (23, 30)],
+            cds.location.strand(),
+            phase,
+        )
+        .unwrap();
+        assert_eq!(extracted.seq, "atgaaatgggtttaa");
+        assert_eq!(
+            extracted.translate().unwrap().seq.to_ascii_uppercase(),
+            cds.qualifier("translation").unwrap()
+        );
+
+        // 正链，外显子不按顺序给出
+        let dna = Sequence::new(BioType::Dna, String::from("CCAATGAAGTAAGTTTCAGAGTGTAA"));
+        let forward = extract_cds(&dna, &[(19, 26), (2, 8)], Strand::Forward, 1).unwrap();
+        assert_eq!(forward.seq, "ATGAAAGTGTAA");
+        assert_eq!(forward.translate().unwrap().seq, "MKV");
+
+        assert!(extract_cds(&dna, &[(2, 8)], Strand::Both, 0).is_err());
+        assert!(extract_cds(&dna, &[(2, 8)], Strand::Forward, 3).is_err());
+        assert!(extract_cds(&dna, &[(2, 30)], Strand::Forward, 0).is_err());
+        assert!(extract_cds(&dna, &[(2, 8), (6, 10)], Strand::Forward, 0).is_err());
+        assert!(extract_cds(&dna, &[], Strand::Forward, 0).is_err());
+    }
+
+    #[test]
+    fn extract_insulin_cds() {
+        use crate::genbank;
+        use std::io::Cursor;
+
+        // 编码区与 /translation 取自人类胰岛素 mRNA NM_000207 的 CDS (前胰岛素原 P01308，110 aa)，
+        // 放在负链上并在 C 肽编码区的密码子中间插入一段内含子，两侧序列为占位序列
+        let record = "\
+LOCUS       INS_EXCERPT              394 bp    DNA     linear   PRI 01-JAN-2024
+FEATURES             Location/Qualifiers
+     CDS             complement(join(10..155,199..385))
+                     /gene=\"INS\"
+                     /codon_start=1
+                     /translation=\"MALWMRLLPLLALLALWGPDPAAAFVNQHLCGSHLVEALYLVCG
+                     ERGFFYTPKTRREAEDLQVGQVELGGGPGAGSLQPLALEGSLQKRGIVEQCCTSICSL
+                     YQLENYCN\"
+ORIGIN
+        1 gggtcgtgcc tagttgcagt agttctccag ctggtagagg gagcagatgc tggtacagca
+       61 ttgttccaca atgccacgct tctgcaggga cccctccagg gccaagggct gcaggctgcc
+      121 tgcaccaggg cccccgccca gctccacctg ccccactgaa aagaaatttt tttttttttt
+      181 ttttttttgg ggacttaccc tgcaggtcct ctgcctcccg gcgggtcttg ggtgtgtaga
+      241 agaagcctcg ttccccgcac actaggtaga gagcttccac caggtgtgag ccgcacaggt
+      301 gttggttcac aaaggctgcg gctgggtcag gtccccagag ggccagcagc gccagcaggg
+      361 gcaggaggcg catccacagg gccatggcag aagg
+//
+";
+        let record = &genbank::parse(Cursor::new(record)).unwrap()[0];
+        let cds = &record.features[0];
+        let phase = cds.qualifier("codon_start").unwrap().parse::<u8>().unwrap() - 1;
+        let extracted = extract_cds(
+            &record.sequence,
+            &[(9, 155), (198, 385)],
+            cds.location.strand(),
+            phase,
+        )
+        .unwrap();
+        assert_eq!(extracted.len(), 333);
+        assert!(extracted.seq.starts_with("atggccctgtggatg"));
+        assert_eq!(extracted, cds.location.extract(&record.sequence).unwrap());
+        assert_eq!(
+            extracted.translate().unwrap().seq.to_ascii_uppercase(),
+            cds.qualifier("translation").unwrap()
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use super::sequence::{BioType, Sequence};
+
+use std::{error, fmt};
+
+// RNA 二级结构的点括号 (dot-bracket) 表示法，与 RNAfold 等 ViennaRNA 工具的输出一致
+// `.` 为未配对碱基，`()` 为普通碱基对，`[]` 与 `{}` 用于表示假结中交叉的碱基对
+
+/// 每一种括号的左右字符，下标即括号的层级
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// 解析点括号字符串时可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructureError {
+    /// 结构字符串与序列长度不一致
+    LengthMismatch { sequence: usize, structure: usize },
+    /// 序列不是核酸
+    InvalidSequence,
+    /// 无法识别的字符，位置以 0 开始
+    InvalidCharacter { position: usize, found: char },
+    /// 没有对应右括号的左括号
+    UnmatchedOpen { position: usize },
+    /// 没有对应左括号的右括号
+    UnmatchedClose { position: usize },
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureError::LengthMismatch {
+                sequence,
+                structure,
+            } => write!(f, "结构长度 {} 与序列长度 {} 不一致", structure, sequence),
+            StructureError::InvalidSequence => write!(f, "二级结构只适用于核酸序列"),
+            StructureError::InvalidCharacter { position, found } => {
+                write!(f, "位置 {}: 无法识别的字符 '{}'", position, found)
+            }
+            StructureError::UnmatchedOpen { position } => {
+                write!(f, "位置 {}: 左括号没有配对", position)
+            }
+            StructureError::UnmatchedClose { position } => {
+                write!(f, "位置 {}: 右括号没有配对", position)
+            }
+        }
+    }
+}
+
+impl error::Error for StructureError {}
+
+/// 结构中的一个位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureChar {
+    /// 未配对
+    Unpaired,
+    /// 碱基对的 5' 端，保存配对碱基的位置
+    PairOpen(usize),
+    /// 碱基对的 3' 端，保存配对碱基的位置
+    PairClose(usize),
+}
+
+/// 发夹结构：连续堆积的茎区以及末端完全未配对的环区
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StemLoop {
+    /// 茎区的碱基对，从外向内排列
+    pub stem: Vec<(usize, usize)>,
+    /// 环区的起点
+    pub loop_start: usize,
+    /// 环区的终点 (不含)
+    pub loop_end: usize,
+}
+
+impl StemLoop {
+    /// 环区的长度
+    pub fn loop_len(&self) -> usize {
+        self.loop_end - self.loop_start
+    }
+}
+
+/// 带有二级结构的 RNA 序列
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecondaryStructure {
+    pub sequence: Sequence,
+    pub structure: Vec<StructureChar>,
+    /// 每个位置所用括号的层级，输出时用于还原括号类型
+    levels: Vec<usize>,
+}
+
+impl SecondaryStructure {
+    /// 从点括号字符串解析二级结构，结构字符串的长度必须与序列相同
+    pub fn from_dot_bracket(
+        seq: &Sequence,
+        notation: &str,
+    ) -> Result<SecondaryStructure, StructureError> {
+        if seq.biotype == BioType::Protein {
+            return Err(StructureError::InvalidSequence);
+        }
+        let notation: Vec<char> = notation.chars().collect();
+        if notation.len() != seq.len() {
+            return Err(StructureError::LengthMismatch {
+                sequence: seq.len(),
+                structure: notation.len(),
+            });
+        }
+        let mut structure = vec![StructureChar::Unpaired; notation.len()];
+        let mut levels = vec![0; notation.len()];
+        let mut stacks: [Vec<usize>; BRACKETS.len()] = Default::default();
+        for (position, &c) in notation.iter().enumerate() {
+            if c == '.' {
+                continue;
+            }
+            if let Some(level) = BRACKETS.iter().position(|&(open, _)| open == c) {
+                stacks[level].push(position);
+                levels[position] = level;
+            } else if let Some(level) = BRACKETS.iter().position(|&(_, close)| close == c) {
+                let open = stacks[level]
+                    .pop()
+                    .ok_or(StructureError::UnmatchedClose { position })?;
+                structure[open] = StructureChar::PairOpen(position);
+                structure[position] = StructureChar::PairClose(open);
+                levels[position] = level;
+            } else {
+                return Err(StructureError::InvalidCharacter { position, found: c });
+            }
+        }
+        if let Some(&position) = stacks.iter().filter_map(|stack| stack.first()).min() {
+            return Err(StructureError::UnmatchedOpen { position });
+        }
+        Ok(SecondaryStructure {
+            sequence: seq.clone(),
+            structure,
+            levels,
+        })
+    }
+
+    /// 输出为点括号字符串，括号类型与解析时相同
+    pub fn to_dot_bracket(&self) -> String {
+        self.structure
+            .iter()
+            .zip(self.levels.iter())
+            .map(|(c, &level)| match c {
+                StructureChar::Unpaired => '.',
+                StructureChar::PairOpen(_) => BRACKETS[level].0,
+                StructureChar::PairClose(_) => BRACKETS[level].1,
+            })
+            .collect()
+    }
+
+    /// 返回全部碱基对 (5' 端位置, 3' 端位置)，按 5' 端排序
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        self.structure
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match c {
+                StructureChar::PairOpen(j) => Some((i, *j)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 找出所有发夹结构
+    /// 环区内的碱基都不配对 (包括假结)，茎区从闭合碱基对向外延伸到不再连续堆积为止
+    pub fn stem_loops(&self) -> Vec<StemLoop> {
+        let mut loops = Vec::new();
+        for (i, j) in self.pairs() {
+            if !self.structure[i + 1..j]
+                .iter()
+                .all(|c| *c == StructureChar::Unpaired)
+            {
+                continue;
+            }
+            let mut stem = vec![(i, j)];
+            let (mut i, mut j) = (i, j);
+            while i > 0 && self.structure[i - 1] == StructureChar::PairOpen(j + 1) {
+                i -= 1;
+                j += 1;
+                stem.push((i, j));
+            }
+            stem.reverse();
+            let (inner_i, inner_j) = stem[stem.len() - 1];
+            loops.push(StemLoop {
+                stem,
+                loop_start: inner_i + 1,
+                loop_end: inner_j,
+            });
+        }
+        loops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_bracket_round_trip() {
+        let seq = Sequence::new(BioType::Rna, String::from("GGGAAACCCAGGCAAAGCCUU"));
+        let notation = "(((...)))((([[..))).]]";
+        assert!(matches!(
+            SecondaryStructure::from_dot_bracket(&seq, notation),
+            Err(StructureError::LengthMismatch { .. })
+        ));
+        let notation = "(((...)))(([[..)).]].";
+        let structure = SecondaryStructure::from_dot_bracket(&seq, notation).unwrap();
+        assert_eq!(structure.to_dot_bracket(), notation);
+        assert_eq!(structure.structure[0], StructureChar::PairOpen(8));
+        assert_eq!(structure.structure[8], StructureChar::PairClose(0));
+        assert_eq!(structure.structure[11], StructureChar::PairOpen(19));
+        assert_eq!(structure.pairs().len(), 7);
+
+        let error = |notation: &str| {
+            let seq = Sequence::new(BioType::Rna, "A".repeat(notation.len()));
+            SecondaryStructure::from_dot_bracket(&seq, notation).unwrap_err()
+        };
+        assert_eq!(error("((.)"), StructureError::UnmatchedOpen { position: 0 });
+        assert_eq!(
+            error("(.))"),
+            StructureError::UnmatchedClose { position: 3 }
+        );
+        assert_eq!(
+            error("(.])"),
+            StructureError::UnmatchedClose { position: 2 }
+        );
+        assert_eq!(
+            error("(.x)"),
+            StructureError::InvalidCharacter {
+                position: 2,
+                found: 'x'
+            }
+        );
+    }
+
+    #[test]
+    fn hairpins() {
+        let seq = Sequence::new(BioType::Rna, String::from("GGGAAACCCAGGCAAAGCCUU"));
+        let structure =
+            SecondaryStructure::from_dot_bracket(&seq, "(((...)))(([[..)).]].").unwrap();
+        let loops = structure.stem_loops();
+        // 第二个发夹的环区中有假结，不算作发夹
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].stem, vec![(0, 8), (1, 7), (2, 6)]);
+        assert_eq!((loops[0].loop_start, loops[0].loop_end), (3, 6));
+        assert_eq!(loops[0].loop_len(), 3);
+
+        let seq = Sequence::new(BioType::Rna, "A".repeat(21));
+        let structure =
+            SecondaryStructure::from_dot_bracket(&seq, "((.((...)).((...)))).").unwrap();
+        let loops = structure.stem_loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].stem, vec![(3, 9), (4, 8)]);
+        assert_eq!(loops[1].stem, vec![(11, 17), (12, 16)]);
+    }
+}
@@ -0,0 +1,277 @@
+use super::sequence::Sequence;
+
+use std::cmp::Ordering;
+
+// 后缀数组 (suffix array)：一次构建，多次查询子串
+// 构建使用 SA-IS 算法，时间复杂度 O(n)；所有比较都不区分大小写
+
+/// 后缀数组，`sa[i]` 为字典序第 i 小的后缀的起点
+/// 只保存下标，查询时需要传入构建时使用的序列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixArray {
+    pub sa: Vec<usize>,
+}
+
+impl SuffixArray {
+    /// 构建序列的后缀数组，字符按大写形式比较
+    pub fn build(seq: &Sequence) -> SuffixArray {
+        let (ranks, upper) = rank_chars(&uppercase_chars(seq));
+        SuffixArray {
+            sa: sa_is(&ranks, upper),
+        }
+    }
+
+    /// 返回后缀数组的长度，即序列长度
+    pub fn len(&self) -> usize {
+        self.sa.len()
+    }
+
+    /// 后缀数组是否为空
+    pub fn is_empty(&self) -> bool {
+        self.sa.is_empty()
+    }
+
+    /// 二分查找 pattern 的所有出现位置 (允许重叠)，按位置从小到大排序，时间复杂度 O(m log n)
+    /// `seq` 必须是构建后缀数组时使用的序列，空 pattern 返回空数组
+    pub fn search(&self, seq: &Sequence, pattern: &str) -> Vec<usize> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        let text = uppercase_chars(seq);
+        let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_uppercase()).collect();
+        // 只比较后缀的前 m 个字符，前缀等于 pattern 的后缀视为相等
+        let compare = |&start: &usize| {
+            let end = (start + pattern.len()).min(text.len());
+            text[start..end].cmp(&pattern[..])
+        };
+        let lower = self
+            .sa
+            .partition_point(|start| compare(start) == Ordering::Less);
+        let upper = self
+            .sa
+            .partition_point(|start| compare(start) != Ordering::Greater);
+        let mut positions = self.sa[lower..upper].to_vec();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// 用 Kasai 算法计算最长公共前缀数组
+    /// `lcp[i]` 为 `sa[i - 1]` 与 `sa[i]` 两个后缀的最长公共前缀长度，`lcp[0]` 为 0
+    pub fn lcp_array(&self, seq: &Sequence) -> Vec<usize> {
+        let text = uppercase_chars(seq);
+        let n = self.sa.len();
+        let mut rank = vec![0; n];
+        for (i, &start) in self.sa.iter().enumerate() {
+            rank[start] = i;
+        }
+        let mut lcp = vec![0; n];
+        let mut h = 0;
+        for i in 0..n {
+            if rank[i] == 0 {
+                h = 0;
+                continue;
+            }
+            let j = self.sa[rank[i] - 1];
+            while i + h < n && j + h < n && text[i + h] == text[j + h] {
+                h += 1;
+            }
+            lcp[rank[i]] = h;
+            h = h.saturating_sub(1);
+        }
+        lcp
+    }
+}
+
+fn uppercase_chars(seq: &Sequence) -> Vec<char> {
+    seq.seq.chars().map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// 把字符映射为保持顺序的紧凑编号 0..=upper
+pub(crate) fn rank_chars(chars: &[char]) -> (Vec<usize>, usize) {
+    let mut alphabet = chars.to_vec();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    let ranks = chars
+        .iter()
+        .map(|c| alphabet.binary_search(c).unwrap_or(0))
+        .collect();
+    (ranks, alphabet.len().saturating_sub(1))
+}
+
+/// SA-IS 算法，`s` 中的每个值都不超过 `upper`
+pub(crate) fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    const EMPTY: usize = usize::MAX;
+    let n = s.len();
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+    // ls[i] 为 true 表示后缀 i 为 S 型 (小于后缀 i + 1)
+    let mut ls = vec![false; n];
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] {
+            ls[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+    // 每个字符的桶中，L 型后缀在前、S 型后缀在后
+    let mut sum_l = vec![0; upper + 1];
+    let mut sum_s = vec![0; upper + 1];
+    for i in 0..n {
+        if !ls[i] {
+            sum_s[s[i]] += 1;
+        } else {
+            sum_l[s[i] + 1] += 1;
+        }
+    }
+    for i in 0..=upper {
+        sum_s[i] += sum_l[i];
+        if i < upper {
+            sum_l[i + 1] += sum_s[i];
+        }
+    }
+
+    // 由排好序的 LMS 后缀诱导排序全部后缀
+    let induce = |lms: &[usize], sa: &mut Vec<usize>| {
+        sa.clear();
+        sa.resize(n, EMPTY);
+        let mut buf = sum_s.clone();
+        for &d in lms {
+            sa[buf[s[d]]] = d;
+            buf[s[d]] += 1;
+        }
+        let mut buf = sum_l.clone();
+        sa[buf[s[n - 1]]] = n - 1;
+        buf[s[n - 1]] += 1;
+        for i in 0..n {
+            let v = sa[i];
+            if v != EMPTY && v >= 1 && !ls[v - 1] {
+                sa[buf[s[v - 1]]] = v - 1;
+                buf[s[v - 1]] += 1;
+            }
+        }
+        let mut buf = sum_l.clone();
+        for i in (0..n).rev() {
+            let v = sa[i];
+            if v != EMPTY && v >= 1 && ls[v - 1] {
+                buf[s[v - 1] + 1] -= 1;
+                sa[buf[s[v - 1] + 1]] = v - 1;
+            }
+        }
+    };
+
+    let mut lms_map = vec![EMPTY; n + 1];
+    let mut lms = Vec::new();
+    for i in 1..n {
+        if !ls[i - 1] && ls[i] {
+            lms_map[i] = lms.len();
+            lms.push(i);
+        }
+    }
+    let m = lms.len();
+    let mut sa = Vec::with_capacity(n);
+    induce(&lms, &mut sa);
+
+    if m > 0 {
+        let mut sorted_lms: Vec<usize> = sa
+            .iter()
+            .cloned()
+            .filter(|&v| lms_map[v] != EMPTY)
+            .collect();
+        // 给 LMS 子串编号，相同的子串编号相同，再递归排序
+        let mut rec_s = vec![0; m];
+        let mut rec_upper = 0;
+        for i in 1..m {
+            let mut l = sorted_lms[i - 1];
+            let mut r = sorted_lms[i];
+            let end_l = if lms_map[l] + 1 < m {
+                lms[lms_map[l] + 1]
+            } else {
+                n
+            };
+            let end_r = if lms_map[r] + 1 < m {
+                lms[lms_map[r] + 1]
+            } else {
+                n
+            };
+            let mut same = true;
+            if end_l - l != end_r - r {
+                same = false;
+            } else {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+                if l == n || s[l] != s[r] {
+                    same = false;
+                }
+            }
+            if !same {
+                rec_upper += 1;
+            }
+            rec_s[lms_map[sorted_lms[i]]] = rec_upper;
+        }
+        let rec_sa = sa_is(&rec_s, rec_upper);
+        for (sorted, &i) in sorted_lms.iter_mut().zip(rec_sa.iter()) {
+            *sorted = lms[i];
+        }
+        induce(&sorted_lms, &mut sa);
+    }
+    sa
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::BioType;
+
+    fn naive_suffix_array(text: &str) -> Vec<usize> {
+        let text = text.to_ascii_uppercase();
+        let mut sa: Vec<usize> = (0..text.len()).collect();
+        sa.sort_by_key(|&i| &text[i..]);
+        sa
+    }
+
+    #[test]
+    fn suffix_array_matches_naive() {
+        let banana = Sequence::new(BioType::Rna, String::from("banana"));
+        assert_eq!(SuffixArray::build(&banana).sa, vec![5, 3, 1, 0, 4, 2]);
+
+        // 用线性同余生成器产生的伪随机序列与朴素排序结果比较
+        let mut state: u64 = 12345;
+        for len in 0..200 {
+            let text: String = (0..len)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    ['A', 'C', 'G', 'T'][(state >> 33) as usize % if len % 2 == 0 { 2 } else { 4 }]
+                })
+                .collect();
+            let seq = Sequence::new(BioType::Dna, text.clone());
+            assert_eq!(
+                SuffixArray::build(&seq).sa,
+                naive_suffix_array(&text),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn suffix_array_search_and_lcp() {
+        let seq = Sequence::new(BioType::Dna, String::from("AcgTACGTacgA"));
+        let sa = SuffixArray::build(&seq);
+        assert_eq!(sa.search(&seq, "acg"), vec![0, 4, 8]);
+        assert_eq!(sa.search(&seq, "GTA"), vec![2, 6]);
+        assert_eq!(sa.search(&seq, "A"), vec![0, 4, 8, 11]);
+        assert!(sa.search(&seq, "TT").is_empty());
+        assert!(sa.search(&seq, "ACGAA").is_empty());
+        assert!(sa.search(&seq, "").is_empty());
+
+        let banana = Sequence::new(BioType::Rna, String::from("BANANA"));
+        let sa = SuffixArray::build(&banana);
+        assert_eq!(sa.lcp_array(&banana), vec![0, 1, 3, 0, 0, 2]);
+    }
+}
@@ -1,14 +1,14 @@
 use biocirc::sequence::BioType;
-use biocirc::FastaReader;
+use biocirc::{fasta, FastaReader};
 
 #[test]
 fn read_fasta() {
     let fastareader = FastaReader {
-        file: "tests\\data\\example.fasta".to_string(),
+        file: "tests/data/example.fasta".to_string(),
         biotype: BioType::Dna,
     };
     let sequences = fastareader.read().unwrap();
-    
+
     assert_eq!(sequences.len(), 3);
     println!("{:?}", sequences[0].sequence);
     println!("{:?}", sequences[0].id);
@@ -17,3 +17,32 @@ fn read_fasta() {
     println!("{:?}", sequences[1].id);
     println!("{:?}", sequences[1].description);
 }
+
+#[test]
+fn read_fasta_infer_biotype() {
+    let records = fasta::read("tests/data/example.fasta").unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].id, "seq1");
+    assert_eq!(records[0].description, "sequence1");
+    assert_eq!(
+        records[1].sequence.seq,
+        "GCTAGCTAGCTAGCTAGCTAGCTAGCTAGCTAAAAAA"
+    );
+    assert_eq!(records[2].sequence.biotype, BioType::Dna);
+}
+
+#[test]
+fn write_fasta_round_trip() {
+    let records = fasta::read("tests/data/example.fasta").unwrap();
+    let path = std::env::temp_dir().join("biocirc_write_fasta_round_trip.fasta");
+    fasta::write(&records, &path, 10).unwrap();
+    let written = fasta::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(written.len(), records.len());
+    for (left, right) in records.iter().zip(written.iter()) {
+        assert_eq!(left.id, right.id);
+        assert_eq!(left.description, right.description);
+        assert_eq!(left.sequence, right.sequence);
+    }
+}
@@ -0,0 +1,34 @@
+use biocirc::sequence::{BioType, Sequence};
+use biocirc::NamedSequence;
+
+#[test]
+fn json_round_trip() {
+    let sequence = Sequence::new(BioType::Rna, String::from("AUGC\"\\\n\tNru"));
+    let json = serde_json::to_string(&sequence).unwrap();
+    assert!(json.contains("\"biotype\":\"rna\""));
+    let decoded: Sequence = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.biotype, BioType::Rna);
+    assert_eq!(decoded.seq, sequence.seq);
+}
+
+#[test]
+fn json_biotype_case_insensitive() {
+    let decoded: Sequence = serde_json::from_str(r#"{"biotype":"DNA","seq":"ACGT"}"#).unwrap();
+    assert_eq!(decoded.biotype, BioType::Dna);
+    assert!(serde_json::from_str::<Sequence>(r#"{"biotype":"peptide","seq":"MK"}"#).is_err());
+}
+
+#[test]
+fn toml_round_trip() {
+    let record = NamedSequence::new(
+        String::from("seq1"),
+        String::from("with 'quotes' and \"escapes\""),
+        Sequence::new(BioType::Protein, String::from("MKV*")),
+    );
+    let text = toml::to_string(&record).unwrap();
+    let decoded: NamedSequence = toml::from_str(&text).unwrap();
+    assert_eq!(decoded.id, record.id);
+    assert_eq!(decoded.description, record.description);
+    assert_eq!(decoded.sequence.biotype, BioType::Protein);
+    assert_eq!(decoded.sequence.seq, "MKV*");
+}